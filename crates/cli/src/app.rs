@@ -1,6 +1,9 @@
 // https://github.com/clap-rs/clap/tree/master/examples/derive_ref#app-attributes
 
 use crate::commands::bin::BinArgs;
+use crate::commands::cache::{
+    CacheExportArgs, CacheImportArgs, CacheListArgs, CacheShowArgs, CacheVerifyArgs,
+};
 use crate::commands::check::CheckArgs;
 use crate::commands::ci::CiArgs;
 use crate::commands::clean::CleanArgs;
@@ -29,6 +32,39 @@ use std::path::PathBuf;
 
 pub const BIN_NAME: &str = if cfg!(windows) { "moon.exe" } else { "moon" };
 
+#[derive(Clone, Debug, Subcommand)]
+pub enum CacheCommands {
+    #[command(
+        name = "export",
+        about = "Export cached archives and hash manifests into a bundle."
+    )]
+    Export(CacheExportArgs),
+
+    #[command(
+        name = "import",
+        about = "Import cached archives and hash manifests from a bundle."
+    )]
+    Import(CacheImportArgs),
+
+    #[command(
+        name = "list",
+        about = "List the last cached hash of each target, with its archive size and age."
+    )]
+    List(CacheListArgs),
+
+    #[command(
+        name = "show",
+        about = "Display the hash manifest and archive contents of a cached hash."
+    )]
+    Show(CacheShowArgs),
+
+    #[command(
+        name = "verify",
+        about = "Find corrupt or orphaned archives and hash manifests."
+    )]
+    Verify(CacheVerifyArgs),
+}
+
 #[derive(Clone, Debug, Subcommand)]
 pub enum DockerCommands {
     #[command(
@@ -253,6 +289,16 @@ pub enum Commands {
 
     // OTHER
 
+    // moon cache <operation>
+    #[command(
+        name = "cache",
+        about = "Operations for inspecting and managing the local cache."
+    )]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
     // moon clean
     #[command(
         name = "clean",
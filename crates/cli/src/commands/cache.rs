@@ -0,0 +1,713 @@
+use clap::Args;
+use console::Term;
+use flate2::read::GzDecoder;
+use miette::{miette, IntoDiagnostic};
+use moon_cache::{format_size, StoreManifest};
+use moon_runner::{
+    get_store_object_path, verify_archive_digest, verify_store_object, RunTargetState,
+};
+use moon_terminal::{ExtendedTerm, Label};
+use moon_time::{elapsed, now_millis};
+use moon_workspace::Workspace;
+use serde::Serialize;
+use starbase::system;
+use starbase_archive::tar::{TarPacker, TarUnpacker};
+use starbase_archive::Archiver;
+use starbase_styles::color;
+use starbase_utils::{fs, json};
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::debug;
+
+fn get_hash_from_path(path: &Path, extension: &str) -> Option<String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(extension))
+        .map(|hash| hash.to_owned())
+}
+
+fn get_archive_size(workspace: &Workspace, hash: &str) -> Option<u64> {
+    fs::metadata(workspace.hash_engine.get_archive_path(hash))
+        .map(|meta| meta.len())
+        .ok()
+}
+
+fn load_store_manifest(workspace: &Workspace, hash: &str) -> Option<StoreManifest> {
+    json::read_file(workspace.hash_engine.get_store_manifest_path(hash)).ok()
+}
+
+fn get_store_objects(manifest: StoreManifest) -> BTreeSet<String> {
+    manifest
+        .files
+        .into_values()
+        .chain(manifest.stdout)
+        .chain(manifest.stderr)
+        .collect()
+}
+
+// Outputs stored for the link hydration mode have no archive,
+// so sum the size of the objects they reference instead
+fn get_store_size(workspace: &Workspace, hash: &str) -> Option<u64> {
+    let objects_dir = workspace.hash_engine.get_store_objects_dir();
+
+    load_store_manifest(workspace, hash).map(|manifest| {
+        get_store_objects(manifest)
+            .iter()
+            .filter_map(|digest| fs::metadata(get_store_object_path(&objects_dir, digest)).ok())
+            .map(|meta| meta.len())
+            .sum()
+    })
+}
+
+// Resolve a full hash from a partial hash, by checking the
+// archives, and hash and store manifests that currently exist.
+fn find_hash(workspace: &Workspace, hash: &str) -> miette::Result<String> {
+    let mut matches = BTreeSet::new();
+
+    for (dir, extension) in [
+        (&workspace.hash_engine.hashes_dir, ".json"),
+        (&workspace.hash_engine.outputs_dir, ".tar.gz"),
+        (&workspace.hash_engine.store_dir, ".json"),
+    ] {
+        if !dir.exists() {
+            continue;
+        }
+
+        for entry in fs::read_dir(dir)? {
+            if let Some(name) = get_hash_from_path(&entry.path(), extension) {
+                if name.starts_with(hash) {
+                    matches.insert(name);
+                }
+            }
+        }
+    }
+
+    if matches.len() > 1 && !matches.contains(hash) {
+        return Err(miette!(
+            "Hash {} is ambiguous, as it matches {}. Provide more characters to narrow it down.",
+            color::hash(hash),
+            matches
+                .iter()
+                .map(color::hash)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if matches.contains(hash) {
+        return Ok(hash.to_owned());
+    }
+
+    matches.into_iter().next().ok_or_else(|| {
+        miette!(
+            "Unable to find a cached archive or hash manifest for {}!",
+            color::hash(hash)
+        )
+    })
+}
+
+fn load_run_states(workspace: &Workspace) -> miette::Result<Vec<RunTargetState>> {
+    let mut states = vec![];
+
+    for path in fs::read_dir_all(&workspace.cache_engine.states_dir)?
+        .into_iter()
+        .map(|entry| entry.path())
+        .filter(|path| path.ends_with("lastRun.json"))
+    {
+        let state = workspace.cache_engine.cache::<RunTargetState>(path)?;
+
+        if !state.data.hash.is_empty() {
+            states.push(state.data);
+        }
+    }
+
+    states.sort_by(|a, b| a.target.cmp(&b.target));
+
+    Ok(states)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheArchiveEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+// Read the entire archive (not just the headers), so that truncated
+// or corrupted contents and checksums are detected.
+fn read_archive_entries(archive_path: &Path) -> miette::Result<Vec<CacheArchiveEntry>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(fs::open_file(archive_path)?));
+    let mut entries = vec![];
+
+    for entry in archive.entries().into_diagnostic()? {
+        let mut entry = entry.into_diagnostic()?;
+
+        io::copy(&mut entry, &mut io::sink()).into_diagnostic()?;
+
+        entries.push(CacheArchiveEntry {
+            path: entry
+                .path()
+                .into_diagnostic()?
+                .to_string_lossy()
+                .to_string(),
+            size: entry.size(),
+        });
+    }
+
+    io::copy(&mut archive.into_inner(), &mut io::sink()).into_diagnostic()?;
+
+    Ok(entries)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheListItem {
+    pub archive_size: Option<u64>,
+    pub hash: String,
+    pub last_run_time: u128,
+    pub store_size: Option<u64>,
+    pub target: String,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CacheListArgs {
+    #[arg(long, help = "Print in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn list(args: ArgsRef<CacheListArgs>, workspace: ResourceRef<Workspace>) {
+    let items = load_run_states(workspace)?
+        .into_iter()
+        .map(|state| CacheListItem {
+            archive_size: get_archive_size(workspace, &state.hash),
+            store_size: get_store_size(workspace, &state.hash),
+            hash: state.hash,
+            last_run_time: state.last_run_time,
+            target: state.target,
+        })
+        .collect::<Vec<_>>();
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&items).into_diagnostic()?
+        );
+
+        return Ok(());
+    }
+
+    let term = Term::buffered_stdout();
+
+    if items.is_empty() {
+        term.line("No targets have been cached")?;
+        term.flush_lines()?;
+
+        return Ok(());
+    }
+
+    let now = now_millis();
+    let target_width = items
+        .iter()
+        .map(|item| item.target.len())
+        .max()
+        .unwrap_or(0);
+
+    for item in items {
+        let age = Duration::from_secs((now.saturating_sub(item.last_run_time) / 1000) as u64);
+
+        term.line(format!(
+            "{}  {}  {:>10}  {}",
+            color::label(format!("{:<target_width$}", item.target)),
+            color::hash(&item.hash[0..item.hash.len().min(16)]),
+            item.archive_size
+                .or(item.store_size)
+                .map(format_size)
+                .unwrap_or_else(|| "-".into()),
+            color::muted_light(format!("{} ago", elapsed(age))),
+        ))?;
+    }
+
+    term.flush_lines()?;
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheShowResult {
+    pub archive: Option<Vec<CacheArchiveEntry>>,
+    pub hash: String,
+    pub manifest: Option<json::JsonValue>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CacheShowArgs {
+    #[arg(required = true, help = "Hash to inspect")]
+    hash: String,
+
+    #[arg(long, help = "Print in JSON format")]
+    json: bool,
+}
+
+#[system]
+pub async fn show(args: ArgsRef<CacheShowArgs>, workspace: ResourceRef<Workspace>) {
+    let hash = find_hash(workspace, &args.hash)?;
+    let manifest_path = workspace.hash_engine.get_manifest_path(&hash);
+    let archive_path = workspace.hash_engine.get_archive_path(&hash);
+
+    let result = CacheShowResult {
+        archive: if archive_path.exists() {
+            Some(read_archive_entries(&archive_path)?)
+        } else {
+            None
+        },
+        manifest: if manifest_path.exists() {
+            Some(json::read_file(&manifest_path)?)
+        } else {
+            None
+        },
+        hash,
+    };
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&result).into_diagnostic()?
+        );
+
+        return Ok(());
+    }
+
+    let term = Term::buffered_stdout();
+
+    term.line("")?;
+    term.render_label(Label::Brand, &result.hash)?;
+
+    term.render_label(Label::Default, "Manifest")?;
+
+    match &result.manifest {
+        Some(manifest) => term.line(json::to_string_pretty(manifest).into_diagnostic()?)?,
+        None => term.line(color::muted_light("No manifest"))?,
+    };
+
+    term.line("")?;
+    term.render_label(Label::Default, "Archive")?;
+
+    match &result.archive {
+        Some(entries) => {
+            term.render_entry("Path", color::path(&archive_path))?;
+            term.render_entry(
+                "Size",
+                format_size(get_archive_size(workspace, &result.hash).unwrap_or_default()),
+            )?;
+            term.render_entry_list(
+                "Contents",
+                entries
+                    .iter()
+                    .map(|entry| {
+                        format!(
+                            "{} {}",
+                            color::file(&entry.path),
+                            color::muted_light(format_size(entry.size))
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )?;
+        }
+        None => term.line(color::muted_light("No archive"))?,
+    };
+
+    term.line("")?;
+    term.flush_lines()?;
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CacheVerifyArgs {
+    #[arg(long, help = "Delete corrupt and orphaned entries")]
+    clean: bool,
+}
+
+#[system]
+pub async fn verify(args: ArgsRef<CacheVerifyArgs>, workspace: ResourceRef<Workspace>) {
+    let hash_engine = &workspace.hash_engine;
+    let mut issues: Vec<(PathBuf, String)> = vec![];
+
+    // Archives without a hash manifest are still hydratable,
+    // so only those that are corrupt are reported
+    for entry in fs::read_dir(&hash_engine.outputs_dir)? {
+        let path = entry.path();
+
+        if let Some(hash) = get_hash_from_path(&path, ".digest.json") {
+            if !hash_engine.get_archive_path(&hash).exists() {
+                issues.push((path, "orphaned digest, no archive".into()));
+            }
+
+            continue;
+        }
+
+        let Some(hash) = get_hash_from_path(&path, ".tar.gz") else {
+            continue;
+        };
+
+        debug!(archive = ?path, "Verifying archive");
+
+        if let Err(error) = read_archive_entries(&path) {
+            issues.push((path, format!("corrupt archive: {error}")));
+            continue;
+        }
+
+        match verify_archive_digest(&path, &hash_engine.get_digest_path(&hash)) {
+            Ok(true) => {}
+            Ok(false) => issues.push((path, "corrupt archive, does not match digest".into())),
+            Err(error) => issues.push((path, format!("corrupt digest: {error}"))),
+        };
+    }
+
+    // Hash manifests are kept after a run for debugging and diffing,
+    // so only those that are corrupt are reported
+    for entry in fs::read_dir(&hash_engine.hashes_dir)? {
+        let path = entry.path();
+
+        if get_hash_from_path(&path, ".json").is_none() {
+            continue;
+        }
+
+        debug!(manifest = ?path, "Verifying hash manifest");
+
+        if json::read_file::<json::JsonValue>(&path).is_err() {
+            issues.push((path, "corrupt manifest, invalid JSON".into()));
+        }
+    }
+
+    // Outputs stored for the link hydration mode
+    if hash_engine.store_dir.exists() {
+        let objects_dir = hash_engine.get_store_objects_dir();
+
+        for entry in fs::read_dir(&hash_engine.store_dir)? {
+            let path = entry.path();
+
+            if get_hash_from_path(&path, ".json").is_none() {
+                continue;
+            }
+
+            debug!(manifest = ?path, "Verifying store manifest");
+
+            let Ok(manifest) = json::read_file::<StoreManifest>(&path) else {
+                issues.push((path, "corrupt store manifest, invalid JSON".into()));
+                continue;
+            };
+
+            let is_complete = manifest
+                .files
+                .values()
+                .chain(manifest.stdout.iter())
+                .chain(manifest.stderr.iter())
+                .all(|digest| get_store_object_path(&objects_dir, digest).exists());
+
+            if !is_complete {
+                issues.push((path, "incomplete store manifest, missing objects".into()));
+            }
+        }
+
+        if objects_dir.exists() {
+            for entry in fs::read_dir_all(&objects_dir)? {
+                let path = entry.path();
+
+                // Objects that are still being written
+                if !path.is_file() || fs::file_name(&path).ends_with(".tmp") {
+                    continue;
+                }
+
+                debug!(object = ?path, "Verifying store object");
+
+                if !verify_store_object(&path).unwrap_or_default() {
+                    issues.push((path, "corrupt object, does not match digest".into()));
+                }
+            }
+        }
+    }
+
+    let term = Term::buffered_stdout();
+
+    if issues.is_empty() {
+        term.line("No issues found in the cache")?;
+        term.flush_lines()?;
+
+        return Ok(());
+    }
+
+    issues.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (path, issue) in &issues {
+        term.line(format!(
+            "{} {}",
+            color::file(fs::file_name(path)),
+            color::muted_light(issue)
+        ))?;
+
+        if args.clean {
+            fs::remove(path)?;
+
+            // Digests are only relevant to their archive
+            if let Some(hash) = get_hash_from_path(path, ".tar.gz") {
+                fs::remove(hash_engine.get_digest_path(&hash))?;
+            }
+        }
+    }
+
+    term.flush_lines()?;
+
+    if !args.clean {
+        return Err(miette!(
+            "Found {} issues in the cache. Pass --clean to delete the affected entries.",
+            issues.len()
+        ));
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CacheExportArgs {
+    #[arg(help = "Hashes to export, otherwise all cached outputs are exported")]
+    hashes: Vec<String>,
+
+    #[arg(
+        long,
+        short = 'o',
+        default_value = "moon-cache.tar.gz",
+        help = "Path to write the bundle to"
+    )]
+    output: PathBuf,
+}
+
+#[system]
+pub async fn export(args: ArgsRef<CacheExportArgs>, workspace: ResourceRef<Workspace>) {
+    let hash_engine = &workspace.hash_engine;
+    let mut hashes = BTreeSet::new();
+    let mut count = 0;
+
+    if args.hashes.is_empty() {
+        for (dir, extension) in [
+            (&hash_engine.outputs_dir, ".tar.gz"),
+            (&hash_engine.store_dir, ".json"),
+        ] {
+            if !dir.exists() {
+                continue;
+            }
+
+            for entry in fs::read_dir(dir)? {
+                if let Some(hash) = get_hash_from_path(&entry.path(), extension) {
+                    hashes.insert(hash);
+                }
+            }
+        }
+    } else {
+        for hash in &args.hashes {
+            hashes.insert(find_hash(workspace, hash)?);
+        }
+    }
+
+    // The manifest is optional, but the archive, or the stored outputs
+    // when using the link hydration mode, are not
+    let objects_dir = hash_engine.get_store_objects_dir();
+    let mut sources = vec![];
+    let mut objects = BTreeSet::new();
+
+    for hash in &hashes {
+        let archive_path = hash_engine.get_archive_path(hash);
+
+        if archive_path.exists() {
+            sources.push((archive_path, format!("outputs/{hash}.tar.gz")));
+
+            let digest_path = hash_engine.get_digest_path(hash);
+
+            if digest_path.exists() {
+                sources.push((digest_path, format!("outputs/{hash}.digest.json")));
+            }
+        } else if let Some(manifest) = load_store_manifest(workspace, hash) {
+            let digests = get_store_objects(manifest);
+
+            if !digests
+                .iter()
+                .all(|digest| get_store_object_path(&objects_dir, digest).exists())
+            {
+                continue;
+            }
+
+            sources.push((
+                hash_engine.get_store_manifest_path(hash),
+                format!("store/{hash}.json"),
+            ));
+
+            objects.extend(digests);
+        } else {
+            continue;
+        }
+
+        let manifest_path = hash_engine.get_manifest_path(hash);
+
+        if manifest_path.exists() {
+            sources.push((manifest_path, format!("hashes/{hash}.json")));
+        }
+
+        count += 1;
+    }
+
+    // Objects are shared between store manifests, so only export them once
+    for digest in objects {
+        sources.push((
+            get_store_object_path(&objects_dir, &digest),
+            format!("store/objects/{}/{digest}", &digest[0..2]),
+        ));
+    }
+
+    if count == 0 {
+        return Err(miette!("No archives or stored outputs to export."));
+    }
+
+    let bundle_path = workspace.working_dir.join(&args.output);
+    let mut archive = Archiver::new(&workspace.cache_engine.cache_dir, &bundle_path);
+
+    for (source, name) in &sources {
+        archive.add_source_file(source, Some(name.as_str()));
+    }
+
+    archive.pack(TarPacker::new_gz)?;
+
+    println!(
+        "Exported {} cached outputs to {}",
+        count,
+        color::path(&bundle_path)
+    );
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CacheImportArgs {
+    #[arg(
+        required = true,
+        help = "Path to a bundle created with `moon cache export`"
+    )]
+    bundle: PathBuf,
+}
+
+#[system]
+pub async fn import(args: ArgsRef<CacheImportArgs>, workspace: ResourceRef<Workspace>) {
+    let hash_engine = &workspace.hash_engine;
+    let bundle_path = workspace.working_dir.join(&args.bundle);
+
+    if !bundle_path.exists() {
+        return Err(miette!(
+            "Bundle {} does not exist.",
+            color::path(&bundle_path)
+        ));
+    }
+
+    // Unpack into a temporary directory first, so that only archives,
+    // manifests, and store objects are copied into the cache, and nothing else
+    let temp_dir = workspace.cache_engine.cache_dir.join("import");
+
+    fs::remove(&temp_dir)?;
+
+    Archiver::new(&temp_dir, &bundle_path).unpack(TarUnpacker::new_gz)?;
+
+    let mut count = 0;
+
+    if temp_dir.join("outputs").exists() {
+        for entry in fs::read_dir(temp_dir.join("outputs"))? {
+            let Some(hash) = get_hash_from_path(&entry.path(), ".tar.gz") else {
+                continue;
+            };
+
+            let archive_path = hash_engine.get_archive_path(&hash);
+
+            // Archives are immutable, so don't overwrite existing
+            if !archive_path.exists() {
+                fs::rename(entry.path(), archive_path)?;
+                count += 1;
+
+                let temp_digest_path = temp_dir.join("outputs").join(format!("{hash}.digest.json"));
+
+                if temp_digest_path.exists() {
+                    fs::rename(temp_digest_path, hash_engine.get_digest_path(&hash))?;
+                }
+            }
+        }
+    }
+
+    let temp_store_dir = temp_dir.join("store");
+
+    if temp_store_dir.exists() {
+        let objects_dir = hash_engine.get_store_objects_dir();
+        let temp_objects_dir = temp_store_dir.join("objects");
+
+        fs::create_dir_all(&hash_engine.store_dir)?;
+
+        for entry in fs::read_dir(&temp_store_dir)? {
+            let Some(hash) = get_hash_from_path(&entry.path(), ".json") else {
+                continue;
+            };
+
+            let manifest_path = hash_engine.get_store_manifest_path(&hash);
+
+            // Manifests are immutable, so don't overwrite existing
+            if manifest_path.exists() {
+                continue;
+            }
+
+            let Ok(manifest) = json::read_file::<StoreManifest>(entry.path()) else {
+                continue;
+            };
+
+            let mut is_complete = true;
+
+            for digest in get_store_objects(manifest) {
+                let object_path = get_store_object_path(&objects_dir, &digest);
+
+                if object_path.exists() {
+                    continue;
+                }
+
+                // Objects are hardlinked into the workspace, so only import
+                // those that match their digest
+                let temp_object_path = get_store_object_path(&temp_objects_dir, &digest);
+
+                if !temp_object_path.exists()
+                    || !verify_store_object(&temp_object_path).unwrap_or_default()
+                {
+                    is_complete = false;
+                    break;
+                }
+
+                fs::create_dir_all(object_path.parent().unwrap())?;
+                fs::rename(temp_object_path, object_path)?;
+            }
+
+            if is_complete {
+                fs::rename(entry.path(), manifest_path)?;
+                count += 1;
+            }
+        }
+    }
+
+    if temp_dir.join("hashes").exists() {
+        for entry in fs::read_dir(temp_dir.join("hashes"))? {
+            let Some(hash) = get_hash_from_path(&entry.path(), ".json") else {
+                continue;
+            };
+
+            let manifest_path = hash_engine.get_manifest_path(&hash);
+
+            if !manifest_path.exists() {
+                fs::rename(entry.path(), manifest_path)?;
+            }
+        }
+    }
+
+    fs::remove(&temp_dir)?;
+
+    println!(
+        "Imported {} cached outputs from {}",
+        count,
+        color::path(&bundle_path)
+    );
+}
@@ -6,15 +6,20 @@ use itertools::Itertools;
 use moon::{build_dep_graph, generate_project_graph};
 use moon_action_context::ActionContext;
 use moon_action_pipeline::Pipeline;
+use moon_cache::DurationsState;
 use moon_common::path::WorkspaceRelativePathBuf;
 use moon_dep_graph::DepGraph;
 use moon_project_graph::ProjectGraph;
 use moon_target::Target;
 use moon_terminal::safe_exit;
+use moon_utils::time;
 use moon_workspace::Workspace;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use starbase::{system, AppResult};
 use starbase_styles::color;
+use starbase_utils::json;
+use std::path::PathBuf;
+use std::time::Duration;
 use tracing::debug;
 
 type TargetList = Vec<Target>;
@@ -26,6 +31,13 @@ pub struct CiArgs {
     #[arg(long, help = "Base branch, commit, or revision to compare against")]
     base: Option<String>,
 
+    #[arg(
+        long,
+        help = "Path to a JSON file of target durations, used to balance targets across jobs",
+        help_heading = HEADING_PARALLELISM
+    )]
+    durations: Option<PathBuf>,
+
     #[arg(long, help = "Current branch, commit, or revision to compare with")]
     head: Option<String>,
 
@@ -124,40 +136,211 @@ fn gather_runnable_targets(
     Ok(targets)
 }
 
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+
+    index
+}
+
+/// Group targets that depend on each other, so that a dependency chain can be ran
+/// within a single job, instead of its dependencies being ran in multiple jobs.
+fn group_dependency_chains(
+    project_graph: &ProjectGraph,
+    targets: TargetList,
+) -> AppResult<Vec<TargetList>> {
+    let indices: FxHashMap<&Target, usize> = targets
+        .iter()
+        .enumerate()
+        .map(|(index, target)| (target, index))
+        .collect();
+    let mut parents = (0..targets.len()).collect::<Vec<_>>();
+
+    for (index, target) in targets.iter().enumerate() {
+        let project = project_graph.get(target.scope_id.as_ref().unwrap())?;
+        let task = project.get_task(&target.task_id)?;
+
+        for dep in &task.deps {
+            if let Some(dep_index) = indices.get(dep) {
+                let root = find_root(&mut parents, index);
+                let dep_root = find_root(&mut parents, *dep_index);
+
+                parents[root.max(dep_root)] = root.min(dep_root);
+            }
+        }
+    }
+
+    let mut groups: Vec<TargetList> = vec![];
+    let mut group_indices = FxHashMap::<usize, usize>::default();
+
+    for (index, target) in targets.iter().enumerate() {
+        let root = find_root(&mut parents, index);
+
+        match group_indices.get(&root) {
+            Some(group_index) => {
+                groups[*group_index].push(target.to_owned());
+            }
+            None => {
+                group_indices.insert(root, groups.len());
+                groups.push(vec![target.to_owned()]);
+            }
+        };
+    }
+
+    Ok(groups)
+}
+
+/// Balance groups of targets across jobs based on their historical durations, by
+/// assigning the slowest groups first to the job with the least amount of work.
+/// Groups that are slower than an evenly balanced job are split into their targets.
+fn balance_targets_across_jobs(
+    groups: Vec<TargetList>,
+    durations: &FxHashMap<String, Duration>,
+    job_total: usize,
+) -> Vec<(Duration, TargetList)> {
+    // Targets without history are treated as an average target
+    let default_duration = if durations.is_empty() {
+        Duration::ZERO
+    } else {
+        durations.values().sum::<Duration>() / durations.len() as u32
+    };
+
+    let get_duration = |target: &Target| {
+        durations
+            .get(&target.id)
+            .copied()
+            .unwrap_or(default_duration)
+    };
+
+    let total_duration = groups.iter().flatten().map(get_duration).sum::<Duration>();
+    let job_duration = total_duration / job_total as u32;
+    let mut weighed_groups = vec![];
+
+    for group in groups {
+        let group_duration = group.iter().map(get_duration).sum::<Duration>();
+
+        if group.len() > 1 && group_duration > job_duration {
+            for target in group {
+                weighed_groups.push((get_duration(&target), vec![target]));
+            }
+        } else {
+            weighed_groups.push((group_duration, group));
+        }
+    }
+
+    weighed_groups.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1[0].id.cmp(&b.1[0].id)));
+
+    let mut jobs = vec![(Duration::ZERO, vec![]); job_total];
+
+    for (group_duration, group) in weighed_groups {
+        // Always pick the first job when tied, so that every job
+        // computes the same distribution
+        let job = jobs
+            .iter_mut()
+            .min_by_key(|(job_duration, _)| *job_duration)
+            .unwrap();
+
+        job.0 += group_duration;
+        job.1.extend(group);
+    }
+
+    jobs
+}
+
+/// Select the targets for the current job. Every job must compute the same
+/// distribution, so the durations must come from the same source in every job.
+/// When none of the targets have a duration, targets are split evenly by count.
+fn select_job_targets(
+    groups: Vec<TargetList>,
+    durations: &FxHashMap<String, Duration>,
+    job_index: usize,
+    job_total: usize,
+) -> (Option<Duration>, TargetList) {
+    let has_history = groups
+        .iter()
+        .flatten()
+        .any(|target| durations.contains_key(&target.id));
+
+    if has_history {
+        let (job_duration, job_targets) = balance_targets_across_jobs(groups, durations, job_total)
+            .into_iter()
+            .nth(job_index)
+            .unwrap_or_default();
+
+        return (Some(job_duration), job_targets);
+    }
+
+    let mut targets = groups.into_iter().flatten().collect::<Vec<_>>();
+    targets.sort();
+
+    let batch_size = targets.len() / job_total;
+
+    let job_targets = if job_index == 0 {
+        targets[0..batch_size].to_vec()
+    } else if job_index == job_total - 1 {
+        targets[(batch_size * job_index)..].to_vec()
+    } else {
+        targets[(batch_size * job_index)..(batch_size * (job_index + 1))].to_vec()
+    };
+
+    (None, job_targets)
+}
+
+/// Load target durations from a file that's shared between jobs. The local cache
+/// is purposefully not used, as it may differ between jobs.
+fn load_durations(
+    workspace: &Workspace,
+    path: Option<&PathBuf>,
+) -> AppResult<FxHashMap<String, Duration>> {
+    let Some(path) = path else {
+        return Ok(FxHashMap::default());
+    };
+
+    let state: DurationsState = json::read_file(workspace.working_dir.join(path))?;
+
+    Ok(state
+        .targets
+        .into_iter()
+        .map(|(target, millis)| (target, Duration::from_millis(millis)))
+        .collect())
+}
+
 /// Distribute targets across jobs if parallelism is enabled.
 fn distribute_targets_across_jobs(
     provider: &CiOutput,
     args: &CiArgs,
+    workspace: &Workspace,
+    project_graph: &ProjectGraph,
     targets: TargetList,
-) -> TargetList {
+) -> AppResult<TargetList> {
     if args.job.is_none() || args.job_total.is_none() {
-        return targets;
+        return Ok(targets);
     }
 
     let job_index = args.job.unwrap_or_default();
     let job_total = args.job_total.unwrap_or_default();
-    let batch_size = targets.len() / job_total;
-    let batched_targets;
+    let durations = load_durations(workspace, args.durations.as_ref())?;
 
     print_header(provider, "Distributing targets across jobs");
     println!("Job index: {job_index}");
-    println!("Job total: {job_index}");
-    println!("Batch size: {batch_size}");
-    println!("Batched targets:");
+    println!("Job total: {job_total}");
 
-    if job_index == 0 {
-        batched_targets = targets[0..batch_size].to_vec();
-    } else if job_index == job_total - 1 {
-        batched_targets = targets[(batch_size * job_index)..].to_vec();
-    } else {
-        batched_targets =
-            targets[(batch_size * job_index)..(batch_size * (job_index + 1))].to_vec();
+    let groups = group_dependency_chains(project_graph, targets)?;
+    let (job_duration, batched_targets) =
+        select_job_targets(groups, &durations, job_index, job_total);
+
+    if let Some(job_duration) = job_duration {
+        println!("Estimated duration: {}", time::elapsed(job_duration));
     }
 
+    println!("Batched targets:");
+
     print_targets(&batched_targets);
     print_footer(provider);
 
-    batched_targets
+    Ok(batched_targets)
 }
 
 /// Generate a dependency graph with the runnable targets.
@@ -205,11 +388,14 @@ pub async fn ci(
         return Ok(());
     }
 
-    let targets = distribute_targets_across_jobs(&ci_provider, args, targets);
+    let targets =
+        distribute_targets_across_jobs(&ci_provider, args, workspace, &project_graph, targets)?;
     let dep_graph = generate_dep_graph(&ci_provider, &project_graph, &targets)?;
 
-    // Process all tasks in the graph
+    // Process all tasks in the graph. Groups can't be nested, and the
+    // output of each target is grouped separately, so close immediately.
     print_header(&ci_provider, "Running all targets");
+    print_footer(&ci_provider);
 
     let context = ActionContext {
         primary_targets: FxHashSet::from_iter(targets),
@@ -229,8 +415,6 @@ pub async fn ci(
         .run(dep_graph, Some(context))
         .await?;
 
-    print_footer(&ci_provider);
-
     print_header(&ci_provider, "Summary");
 
     pipeline.render_summary(&results)?;
@@ -246,3 +430,178 @@ pub async fn ci(
         safe_exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_durations(list: &[(&str, u64)]) -> FxHashMap<String, Duration> {
+        FxHashMap::from_iter(
+            list.iter()
+                .map(|(id, secs)| (id.to_string(), Duration::from_secs(*secs))),
+        )
+    }
+
+    fn create_group(ids: &[&str]) -> TargetList {
+        ids.iter().map(|id| Target::parse(id).unwrap()).collect()
+    }
+
+    fn get_ids(job: &(Duration, TargetList)) -> Vec<&str> {
+        job.1.iter().map(|target| target.id.as_str()).collect()
+    }
+
+    #[test]
+    fn balances_by_duration() {
+        let jobs = balance_targets_across_jobs(
+            vec![
+                create_group(&["a:e2e"]),
+                create_group(&["b:e2e"]),
+                create_group(&["a:lint"]),
+                create_group(&["b:lint"]),
+                create_group(&["c:lint"]),
+            ],
+            &create_durations(&[
+                ("a:e2e", 100),
+                ("b:e2e", 70),
+                ("a:lint", 10),
+                ("b:lint", 10),
+                ("c:lint", 10),
+            ]),
+            2,
+        );
+
+        assert_eq!(get_ids(&jobs[0]), vec!["a:e2e"]);
+        assert_eq!(
+            get_ids(&jobs[1]),
+            vec!["b:e2e", "a:lint", "b:lint", "c:lint"]
+        );
+        assert_eq!(jobs[0].0, Duration::from_secs(100));
+        assert_eq!(jobs[1].0, Duration::from_secs(100));
+    }
+
+    #[test]
+    fn keeps_dependency_chains_together() {
+        let jobs = balance_targets_across_jobs(
+            vec![
+                create_group(&["a:build", "a:test"]),
+                create_group(&["b:build"]),
+                create_group(&["c:build"]),
+            ],
+            &create_durations(&[
+                ("a:build", 20),
+                ("a:test", 20),
+                ("b:build", 20),
+                ("c:build", 20),
+            ]),
+            2,
+        );
+
+        assert_eq!(get_ids(&jobs[0]), vec!["a:build", "a:test"]);
+        assert_eq!(get_ids(&jobs[1]), vec!["b:build", "c:build"]);
+    }
+
+    #[test]
+    fn splits_chains_slower_than_a_job() {
+        let jobs = balance_targets_across_jobs(
+            vec![
+                create_group(&["a:build", "a:test"]),
+                create_group(&["b:build"]),
+            ],
+            &create_durations(&[("a:build", 50), ("a:test", 40), ("b:build", 10)]),
+            2,
+        );
+
+        assert_eq!(get_ids(&jobs[0]), vec!["a:build"]);
+        assert_eq!(get_ids(&jobs[1]), vec!["a:test", "b:build"]);
+    }
+
+    #[test]
+    fn uses_average_duration_without_history() {
+        let jobs = balance_targets_across_jobs(
+            vec![
+                create_group(&["a:build"]),
+                create_group(&["b:build"]),
+                create_group(&["c:build"]),
+            ],
+            &create_durations(&[("a:build", 30), ("b:build", 10)]),
+            2,
+        );
+
+        assert_eq!(get_ids(&jobs[0]), vec!["a:build"]);
+        assert_eq!(get_ids(&jobs[1]), vec!["c:build", "b:build"]);
+    }
+
+    fn cover_all_jobs(
+        groups: &[TargetList],
+        durations: &FxHashMap<String, Duration>,
+        job_total: usize,
+    ) -> Vec<String> {
+        let mut ids = (0..job_total)
+            .flat_map(|job_index| {
+                select_job_targets(groups.to_vec(), durations, job_index, job_total).1
+            })
+            .map(|target| target.id)
+            .collect::<Vec<_>>();
+
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn every_job_covers_each_target_once() {
+        let groups = vec![
+            create_group(&["a:build", "a:test"]),
+            create_group(&["b:build"]),
+            create_group(&["c:build"]),
+            create_group(&["d:lint"]),
+            create_group(&["e:lint"]),
+        ];
+        let expected = vec![
+            "a:build", "a:test", "b:build", "c:build", "d:lint", "e:lint",
+        ];
+
+        // Shared durations, as passed with `--durations`
+        let shared = create_durations(&[("a:build", 30), ("a:test", 50), ("c:build", 10)]);
+
+        for job_total in 1..=4 {
+            assert_eq!(cover_all_jobs(&groups, &shared, job_total), expected);
+            assert_eq!(
+                cover_all_jobs(&groups, &FxHashMap::default(), job_total),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_order_without_durations() {
+        let durations = FxHashMap::default();
+
+        let (_, job_a) = select_job_targets(
+            vec![create_group(&["b:build"]), create_group(&["a:build"])],
+            &durations,
+            0,
+            2,
+        );
+        let (_, job_b) = select_job_targets(
+            vec![create_group(&["a:build"]), create_group(&["b:build"])],
+            &durations,
+            0,
+            2,
+        );
+
+        assert_eq!(job_a, job_b);
+    }
+
+    #[test]
+    fn falls_back_to_count_without_matching_durations() {
+        let (duration, targets) = select_job_targets(
+            vec![create_group(&["a:build"]), create_group(&["b:build"])],
+            &create_durations(&[("z:build", 10)]),
+            1,
+            2,
+        );
+
+        assert_eq!(duration, None);
+        assert_eq!(targets, create_group(&["b:build"]));
+    }
+}
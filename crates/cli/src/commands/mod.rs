@@ -1,4 +1,5 @@
 pub mod bin;
+pub mod cache;
 pub mod check;
 pub mod ci;
 pub mod clean;
@@ -12,6 +13,7 @@ pub mod node;
 pub mod project;
 pub mod query;
 pub mod run;
+pub mod run_watch;
 pub mod setup;
 pub mod sync;
 pub mod syncs;
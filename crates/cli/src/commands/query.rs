@@ -10,6 +10,7 @@ pub use crate::queries::touched_files::{
 use clap::Args;
 use console::Term;
 use miette::IntoDiagnostic;
+use moon_hash::{diff_manifest_lines, ManifestLine};
 use moon_terminal::ExtendedTerm;
 use moon_workspace::Workspace;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -58,10 +59,10 @@ pub async fn hash_diff(args: ArgsRef<QueryHashDiffArgs>, workspace: ResourceMut<
     let term = Term::buffered_stdout();
 
     if args.json {
-        for diff in diff::lines(&result.left, &result.right) {
-            match diff {
-                diff::Result::Left(l) => result.left_diffs.push(l.trim().to_owned()),
-                diff::Result::Right(r) => result.right_diffs.push(r.trim().to_owned()),
+        for line in diff_manifest_lines(&result.left, &result.right) {
+            match line {
+                ManifestLine::Left(l) => result.left_diffs.push(l.trim().to_owned()),
+                ManifestLine::Right(r) => result.right_diffs.push(r.trim().to_owned()),
                 _ => {}
             };
         }
@@ -71,23 +72,23 @@ pub async fn hash_diff(args: ArgsRef<QueryHashDiffArgs>, workspace: ResourceMut<
         term.line(format!("Left:  {}", color::id(&result.left_hash)))?;
         term.line(format!("Right: {}\n", color::id(&result.right_hash)))?;
 
-        for diff in diff::lines(&result.left, &result.right) {
-            match diff {
-                diff::Result::Left(l) => {
+        for line in diff_manifest_lines(&result.left, &result.right) {
+            match line {
+                ManifestLine::Left(l) => {
                     if is_tty {
                         term.line(color::success(l))?
                     } else {
                         term.line(format!("+{}", l))?
                     }
                 }
-                diff::Result::Both(l, _) => {
+                ManifestLine::Both(l) => {
                     if is_tty {
                         term.line(l)?
                     } else {
                         term.line(format!(" {}", l))?
                     }
                 }
-                diff::Result::Right(r) => {
+                ManifestLine::Right(r) => {
                     if is_tty {
                         term.line(color::failure(r))?
                     } else {
@@ -1,14 +1,16 @@
 use crate::app::GlobalArgs;
+use crate::commands::run_watch::TargetWatcher;
 use crate::enums::{CacheMode, TouchedStatus};
 use crate::helpers::map_list;
 use crate::queries::touched_files::{query_touched_files, QueryTouchedFilesOptions};
 use clap::Args;
-use miette::miette;
+use miette::{miette, IntoDiagnostic};
 use moon::{build_dep_graph, generate_project_graph};
 use moon_action_context::{ActionContext, ProfileType};
 use moon_action_pipeline::Pipeline;
 use moon_common::is_test_env;
 use moon_project_graph::ProjectGraph;
+use moon_terminal::safe_exit;
 use moon_utils::is_ci;
 use moon_workspace::Workspace;
 use rustc_hash::FxHashSet;
@@ -41,9 +43,22 @@ pub struct RunArgs {
     #[arg(long, short = 'i', help = "Run the target interactively")]
     pub interactive: bool,
 
+    #[arg(
+        long = "no-bail",
+        help = "Continue running actions that are not downstream of a failure"
+    )]
+    pub no_bail: bool,
+
     #[arg(long, help = "Focus target(s) based on the result of a query")]
     pub query: Option<String>,
 
+    #[arg(
+        long,
+        help = "Render a live dashboard of running actions and their logs",
+        conflicts_with_all = ["interactive", "watch"]
+    )]
+    pub ui: bool,
+
     #[arg(
         short = 'u',
         long = "updateCache",
@@ -51,7 +66,38 @@ pub struct RunArgs {
     )]
     pub update_cache: bool,
 
+    #[arg(
+        long,
+        short = 'w',
+        help = "Watch for file changes and re-run affected targets",
+        conflicts_with_all = ["affected", "interactive"]
+    )]
+    pub watch: bool,
+
     // Debugging
+    #[arg(
+        long = "dry-run",
+        help = "Print the actions that would run, their commands, and cache status, without running them",
+        help_heading = HEADING_DEBUGGING,
+        conflicts_with_all = ["interactive", "ui", "watch"]
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long = "explain-miss",
+        help = "Explain what changed since the previous run when a target misses the cache",
+        help_heading = HEADING_DEBUGGING,
+    )]
+    pub explain_miss: bool,
+
+    #[arg(
+        long,
+        help = "Print the dry run in JSON format",
+        help_heading = HEADING_DEBUGGING,
+        requires = "dry_run"
+    )]
+    pub json: bool,
+
     #[arg(
         value_enum,
         long,
@@ -192,6 +238,7 @@ pub async fn run_target(
     // Process all tasks in the graph
     let context = ActionContext {
         affected_only: should_run_affected,
+        explain_miss: args.explain_miss,
         initial_targets: FxHashSet::from_iter(target_ids.to_owned()),
         interactive: args.interactive,
         passthrough_args: args.passthrough.to_owned(),
@@ -209,14 +256,45 @@ pub async fn run_target(
         pipeline.concurrency(concurrency);
     }
 
+    if !args.no_bail && workspace.config.runner.bail {
+        pipeline.bail_on_error();
+    }
+
+    if args.ui {
+        pipeline.show_dashboard();
+    }
+
+    if args.dry_run {
+        let plan = pipeline.dry_run(dep_graph, Some(context)).await?;
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&plan).into_diagnostic()?);
+        } else {
+            println!("{}", plan.render());
+        }
+
+        return Ok(());
+    }
+
     let results = pipeline
-        .bail_on_error()
         .generate_report("runReport.json")
         .run(dep_graph, Some(context))
         .await?;
 
+    // When not bailing, failures don't abort the pipeline,
+    // so summarize them once everything else has ran
+    let failed = results.iter().any(|result| result.should_bail());
+
+    if failed {
+        pipeline.render_summary(&results)?;
+    }
+
     pipeline.render_stats(&results, true)?;
 
+    if failed {
+        safe_exit(1);
+    }
+
     Ok(())
 }
 
@@ -228,6 +306,18 @@ pub async fn run(
 ) {
     let project_graph = generate_project_graph(workspace).await?;
 
+    if args.watch {
+        return TargetWatcher::new(
+            &args.targets,
+            args,
+            global_args.concurrency,
+            workspace,
+            project_graph,
+        )?
+        .watch()
+        .await;
+    }
+
     run_target(
         &args.targets,
         args,
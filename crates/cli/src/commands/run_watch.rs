@@ -0,0 +1,623 @@
+use crate::commands::run::RunArgs;
+use crate::helpers::map_list;
+use miette::{miette, IntoDiagnostic};
+use moon::build_dep_graph;
+use moon_action::ActionNode;
+use moon_action_context::ActionContext;
+use moon_action_pipeline::{ActionResults, Pipeline};
+use moon_common::path::{standardize_separators, WorkspaceRelativePathBuf};
+use moon_dep_graph::DepGraph;
+use moon_process::{find_process_groups, terminate_process_group, TIMEOUT_GRACE_PERIOD};
+use moon_project_graph::ProjectGraph;
+use moon_target::Target;
+use moon_task::Task;
+use moon_workspace::Workspace;
+use notify::{EventKind, RecursiveMode, Watcher};
+use rustc_hash::{FxHashMap, FxHashSet};
+use starbase::AppResult;
+use starbase_styles::color;
+use starbase_utils::glob::GlobSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// How long to wait for the file system to settle before re-running targets.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+type ChangedFiles = FxHashSet<WorkspaceRelativePathBuf>;
+
+type PersistentHandle = JoinHandle<miette::Result<ActionResults>>;
+
+/// Watches the workspace for changes, and re-runs only the targets (and their
+/// dependents) affected by the changed files. The project and action graphs
+/// are only generated once, and are kept in memory between runs.
+pub struct TargetWatcher<'app> {
+    args: &'app RunArgs,
+
+    concurrency: Option<usize>,
+
+    /// Watched targets that depend on a target (reverse dependencies).
+    dependents: FxHashMap<Target, Vec<Target>>,
+
+    /// Running persistent targets, each within their own pipeline,
+    /// so that they can be restarted independently of each other.
+    persistent: FxHashMap<Target, PersistentHandle>,
+
+    primary_targets: Vec<Target>,
+
+    project_graph: Arc<RwLock<ProjectGraph>>,
+
+    target_ids: &'app [String],
+
+    /// All targets within the action graph, and their tasks.
+    tasks: FxHashMap<Target, Task>,
+
+    workspace: &'app Workspace,
+}
+
+impl<'app> TargetWatcher<'app> {
+    pub fn new(
+        target_ids: &'app [String],
+        args: &'app RunArgs,
+        concurrency: Option<usize>,
+        workspace: &'app Workspace,
+        project_graph: ProjectGraph,
+    ) -> miette::Result<Self> {
+        if args.interactive {
+            return Err(miette!("Interactive targets cannot be watched."));
+        }
+
+        let mut dep_builder = build_dep_graph(&project_graph);
+
+        if let Some(query_input) = &args.query {
+            dep_builder.set_query(query_input)?;
+        }
+
+        let primary_targets = dep_builder.run_targets_by_id(target_ids, None)?;
+
+        if args.dependents {
+            for target in &primary_targets {
+                dep_builder.run_dependents_for_target(target)?;
+            }
+        }
+
+        let dep_graph = dep_builder.build();
+        let mut tasks = FxHashMap::default();
+
+        for index in dep_graph.get_node_indices() {
+            let Some(
+                ActionNode::RunTarget(_, target)
+                | ActionNode::RunInteractiveTarget(_, target)
+                | ActionNode::RunPersistentTarget(_, target),
+            ) = dep_graph.get_node_from_index(&index)
+            else {
+                continue;
+            };
+
+            let project = project_graph.get(target.scope_id.as_ref().unwrap())?;
+
+            tasks.insert(
+                target.clone(),
+                project.get_task(&target.task_id)?.to_owned(),
+            );
+        }
+
+        let mut dependents: FxHashMap<Target, Vec<Target>> = FxHashMap::default();
+
+        for (target, task) in &tasks {
+            for dep in &task.deps {
+                if tasks.contains_key(dep) {
+                    dependents
+                        .entry(dep.to_owned())
+                        .or_default()
+                        .push(target.to_owned());
+                }
+            }
+        }
+
+        Ok(TargetWatcher {
+            args,
+            concurrency,
+            dependents,
+            persistent: FxHashMap::default(),
+            primary_targets,
+            project_graph: Arc::new(RwLock::new(project_graph)),
+            target_ids,
+            tasks,
+            workspace,
+        })
+    }
+
+    pub async fn watch(&mut self) -> AppResult {
+        if self.primary_targets.is_empty() {
+            println!(
+                "No tasks found for target(s) {}",
+                map_list(self.target_ids, |id| color::label(id))
+            );
+
+            return Ok(());
+        }
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+
+        let mut watcher =
+            notify::recommended_watcher(move |result: Result<notify::Event, notify::Error>| {
+                if let Ok(event) = result {
+                    let _ = sender.send(event);
+                }
+            })
+            .into_diagnostic()?;
+
+        watcher
+            .watch(&self.workspace.root, RecursiveMode::Recursive)
+            .into_diagnostic()?;
+
+        // Initial run of everything that was requested
+        self.run(self.primary_targets.clone(), FxHashSet::default())
+            .await?;
+
+        loop {
+            println!("{}", color::muted("Watching for changes..."));
+
+            let mut changed_files = ChangedFiles::default();
+
+            // Wait for the first change, then for the file system to settle
+            tokio::select! {
+                event = receiver.recv() => match event {
+                    Some(event) => self.collect_changed_files(event, &mut changed_files),
+                    None => break,
+                },
+                _ = tokio::signal::ctrl_c() => break,
+            };
+
+            while let Ok(Some(event)) = tokio::time::timeout(DEBOUNCE, receiver.recv()).await {
+                self.collect_changed_files(event, &mut changed_files);
+            }
+
+            // Changes to a target's own outputs should not trigger another run
+            let changed_files = remove_output_files(&self.tasks, changed_files)?;
+
+            if changed_files.is_empty() {
+                continue;
+            }
+
+            let affected = get_affected_targets(&self.tasks, &self.dependents, &changed_files)?;
+
+            if affected.is_empty() {
+                debug!(
+                    files = ?changed_files,
+                    "Changed files did not affect any watched targets"
+                );
+
+                continue;
+            }
+
+            self.run(affected, changed_files).await?;
+        }
+
+        for (target, handle) in self.persistent.drain() {
+            stop_persistent_target(&target, handle).await;
+        }
+
+        Ok(())
+    }
+
+    fn collect_changed_files(&self, event: notify::Event, changed_files: &mut ChangedFiles) {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+
+        for path in event.paths {
+            if let Some(file) = self.to_workspace_relative(&path) {
+                changed_files.insert(file);
+            }
+        }
+    }
+
+    fn to_workspace_relative(&self, path: &Path) -> Option<WorkspaceRelativePathBuf> {
+        let file = to_workspace_relative(
+            path,
+            &self.workspace.root,
+            &self.workspace.cache_engine.cache_dir,
+        )?;
+
+        if self.workspace.vcs.is_ignored(path) {
+            return None;
+        }
+
+        Some(file)
+    }
+
+    async fn run(&mut self, targets: Vec<Target>, changed_files: ChangedFiles) -> AppResult {
+        let (persistent, targets): (Vec<_>, Vec<_>) = targets
+            .into_iter()
+            .partition(|target| self.tasks[target].is_persistent());
+
+        if !targets.is_empty() {
+            let dep_graph = self.build_dep_graph(&targets).await?;
+            let context = self.create_context(targets, &changed_files);
+
+            // Failures should not stop the watcher, so never bail
+            let mut pipeline = self.create_pipeline();
+            let results = pipeline.run(dep_graph, Some(context)).await?;
+
+            if results.iter().any(|result| result.should_bail()) {
+                pipeline.render_summary(&results)?;
+            }
+
+            pipeline.render_stats(&results, true)?;
+        }
+
+        // Persistent targets never complete, so run them in the background,
+        // and only after their dependencies have been ran above
+        for target in persistent {
+            if let Some(handle) = self.persistent.remove(&target) {
+                debug!(target = target.as_str(), "Restarting persistent target");
+
+                stop_persistent_target(&target, handle).await;
+            }
+
+            let dep_graph = self.build_dep_graph(&[target.clone()]).await?;
+            let context = self.create_context(vec![target.clone()], &changed_files);
+            let mut pipeline = self.create_pipeline();
+
+            self.persistent.insert(
+                target,
+                tokio::spawn(async move { pipeline.run(dep_graph, Some(context)).await }),
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn build_dep_graph(&self, targets: &[Target]) -> miette::Result<DepGraph> {
+        let project_graph = self.project_graph.read().await;
+        let mut dep_builder = build_dep_graph(&project_graph);
+
+        for target in targets {
+            dep_builder.run_target(target, None)?;
+        }
+
+        Ok(dep_builder.build())
+    }
+
+    fn create_context(&self, targets: Vec<Target>, changed_files: &ChangedFiles) -> ActionContext {
+        ActionContext {
+            explain_miss: self.args.explain_miss,
+            initial_targets: FxHashSet::from_iter(self.target_ids.to_owned()),
+            passthrough_args: self.args.passthrough.to_owned(),
+            primary_targets: FxHashSet::from_iter(targets),
+            profile: self.args.profile.to_owned(),
+            touched_files: changed_files.to_owned(),
+            watch: true,
+            workspace_root: self.workspace.root.clone(),
+            ..ActionContext::default()
+        }
+    }
+
+    fn create_pipeline(&self) -> Pipeline {
+        let mut pipeline =
+            Pipeline::with_shared_graph(self.workspace.to_owned(), Arc::clone(&self.project_graph));
+
+        if let Some(concurrency) = self.concurrency {
+            pipeline.concurrency(concurrency);
+        }
+
+        pipeline
+    }
+}
+
+/// Stop the pipeline of a persistent target, and terminate its process groups,
+/// so that its descendants (dev servers, file watchers, etc) don't outlive it.
+/// Aborting the pipeline only kills the group leader, so wait for the rest of
+/// the group to exit, before the target is respawned.
+async fn stop_persistent_target(target: &Target, handle: PersistentHandle) {
+    let pids = find_process_groups(&target.id);
+
+    handle.abort();
+    let _ = handle.await;
+
+    for pid in pids {
+        debug!(
+            target = target.as_str(),
+            pid, "Terminating process group of persistent target"
+        );
+
+        terminate_process_group(pid, TIMEOUT_GRACE_PERIOD).await;
+    }
+}
+
+fn to_workspace_relative(
+    path: &Path,
+    workspace_root: &Path,
+    cache_dir: &Path,
+) -> Option<WorkspaceRelativePathBuf> {
+    let relative_path = path.strip_prefix(workspace_root).ok()?;
+
+    if relative_path.starts_with(".git") || path.starts_with(cache_dir) {
+        return None;
+    }
+
+    Some(WorkspaceRelativePathBuf::from(standardize_separators(
+        relative_path.to_string_lossy(),
+    )))
+}
+
+/// Remove files that match an output file or glob of any watched target.
+fn remove_output_files(
+    tasks: &FxHashMap<Target, Task>,
+    changed_files: ChangedFiles,
+) -> miette::Result<ChangedFiles> {
+    let output_globs = tasks
+        .values()
+        .flat_map(|task| task.output_globs.iter())
+        .collect::<Vec<_>>();
+    let globset = GlobSet::new(&output_globs)?;
+
+    Ok(changed_files
+        .into_iter()
+        .filter(|file| {
+            !globset.matches(file.as_str())
+                && !tasks.values().any(|task| {
+                    task.output_files
+                        .iter()
+                        .any(|output| file.starts_with(output))
+                })
+        })
+        .collect())
+}
+
+/// Return targets whose inputs have changed, and their dependents.
+/// Persistent dependents are only included if their own inputs changed.
+fn get_affected_targets(
+    tasks: &FxHashMap<Target, Task>,
+    dependents: &FxHashMap<Target, Vec<Target>>,
+    changed_files: &ChangedFiles,
+) -> miette::Result<Vec<Target>> {
+    let mut affected = FxHashSet::default();
+    let mut queue = vec![];
+
+    for (target, task) in tasks {
+        if task.is_affected(changed_files)? {
+            affected.insert(target.to_owned());
+            queue.push(target.to_owned());
+        }
+    }
+
+    while let Some(target) = queue.pop() {
+        for dependent in dependents.get(&target).into_iter().flatten() {
+            if tasks[dependent].is_persistent() || affected.contains(dependent) {
+                continue;
+            }
+
+            affected.insert(dependent.to_owned());
+            queue.push(dependent.to_owned());
+        }
+    }
+
+    let mut targets = affected.into_iter().collect::<Vec<_>>();
+    targets.sort();
+
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn create_task(id: &str, inputs: &[&str], deps: &[&str]) -> (Target, Task) {
+        let target = Target::parse(id).unwrap();
+        let mut task = Task {
+            deps: deps.iter().map(|dep| Target::parse(dep).unwrap()).collect(),
+            target: target.clone(),
+            ..Task::default()
+        };
+
+        for input in inputs {
+            if input.contains('*') {
+                task.input_globs
+                    .insert(WorkspaceRelativePathBuf::from(input));
+            } else {
+                task.input_files
+                    .insert(WorkspaceRelativePathBuf::from(input));
+            }
+        }
+
+        (target, task)
+    }
+
+    fn create_dependents(tasks: &FxHashMap<Target, Task>) -> FxHashMap<Target, Vec<Target>> {
+        let mut dependents: FxHashMap<Target, Vec<Target>> = FxHashMap::default();
+
+        for (target, task) in tasks {
+            for dep in &task.deps {
+                dependents
+                    .entry(dep.to_owned())
+                    .or_default()
+                    .push(target.to_owned());
+            }
+        }
+
+        dependents
+    }
+
+    fn create_files(files: &[&str]) -> ChangedFiles {
+        files.iter().map(WorkspaceRelativePathBuf::from).collect()
+    }
+
+    fn get_ids(targets: Vec<Target>) -> Vec<String> {
+        targets.into_iter().map(|target| target.id).collect()
+    }
+
+    mod affected_targets {
+        use super::*;
+
+        #[test]
+        fn returns_nothing_for_unrelated_files() {
+            let tasks = FxHashMap::from_iter([create_task("a:build", &["a/src/**/*"], &[])]);
+
+            assert!(get_affected_targets(
+                &tasks,
+                &create_dependents(&tasks),
+                &create_files(&["b/src/index.ts"])
+            )
+            .unwrap()
+            .is_empty());
+        }
+
+        #[test]
+        fn includes_dependents() {
+            let tasks = FxHashMap::from_iter([
+                create_task("a:build", &["a/src/**/*"], &[]),
+                create_task("b:build", &["b/src/**/*"], &["a:build"]),
+                create_task("c:test", &["c/tests/**/*"], &["b:build"]),
+                create_task("d:lint", &["d/src/**/*"], &[]),
+            ]);
+
+            assert_eq!(
+                get_ids(
+                    get_affected_targets(
+                        &tasks,
+                        &create_dependents(&tasks),
+                        &create_files(&["a/src/index.ts"])
+                    )
+                    .unwrap()
+                ),
+                vec!["a:build", "b:build", "c:test"]
+            );
+        }
+
+        #[test]
+        fn doesnt_restart_persistent_dependents() {
+            let mut tasks = FxHashMap::from_iter([
+                create_task("a:build", &["a/src/**/*"], &[]),
+                create_task("a:dev", &["a/package.json"], &["a:build"]),
+                create_task("a:test", &["a/tests/**/*"], &["a:build"]),
+            ]);
+
+            tasks
+                .get_mut(&Target::parse("a:dev").unwrap())
+                .unwrap()
+                .options
+                .persistent = true;
+
+            let dependents = create_dependents(&tasks);
+
+            assert_eq!(
+                get_ids(
+                    get_affected_targets(&tasks, &dependents, &create_files(&["a/src/index.ts"]))
+                        .unwrap()
+                ),
+                vec!["a:build", "a:test"]
+            );
+
+            // Unless its own inputs changed
+            assert_eq!(
+                get_ids(
+                    get_affected_targets(&tasks, &dependents, &create_files(&["a/package.json"]))
+                        .unwrap()
+                ),
+                vec!["a:dev"]
+            );
+        }
+    }
+
+    mod workspace_relative {
+        use super::*;
+
+        #[test]
+        fn strips_workspace_root() {
+            let root = PathBuf::from("/workspace");
+
+            assert_eq!(
+                to_workspace_relative(
+                    &root.join("a/src/index.ts"),
+                    &root,
+                    &root.join(".moon/cache")
+                ),
+                Some(WorkspaceRelativePathBuf::from("a/src/index.ts"))
+            );
+        }
+
+        #[test]
+        fn ignores_files_outside_workspace() {
+            let root = PathBuf::from("/workspace");
+
+            assert_eq!(
+                to_workspace_relative(
+                    &PathBuf::from("/other/index.ts"),
+                    &root,
+                    &root.join(".moon/cache")
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn ignores_git_and_cache_dirs() {
+            let root = PathBuf::from("/workspace");
+            let cache_dir = root.join(".moon/cache");
+
+            assert_eq!(
+                to_workspace_relative(&root.join(".git/index"), &root, &cache_dir),
+                None
+            );
+            assert_eq!(
+                to_workspace_relative(&cache_dir.join("states/a.json"), &root, &cache_dir),
+                None
+            );
+        }
+    }
+
+    mod output_files {
+        use super::*;
+
+        #[test]
+        fn removes_output_files_and_dirs() {
+            let (target, mut task) = create_task("a:build", &["a/src/**/*"], &[]);
+            task.output_files
+                .insert(WorkspaceRelativePathBuf::from("a/lib"));
+            task.output_files
+                .insert(WorkspaceRelativePathBuf::from("a/types.d.ts"));
+
+            let tasks = FxHashMap::from_iter([(target, task)]);
+
+            assert_eq!(
+                remove_output_files(
+                    &tasks,
+                    create_files(&["a/lib/index.js", "a/types.d.ts", "a/src/index.ts"])
+                )
+                .unwrap(),
+                create_files(&["a/src/index.ts"])
+            );
+        }
+
+        #[test]
+        fn removes_output_globs() {
+            let (target, mut task) = create_task("a:build", &["a/src/**/*"], &[]);
+            task.output_globs
+                .insert(WorkspaceRelativePathBuf::from("a/dist/**/*.js"));
+            task.output_globs
+                .insert(WorkspaceRelativePathBuf::from("a/src/**/*.d.ts"));
+
+            let tasks = FxHashMap::from_iter([(target, task)]);
+
+            assert_eq!(
+                remove_output_files(
+                    &tasks,
+                    create_files(&[
+                        "a/dist/nested/index.js",
+                        "a/src/index.d.ts",
+                        "a/src/index.ts"
+                    ])
+                )
+                .unwrap(),
+                create_files(&["a/src/index.ts"])
+            );
+        }
+    }
+}
@@ -6,9 +6,11 @@ pub mod queries;
 mod systems;
 
 use crate::app::{
-    Commands, DockerCommands, MigrateCommands, NodeCommands, QueryCommands, SyncCommands,
+    CacheCommands, Commands, DockerCommands, MigrateCommands, NodeCommands, QueryCommands,
+    SyncCommands,
 };
 use crate::commands::bin::bin;
+use crate::commands::cache;
 use crate::commands::check::check;
 use crate::commands::ci::ci;
 use crate::commands::clean::clean;
@@ -106,6 +108,13 @@ pub async fn run_cli() -> AppResult {
             app.execute(systems::check_for_new_version);
             app.execute_with_args(check, args)
         }
+        Commands::Cache { command } => match command {
+            CacheCommands::Export(args) => app.execute_with_args(cache::export, args),
+            CacheCommands::Import(args) => app.execute_with_args(cache::import, args),
+            CacheCommands::List(args) => app.execute_with_args(cache::list, args),
+            CacheCommands::Show(args) => app.execute_with_args(cache::show, args),
+            CacheCommands::Verify(args) => app.execute_with_args(cache::verify, args),
+        },
         Commands::Clean(args) => app.execute_with_args(clean, args),
         Commands::Completions(args) => app.execute_with_args(completions::completions, args),
         Commands::DepGraph(args) => app.execute_with_args(dep_graph, args),
@@ -0,0 +1,350 @@
+use moon_test_utils::{create_sandbox_with_config, predicates::prelude::*, Sandbox};
+use starbase_archive::tar::TarPacker;
+use starbase_archive::Archiver;
+
+const HASH: &str = "a1b2c3d4e5f6";
+const OBJECT: &str = "2e29cd9a98755c46896f7a2d56524db2d6d96b248e36db46de14c30bf47c8d05";
+
+fn create_cached_target(sandbox: &Sandbox) {
+    sandbox.create_file("dist/index.js", "export {};");
+    sandbox.create_file(
+        &format!(".moon/cache/hashes/{HASH}.json"),
+        r#"{"command":"build"}"#,
+    );
+    sandbox.create_file(
+        ".moon/cache/states/app/build/lastRun.json",
+        format!(r#"{{"exitCode":0,"hash":"{HASH}","lastRunTime":0,"target":"app:build"}}"#),
+    );
+
+    let mut archive = Archiver::new(
+        sandbox.path(),
+        &sandbox
+            .path()
+            .join(format!(".moon/cache/outputs/{HASH}.tar.gz")),
+    );
+    archive.add_source_file("dist/index.js", None);
+    archive.pack(TarPacker::new_gz).unwrap();
+}
+
+// Outputs stored for the link hydration mode, without an archive
+fn create_stored_target(sandbox: &Sandbox) {
+    sandbox.create_file(
+        &format!(".moon/cache/hashes/{HASH}.json"),
+        r#"{"command":"build"}"#,
+    );
+    sandbox.create_file(
+        ".moon/cache/states/app/build/lastRun.json",
+        format!(r#"{{"exitCode":0,"hash":"{HASH}","lastRunTime":0,"target":"app:build"}}"#),
+    );
+    sandbox.create_file(
+        &format!(".moon/cache/store/{HASH}.json"),
+        format!(r#"{{"files":{{"dist/index.js":"{OBJECT}"}}}}"#),
+    );
+    sandbox.create_file(
+        &format!(".moon/cache/store/objects/{}/{OBJECT}", &OBJECT[0..2]),
+        "export {};",
+    );
+}
+
+mod cache_list {
+    use super::*;
+
+    #[test]
+    fn lists_cached_targets() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&sandbox);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("list");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("app:build").eval(&output));
+        assert!(predicate::str::contains(HASH).eval(&output));
+    }
+
+    #[test]
+    fn lists_in_json() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&sandbox);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("list").arg("--json");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains(r#""target": "app:build""#).eval(&output));
+        assert!(predicate::str::contains(r#""archiveSize":"#).eval(&output));
+    }
+
+    #[test]
+    fn lists_stored_outputs_size() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_stored_target(&sandbox);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("list").arg("--json");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains(r#""archiveSize": null"#).eval(&output));
+        assert!(predicate::str::contains(r#""storeSize": 10"#).eval(&output));
+    }
+}
+
+mod cache_show {
+    use super::*;
+
+    #[test]
+    fn errors_if_hash_doesnt_exist() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("show").arg("zzz");
+        });
+
+        let output = assert.output();
+
+        assert!(
+            predicate::str::contains("Unable to find a cached archive or hash manifest")
+                .eval(&output)
+        );
+    }
+
+    #[test]
+    fn errors_if_hash_is_ambiguous() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&sandbox);
+
+        sandbox.create_file(".moon/cache/hashes/a1b2ffffffff.json", "{}");
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("show").arg("a1b2");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("is ambiguous").eval(&output));
+    }
+
+    #[test]
+    fn shows_manifest_and_contents() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&sandbox);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("show").arg("a1b2");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains(r#""command": "build""#).eval(&output));
+        assert!(predicate::str::contains("dist/index.js").eval(&output));
+    }
+}
+
+mod cache_verify {
+    use super::*;
+
+    #[test]
+    fn passes_for_valid_cache() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&sandbox);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("verify");
+        });
+
+        assert.success();
+    }
+
+    #[test]
+    fn detects_corrupt_entries() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&sandbox);
+
+        sandbox.create_file(".moon/cache/outputs/corrupt.tar.gz", "not an archive");
+        sandbox.create_file(".moon/cache/hashes/corrupt.json", "{");
+        sandbox.create_file(format!(".moon/cache/outputs/{HASH}.digest.json"), "{");
+        sandbox.create_file(".moon/cache/outputs/orphan.digest.json", "{}");
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("verify");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("Found 4 issues").eval(&output));
+        assert!(predicate::str::contains("corrupt archive").eval(&output));
+        assert!(predicate::str::contains("corrupt manifest").eval(&output));
+        assert!(predicate::str::contains("corrupt digest").eval(&output));
+        assert!(predicate::str::contains("orphaned digest").eval(&output));
+    }
+
+    #[test]
+    fn ignores_manifests_without_an_archive() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        sandbox.create_file(".moon/cache/hashes/previous.json", "{}");
+
+        sandbox
+            .run_moon(|cmd| {
+                cmd.arg("cache").arg("verify").arg("--clean");
+            })
+            .success();
+
+        assert!(sandbox
+            .path()
+            .join(".moon/cache/hashes/previous.json")
+            .exists());
+    }
+
+    #[test]
+    fn detects_corrupt_store_entries() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        sandbox.create_file(
+            ".moon/cache/store/incomplete.json",
+            r#"{"files":{"dist/index.js":"ff00"}}"#,
+        );
+        sandbox.create_file(".moon/cache/store/corrupt.json", "{");
+        sandbox.create_file(".moon/cache/store/objects/ab/abcdef", "modified");
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("verify");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("Found 3 issues").eval(&output));
+        assert!(predicate::str::contains("incomplete store manifest").eval(&output));
+        assert!(predicate::str::contains("corrupt store manifest").eval(&output));
+        assert!(predicate::str::contains("corrupt object").eval(&output));
+    }
+
+    #[test]
+    fn can_clean_entries() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        sandbox.create_file(".moon/cache/outputs/orphan.tar.gz", "not an archive");
+
+        sandbox
+            .run_moon(|cmd| {
+                cmd.arg("cache").arg("verify").arg("--clean");
+            })
+            .success();
+
+        assert!(!sandbox
+            .path()
+            .join(".moon/cache/outputs/orphan.tar.gz")
+            .exists());
+    }
+}
+
+mod cache_export_import {
+    use super::*;
+
+    #[test]
+    fn errors_if_nothing_to_export() {
+        let sandbox = create_sandbox_with_config("base", None, None, None);
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("cache").arg("export");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("No archives or stored outputs to export").eval(&output));
+    }
+
+    #[test]
+    fn round_trips_a_bundle() {
+        let source = create_sandbox_with_config("base", None, None, None);
+
+        create_cached_target(&source);
+
+        source
+            .run_moon(|cmd| {
+                cmd.arg("cache")
+                    .arg("export")
+                    .arg("--output")
+                    .arg("bundle.tar.gz");
+            })
+            .success();
+
+        assert!(source.path().join("bundle.tar.gz").exists());
+
+        let dest = create_sandbox_with_config("base", None, None, None);
+
+        let assert = dest.run_moon(|cmd| {
+            cmd.arg("cache")
+                .arg("import")
+                .arg(source.path().join("bundle.tar.gz"));
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("Imported 1 cached outputs").eval(&output));
+        assert!(dest
+            .path()
+            .join(format!(".moon/cache/outputs/{HASH}.tar.gz"))
+            .exists());
+        assert!(dest
+            .path()
+            .join(format!(".moon/cache/hashes/{HASH}.json"))
+            .exists());
+    }
+
+    #[test]
+    fn round_trips_stored_outputs() {
+        let source = create_sandbox_with_config("base", None, None, None);
+
+        create_stored_target(&source);
+
+        source
+            .run_moon(|cmd| {
+                cmd.arg("cache")
+                    .arg("export")
+                    .arg("--output")
+                    .arg("bundle.tar.gz");
+            })
+            .success();
+
+        let dest = create_sandbox_with_config("base", None, None, None);
+
+        let assert = dest.run_moon(|cmd| {
+            cmd.arg("cache")
+                .arg("import")
+                .arg(source.path().join("bundle.tar.gz"));
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("Imported 1 cached outputs").eval(&output));
+        assert!(dest
+            .path()
+            .join(format!(".moon/cache/store/{HASH}.json"))
+            .exists());
+        assert!(dest
+            .path()
+            .join(format!(
+                ".moon/cache/store/objects/{}/{OBJECT}",
+                &OBJECT[0..2]
+            ))
+            .exists());
+        assert!(dest
+            .path()
+            .join(format!(".moon/cache/hashes/{HASH}.json"))
+            .exists());
+    }
+}
@@ -0,0 +1,111 @@
+use httpmock::prelude::*;
+use moon_config::PartialOpenTelemetryConfig;
+use moon_test_utils::{create_sandbox_with_config, get_node_fixture_configs, Sandbox};
+use std::fs;
+
+fn sandbox(config: PartialOpenTelemetryConfig) -> Sandbox {
+    let (mut workspace_config, toolchain_config, tasks_config) = get_node_fixture_configs();
+
+    workspace_config.open_telemetry = Some(config);
+
+    let sandbox = create_sandbox_with_config(
+        "node",
+        Some(workspace_config),
+        Some(toolchain_config),
+        Some(tasks_config),
+    );
+
+    sandbox.enable_git();
+    sandbox
+}
+
+#[test]
+fn writes_spans_to_a_file() {
+    let sandbox = sandbox(PartialOpenTelemetryConfig {
+        file: Some("traces.json".into()),
+        ..PartialOpenTelemetryConfig::default()
+    });
+
+    let assert = sandbox.run_moon(|cmd| {
+        cmd.arg("run").arg("node:cjs");
+    });
+
+    assert.success();
+
+    let request: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(sandbox.path().join("traces.json")).unwrap())
+            .unwrap();
+    let resource = &request["resourceSpans"][0];
+    let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+
+    assert_eq!(
+        resource["resource"]["attributes"][0]["value"]["stringValue"],
+        "moon"
+    );
+
+    // Root span for the pipeline
+    let root = &spans[0];
+
+    assert_eq!(root["name"], "pipeline");
+    assert!(root.get("parentSpanId").is_none());
+
+    // Child spans for each action
+    for span in &spans[1..] {
+        assert_eq!(span["traceId"], root["traceId"]);
+        assert_eq!(span["parentSpanId"], root["spanId"]);
+    }
+
+    let target = spans
+        .iter()
+        .find(|span| span["name"] == "RunTarget(node:cjs)")
+        .unwrap();
+    let attributes = target["attributes"].as_array().unwrap();
+
+    for key in [
+        "moon.action.type",
+        "moon.action.status",
+        "moon.action.attempts",
+        "moon.hash",
+    ] {
+        assert!(
+            attributes.iter().any(|attr| attr["key"] == key),
+            "missing attribute {key}"
+        );
+    }
+
+    assert!(spans
+        .iter()
+        .any(|span| span["name"].as_str().unwrap().starts_with("SetupNodeTool")));
+}
+
+#[tokio::test]
+async fn exports_spans_to_an_endpoint() {
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/v1/traces")
+            .header("x-api-key", "abc123")
+            .body_contains(r#""name":"RunTarget(node:cjs)""#);
+
+        then.status(200);
+    });
+
+    let sandbox = sandbox(PartialOpenTelemetryConfig {
+        endpoint: Some(server.url("/v1/traces")),
+        headers: Some(
+            [("x-api-key".to_owned(), "abc123".to_owned())]
+                .into_iter()
+                .collect(),
+        ),
+        ..PartialOpenTelemetryConfig::default()
+    });
+
+    let assert = sandbox.run_moon(|cmd| {
+        cmd.arg("run").arg("node:cjs");
+    });
+
+    mock.assert_hits(1);
+
+    assert.success();
+}
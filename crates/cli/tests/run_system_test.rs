@@ -7,7 +7,14 @@ use rustc_hash::FxHashMap;
 use starbase_utils::json;
 
 fn system_sandbox() -> Sandbox {
-    let workspace_config = PartialWorkspaceConfig {
+    system_sandbox_with_config(|_| {})
+}
+
+fn system_sandbox_with_config<C>(callback: C) -> Sandbox
+where
+    C: FnOnce(&mut PartialWorkspaceConfig),
+{
+    let mut workspace_config = PartialWorkspaceConfig {
         projects: Some(PartialWorkspaceProjects::Sources(FxHashMap::from_iter([
             ("unix".into(), "unix".to_owned()),
             ("windows".into(), "windows".to_owned()),
@@ -15,6 +22,8 @@ fn system_sandbox() -> Sandbox {
         ..PartialWorkspaceConfig::default()
     };
 
+    callback(&mut workspace_config);
+
     let tasks_config = PartialInheritedTasksConfig {
         // Avoid defaults in hashes or snapshots
         implicit_inputs: Some(vec![]),
@@ -89,6 +98,129 @@ mod unix {
         assert.code(1);
     }
 
+    #[test]
+    fn continues_past_failures_when_not_bailing() {
+        let sandbox = system_sandbox();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run")
+                .arg("unix:downstreamOfFailure")
+                .arg("unix:exitZero")
+                .arg("--no-bail");
+        });
+
+        let output = assert.output();
+
+        // Independent targets still run
+        assert!(predicate::str::contains("unix:exitZero").eval(&output));
+        assert!(
+            predicate::str::contains("skipped, dependency unix:exitNonZero failed").eval(&output)
+        );
+        assert!(predicate::str::contains("Skipped actions").eval(&output));
+        assert!(!predicate::str::contains("should not run").eval(&output));
+
+        assert.code(1);
+    }
+
+    #[test]
+    fn writes_reports_when_bailing() {
+        let sandbox = system_sandbox();
+
+        sandbox
+            .run_moon(|cmd| {
+                cmd.arg("run").arg("unix:exitNonZero");
+            })
+            .code(1);
+
+        let cache_dir = sandbox.path().join(".moon/cache");
+        let xml = std::fs::read_to_string(cache_dir.join("runReport.junit.xml")).unwrap();
+
+        assert!(xml.contains("<testcase name=\"exitNonZero\" classname=\"unix\""));
+        assert!(xml.contains("<failure"));
+        assert!(xml.contains("<system-out>stdout"));
+        assert!(cache_dir.join("runReport.json").exists());
+        assert!(cache_dir.join("runReport.trace.json").exists());
+    }
+
+    #[test]
+    fn runs_cleanup_after_failure_when_bailing() {
+        let sandbox = system_sandbox();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("unix:exitNonZeroWithCleanup");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("unix:cleanup").eval(&output));
+        assert!(predicate::str::contains("cleaned up").eval(&output));
+        assert!(predicate::str::contains("unix:exitNonZeroWithCleanup").eval(&output));
+
+        assert.code(1);
+    }
+
+    #[test]
+    fn runs_cleanup_when_cancelled() {
+        use std::process::{Command, Stdio};
+        use std::time::Duration;
+
+        let sandbox = system_sandbox();
+
+        let child = Command::new(env!("CARGO_BIN_EXE_moon"))
+            .current_dir(sandbox.path())
+            .env("MOON_TEST", "true")
+            .env("MOON_HOME", sandbox.path().join(".moon-home"))
+            .arg("run")
+            .arg("unix:sleepWithCleanup")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // Wait for the task to start before sending ctrl + c
+        let started = sandbox.path().join("unix/started");
+
+        for _ in 0..600 {
+            if started.exists() {
+                break;
+            }
+
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        assert!(started.exists());
+
+        Command::new("kill")
+            .arg("-INT")
+            .arg(child.id().to_string())
+            .status()
+            .unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(predicate::str::contains("cleaned up").eval(&stdout));
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn runs_cleanup_after_failure_when_not_bailing() {
+        let sandbox = system_sandbox();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run")
+                .arg("unix:exitNonZeroWithCleanup")
+                .arg("--no-bail");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("cleaned up").eval(&output));
+        assert!(!predicate::str::contains("skipped, dependency").eval(&output));
+
+        assert.code(1);
+    }
+
     #[test]
     fn handles_process_exit_nonzero_inline() {
         let sandbox = system_sandbox();
@@ -191,6 +323,322 @@ mod unix {
         assert_snapshot!(assert.output());
     }
 
+    #[test]
+    fn terminates_on_timeout_and_retries() {
+        let sandbox = system_sandbox();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("unix:timeout");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("unix:timeout (2/2)").eval(&output));
+        assert!(predicate::str::contains("timed out after 1 seconds").eval(&output));
+    }
+
+    #[cfg(target_os = "linux")]
+    mod sandbox {
+        use super::*;
+
+        fn is_unsupported(output: &str) -> bool {
+            predicate::str::contains("with Landlock enabled").eval(output)
+        }
+
+        #[test]
+        fn allows_declared_inputs_and_outputs() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:sandbox");
+            });
+
+            assert.success();
+
+            assert!(sandbox.path().join("unix/sandbox/out.txt").exists());
+        }
+
+        #[test]
+        fn reports_undeclared_inputs() {
+            let sandbox = system_sandbox();
+
+            sandbox.create_file("unix/undeclared.txt", "secret");
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:sandboxViolation");
+            });
+
+            let output = assert.output();
+
+            if is_unsupported(&output) {
+                return;
+            }
+
+            assert!(predicate::str::contains("outside of its sandbox").eval(&output));
+            assert!(predicate::str::contains("unix/undeclared.txt").eval(&output));
+        }
+    }
+
+    mod conditions {
+        use super::*;
+
+        #[test]
+        fn skips_when_os_not_met() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:conditionOs");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains("skipped, condition not met, host OS").eval(&output));
+            assert!(!predicate::str::contains("condition met\n").eval(&output));
+        }
+
+        #[test]
+        fn skips_dependents() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:downstreamOfCondition");
+            });
+
+            let output = assert.output();
+
+            assert!(
+                predicate::str::contains("skipped, dependency unix:conditionOs was skipped")
+                    .eval(&output)
+            );
+            assert!(!predicate::str::contains("should not run").eval(&output));
+        }
+
+        #[test]
+        fn skips_dependents_transitively() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:transitivelyDownstreamOfCondition");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains(
+                "skipped, dependency unix:downstreamOfCondition was skipped"
+            )
+            .eval(&output));
+            assert!(!predicate::str::contains("should not run").eval(&output));
+        }
+
+        #[test]
+        fn skips_when_env_var_not_met() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:conditionEnv");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains(
+                "condition not met, env var MOON_CONDITION is not yes"
+            )
+            .eval(&output));
+        }
+
+        #[test]
+        fn runs_when_env_var_met() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run")
+                    .arg("unix:conditionEnv")
+                    .env("MOON_CONDITION", "yes");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains("condition met").eval(&output));
+            assert!(!predicate::str::contains("condition not met").eval(&output));
+
+            assert.success();
+        }
+
+        #[test]
+        fn skips_when_files_dont_exist() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:conditionFiles");
+            });
+
+            let output = assert.output();
+
+            assert!(
+                predicate::str::contains("condition not met, no files match missing.txt")
+                    .eval(&output)
+            );
+        }
+
+        #[test]
+        fn runs_when_files_exist() {
+            let sandbox = system_sandbox();
+
+            sandbox.create_file("unix/missing.txt", "");
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:conditionFiles");
+            });
+
+            let output = assert.output();
+
+            assert!(!predicate::str::contains("condition not met").eval(&output));
+
+            assert.success();
+        }
+
+        #[test]
+        fn plans_as_skipped_in_dry_run() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run")
+                    .arg("unix:downstreamOfCondition")
+                    .arg("--dry-run");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains("skipped, condition not met, host OS").eval(&output));
+            assert!(
+                predicate::str::contains("skipped, dependency unix:conditionOs was skipped")
+                    .eval(&output)
+            );
+        }
+    }
+
+    mod secrets {
+        use super::*;
+        use moon_config::PartialRunnerConfig;
+        use std::fs;
+
+        #[test]
+        fn masks_values_in_output() {
+            let sandbox = system_sandbox();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:envVarsSecret");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains("MOON_FOO=*****").eval(&output));
+            assert!(predicate::str::contains("MOON_BAR=123").eval(&output));
+            assert!(!predicate::str::contains("hunter2").eval(&output));
+        }
+
+        #[test]
+        fn masks_values_in_cached_output() {
+            let sandbox = system_sandbox();
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:envVarsSecret");
+            });
+
+            let stdout = fs::read_to_string(
+                sandbox
+                    .path()
+                    .join(".moon/cache/states/unix/envVarsSecret/stdout.log"),
+            )
+            .unwrap();
+
+            assert!(predicate::str::contains("MOON_FOO=*****").eval(&stdout));
+            assert!(!predicate::str::contains("hunter2").eval(&stdout));
+        }
+
+        #[test]
+        fn masks_values_in_run_report() {
+            let sandbox = system_sandbox();
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run")
+                    .arg("unix:envVarsSecret")
+                    .arg("--")
+                    .arg("hunter2");
+            });
+
+            let report =
+                fs::read_to_string(sandbox.path().join(".moon/cache/runReport.json")).unwrap();
+
+            assert!(predicate::str::contains("*****").eval(&report));
+            assert!(!predicate::str::contains("hunter2").eval(&report));
+        }
+
+        #[test]
+        fn hashes_values_in_manifest() {
+            let sandbox = system_sandbox();
+
+            sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:envVarsSecret");
+            });
+
+            let state: RunTargetState = json::read_file(
+                sandbox
+                    .path()
+                    .join(".moon/cache/states/unix/envVarsSecret/lastRun.json"),
+            )
+            .unwrap();
+            let manifest = fs::read_to_string(
+                sandbox
+                    .path()
+                    .join(format!(".moon/cache/hashes/{}.json", state.hash)),
+            )
+            .unwrap();
+
+            assert!(predicate::str::contains("sha256:").eval(&manifest));
+            assert!(!predicate::str::contains("hunter2").eval(&manifest));
+        }
+
+        #[test]
+        fn masks_values_configured_in_workspace() {
+            let sandbox = system_sandbox_with_config(|cfg| {
+                cfg.runner = Some(PartialRunnerConfig {
+                    secret_env_vars: Some(vec!["MOON_BA*".into()]),
+                    ..PartialRunnerConfig::default()
+                });
+            });
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("unix:envVars");
+            });
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains("MOON_FOO=abc").eval(&output));
+            assert!(predicate::str::contains("MOON_BAR=*****").eval(&output));
+            assert!(predicate::str::contains("MOON_BAZ=*****").eval(&output));
+        }
+    }
+
+    #[test]
+    fn never_runs_targets_holding_the_same_resource_concurrently() {
+        let sandbox = system_sandbox();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run")
+                .arg("unix:resourceA")
+                .arg("unix:resourceB")
+                .arg("--concurrency")
+                .arg("4");
+        });
+
+        let output = assert.output();
+
+        assert!(!predicate::str::contains("resource is already held").eval(&output));
+
+        assert.success();
+    }
+
     #[test]
     fn can_run_many_targets() {
         let sandbox = system_sandbox();
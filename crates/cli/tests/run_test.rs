@@ -1,7 +1,7 @@
 use moon_cache::CacheEngine;
 use moon_config::{
     HasherWalkStrategy, PartialCodeownersConfig, PartialHasherConfig, PartialRunnerConfig,
-    PartialVcsConfig, PartialWorkspaceConfig, VcsProvider,
+    PartialVcsConfig, PartialWorkspaceConfig, UndeclaredOutputsMode, VcsProvider,
 };
 use moon_runner::RunTargetState;
 use moon_target::Target;
@@ -612,6 +612,29 @@ mod hashing {
         assert_ne!(hash2, hash3);
     }
 
+    #[test]
+    fn explains_cache_misses() {
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        sandbox.create_file("outputs/.env", "FOO=123");
+
+        sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("outputs:envFile");
+        });
+
+        sandbox.create_file("outputs/.env", "FOO=456");
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("outputs:envFile").arg("--explain-miss");
+        });
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("cache miss").eval(&output));
+        assert!(predicate::str::contains("outputs/.env").eval(&output));
+    }
+
     #[test]
     fn supports_diff_walking_strategies() {
         let sandbox = cases_sandbox();
@@ -976,6 +999,77 @@ mod outputs {
         assert!(!predicate::str::contains("cached from previous run").eval(&assert.output()));
     }
 
+    mod undeclared {
+        use super::*;
+
+        fn sandbox_with_mode(mode: UndeclaredOutputsMode) -> Sandbox {
+            let sandbox = cases_sandbox_with_config(|cfg| {
+                cfg.runner = Some(PartialRunnerConfig {
+                    undeclared_outputs: Some(mode),
+                    ..PartialRunnerConfig::default()
+                });
+            });
+
+            sandbox.enable_git();
+            sandbox
+        }
+
+        #[test]
+        fn warns_for_undeclared_outputs() {
+            let sandbox = cases_sandbox();
+            sandbox.enable_git();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateUndeclared");
+            });
+
+            assert.success();
+
+            let output = assert.output();
+
+            assert!(predicate::str::contains("not declared as outputs").eval(&output));
+            assert!(predicate::str::contains("outputs/single-file/one.js").eval(&output));
+        }
+
+        #[test]
+        fn doesnt_warn_for_declared_outputs() {
+            let sandbox = cases_sandbox();
+            sandbox.enable_git();
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateFile");
+            });
+
+            assert!(!predicate::str::contains("not declared as outputs").eval(&assert.output()));
+        }
+
+        #[test]
+        fn errors_for_undeclared_outputs_in_error_mode() {
+            let sandbox = sandbox_with_mode(UndeclaredOutputsMode::Error);
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateUndeclared");
+            });
+
+            assert.failure();
+
+            assert!(predicate::str::contains("outputs/single-file/one.js").eval(&assert.output()));
+        }
+
+        #[test]
+        fn doesnt_check_when_off() {
+            let sandbox = sandbox_with_mode(UndeclaredOutputsMode::Off);
+
+            let assert = sandbox.run_moon(|cmd| {
+                cmd.arg("run").arg("outputs:generateUndeclared");
+            });
+
+            assert.success();
+
+            assert!(!predicate::str::contains("not declared as outputs").eval(&assert.output()));
+        }
+    }
+
     mod hydration {
         use super::*;
         use moon_test_utils::pretty_assertions::assert_eq;
@@ -2054,3 +2148,113 @@ mod sync_vcs_hooks {
         assert!(sandbox.path().join(".moon/hooks").exists());
     }
 }
+
+mod dry_run {
+    use super::*;
+
+    fn get_planned_target(output: &str, target: &str) -> serde_json::Value {
+        let plan: serde_json::Value = serde_json::from_str(output).unwrap();
+
+        plan["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|action| action["target"] == target)
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn doesnt_run_targets() {
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("outputs:generateFile").arg("--dry-run");
+        });
+
+        assert.success();
+
+        let output = assert.output();
+
+        assert!(predicate::str::contains("RunTarget(outputs:generateFile)").eval(&output));
+        assert!(predicate::str::contains("generate.js single-file").eval(&output));
+        assert!(!sandbox.path().join("outputs/single-file/one.js").exists());
+    }
+
+    #[test]
+    fn prints_json() {
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run")
+                .arg("outputs:generateFile")
+                .arg("--dry-run")
+                .arg("--json");
+        });
+
+        let action = get_planned_target(&assert.output(), "outputs:generateFile");
+
+        assert_eq!(action["status"], "run");
+        assert!(action["hash"].is_string());
+        assert!(action["command"]["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|arg| arg == "single-file"));
+    }
+
+    #[test]
+    fn matches_the_hash_of_a_real_run() {
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("outputs:generateFile");
+        });
+
+        let hash = extract_hash_from_run(sandbox.path(), "outputs:generateFile");
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run")
+                .arg("outputs:generateFile")
+                .arg("--dry-run")
+                .arg("--json");
+        });
+
+        let action = get_planned_target(&assert.output(), "outputs:generateFile");
+
+        assert_eq!(action["hash"], hash);
+        assert_eq!(action["status"], "previous-output");
+    }
+
+    #[test]
+    fn doesnt_hash_targets_with_cache_disabled() {
+        let sandbox = cases_sandbox();
+        sandbox.enable_git();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run")
+                .arg("outputs:noCache")
+                .arg("--dry-run")
+                .arg("--json");
+        });
+
+        let action = get_planned_target(&assert.output(), "outputs:noCache");
+
+        assert!(action["hash"].is_null());
+        assert_eq!(action["status"], "run");
+    }
+
+    #[test]
+    fn requires_dry_run_for_json() {
+        let sandbox = cases_sandbox();
+
+        let assert = sandbox.run_moon(|cmd| {
+            cmd.arg("run").arg("outputs:generateFile").arg("--json");
+        });
+
+        assert.failure();
+    }
+}
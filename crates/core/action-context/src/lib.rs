@@ -31,6 +31,8 @@ impl TargetState {
 pub struct ActionContext {
     pub affected_only: bool,
 
+    pub explain_miss: bool,
+
     pub initial_targets: FxHashSet<String>,
 
     pub interactive: bool,
@@ -45,6 +47,8 @@ pub struct ActionContext {
 
     pub touched_files: FxHashSet<WorkspaceRelativePathBuf>,
 
+    pub watch: bool,
+
     pub workspace_root: PathBuf,
 }
 
@@ -4,7 +4,7 @@ use moon_emitter::Emitter;
 use moon_logger::{debug, warn};
 use moon_platform::Runtime;
 use moon_project::Project;
-use moon_runner::Runner;
+use moon_runner::{OutputListener, Runner};
 use moon_target::Target;
 use moon_terminal::Checkpoint;
 use moon_workspace::Workspace;
@@ -23,6 +23,7 @@ pub async fn run_target(
     project: &Project,
     target: &Target,
     runtime: &Runtime,
+    output_listener: Option<OutputListener>,
 ) -> miette::Result<ActionStatus> {
     env::set_var("MOON_RUNNING_ACTION", "run-target");
 
@@ -31,6 +32,10 @@ pub async fn run_target(
     let task = project.get_task(&target.task_id)?;
     let mut runner = Runner::new(&emitter, &workspace, project, task)?;
 
+    if let Some(listener) = output_listener {
+        runner.set_output_listener(listener);
+    }
+
     debug!(
         target: LOG_TARGET,
         "Running target {}",
@@ -56,7 +61,7 @@ pub async fn run_target(
                         color::label(&task.target)
                     );
 
-                    runner.print_checkpoint(Checkpoint::RunFailed, &["skipped"])?;
+                    runner.print_checkpoint(Checkpoint::RunSkipped, &["skipped"])?;
                     runner.flush_output()?;
 
                     return Ok(ActionStatus::Skipped);
@@ -74,7 +79,9 @@ pub async fn run_target(
         let mut ctx = context.write().await;
 
         if let Some(cache_location) = runner.is_cached(&mut ctx, runtime).await? {
-            return runner.hydrate(cache_location).await;
+            if let Some(status) = runner.hydrate(cache_location).await? {
+                return Ok(status);
+            }
         }
     } else {
         debug!(
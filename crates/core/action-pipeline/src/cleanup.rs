@@ -0,0 +1,83 @@
+use crate::pipeline::ActionResults;
+use crate::processor::ActionProcessor;
+use moon_action::{Action, ActionNode};
+use moon_dep_graph::DepGraph;
+use moon_logger::{debug, error};
+use petgraph::graph::NodeIndex;
+use rustc_hash::FxHashSet;
+use starbase_styles::color;
+
+const LOG_TARGET: &str = "moon:action-pipeline:cleanup";
+
+/// Cleanup targets of the started actions, that have not been started themselves.
+pub fn get_pending_cleanup_nodes(
+    dep_graph: &DepGraph,
+    started_nodes: &FxHashSet<NodeIndex>,
+) -> Vec<NodeIndex> {
+    let mut nodes = vec![];
+
+    for index in dep_graph.get_node_indices() {
+        if !started_nodes.contains(&index) {
+            continue;
+        }
+
+        for cleanup_index in dep_graph.get_finally_of(&index) {
+            if !started_nodes.contains(&cleanup_index) && !nodes.contains(&cleanup_index) {
+                nodes.push(cleanup_index);
+            }
+        }
+    }
+
+    nodes
+}
+
+/// Run the pending cleanup targets of the started actions, before the pipeline
+/// is aborted. Their failures are only logged, so that the original error is
+/// returned by the pipeline.
+pub async fn run_pending_cleanup<F>(
+    dep_graph: &DepGraph,
+    started_nodes: &FxHashSet<NodeIndex>,
+    processor: &ActionProcessor,
+    mut create_action: F,
+    results: &mut ActionResults,
+) where
+    F: FnMut(&ActionNode) -> Action,
+{
+    for cleanup_index in get_pending_cleanup_nodes(dep_graph, started_nodes) {
+        let Some(node) = dep_graph.get_node_from_index(&cleanup_index) else {
+            continue;
+        };
+
+        let action = create_action(node);
+        let label = action.label.clone();
+
+        debug!(
+            target: LOG_TARGET,
+            "Running cleanup action {} before aborting",
+            color::muted_light(&label),
+        );
+
+        match processor.clone().process(action).await {
+            Ok(mut result) => {
+                if result.should_bail() {
+                    error!(
+                        target: LOG_TARGET,
+                        "Cleanup action {} failed: {}",
+                        color::muted_light(label),
+                        result.get_error()
+                    );
+                }
+
+                results.push(result);
+            }
+            Err(error) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Cleanup action {} failed: {}",
+                    color::muted_light(label),
+                    error
+                );
+            }
+        };
+    }
+}
@@ -0,0 +1,473 @@
+use crossterm::event::{self, Event as InputEvent, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::ExecutableCommand;
+use miette::IntoDiagnostic;
+use moon_action::{Action, ActionNode, ActionStatus};
+use moon_runner::OutputListener;
+use moon_target::Target;
+use moon_utils::time;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How often to redraw the dashboard, and to check for input.
+const TICK: Duration = Duration::from_millis(100);
+
+pub struct DashboardAction {
+    pub duration: Option<Duration>,
+
+    pub label: String,
+
+    pub logs: Vec<String>,
+
+    pub started_at: Instant,
+
+    pub status: ActionStatus,
+
+    pub target: Option<Target>,
+}
+
+impl DashboardAction {
+    pub fn is_running(&self) -> bool {
+        matches!(self.status, ActionStatus::Running)
+    }
+
+    pub fn get_elapsed(&self) -> Duration {
+        self.duration.unwrap_or_else(|| self.started_at.elapsed())
+    }
+}
+
+#[derive(Default)]
+pub struct DashboardState {
+    /// Actions that have started, in the order they started.
+    pub actions: Vec<DashboardAction>,
+
+    /// Offset of the first visible log line, or `None` to follow the end.
+    pub log_offset: Option<usize>,
+
+    /// Amount of log lines that fit on screen, as of the last draw.
+    pub log_height: usize,
+
+    /// Whether something other than the dashboard may have written to the
+    /// terminal, and the entire screen must be redrawn.
+    pub repaint: bool,
+
+    /// Index of the action to show logs for, or `None` to follow the
+    /// most recently started action.
+    pub selected: Option<usize>,
+
+    pub skipped_count: usize,
+
+    pub total_count: usize,
+}
+
+impl DashboardState {
+    pub fn new(total_count: usize) -> Self {
+        DashboardState {
+            total_count,
+            ..DashboardState::default()
+        }
+    }
+
+    pub fn start_action(&mut self, action: &Action, node: &ActionNode) {
+        self.actions.push(DashboardAction {
+            duration: None,
+            label: action.label.clone(),
+            logs: vec![],
+            started_at: Instant::now(),
+            status: ActionStatus::Running,
+            target: match node {
+                ActionNode::RunTarget(_, target)
+                | ActionNode::RunInteractiveTarget(_, target)
+                | ActionNode::RunPersistentTarget(_, target) => Some(target.to_owned()),
+                _ => None,
+            },
+        });
+    }
+
+    pub fn finish_action(&mut self, action: &Action) {
+        let Some(item) = self
+            .actions
+            .iter_mut()
+            .rev()
+            .find(|item| item.label == action.label)
+        else {
+            return;
+        };
+
+        item.duration = Some(item.started_at.elapsed());
+        item.status = action.status;
+
+        if let Some(error) = &action.error {
+            item.logs.push(console::strip_ansi_codes(error).to_string());
+        }
+
+        // Setup and install actions may write to the terminal directly
+        if item.target.is_none() {
+            self.repaint = true;
+        }
+    }
+
+    pub fn skip_action(&mut self) {
+        self.skipped_count += 1;
+    }
+
+    pub fn append_log(&mut self, target: &Target, output: &str) {
+        if let Some(item) = self
+            .actions
+            .iter_mut()
+            .rev()
+            .find(|item| item.target.as_ref() == Some(target))
+        {
+            item.logs.extend(
+                console::strip_ansi_codes(output)
+                    .lines()
+                    .map(|line| line.to_owned()),
+            );
+        }
+    }
+
+    pub fn get_running_count(&self) -> usize {
+        self.actions.iter().filter(|item| item.is_running()).count()
+    }
+
+    pub fn get_completed_count(&self) -> usize {
+        self.actions.len() - self.get_running_count()
+    }
+
+    pub fn get_cached_count(&self) -> usize {
+        self.actions
+            .iter()
+            .filter(|item| {
+                matches!(
+                    item.status,
+                    ActionStatus::Cached | ActionStatus::CachedFromRemote
+                )
+            })
+            .count()
+    }
+
+    pub fn get_failed_count(&self) -> usize {
+        self.actions
+            .iter()
+            .filter(|item| {
+                matches!(
+                    item.status,
+                    ActionStatus::Failed | ActionStatus::FailedAndAbort
+                )
+            })
+            .count()
+    }
+
+    pub fn get_queued_count(&self) -> usize {
+        self.total_count
+            .saturating_sub(self.actions.len())
+            .saturating_sub(self.skipped_count)
+    }
+
+    pub fn get_selected_index(&self) -> Option<usize> {
+        if self.selected.is_some() {
+            return self.selected;
+        }
+
+        self.actions
+            .iter()
+            .rposition(|item| item.is_running())
+            .or_else(|| self.actions.len().checked_sub(1))
+    }
+
+    /// Return the offset of the first visible log line of the selected action.
+    pub fn get_log_offset(&self) -> usize {
+        let total = self
+            .get_selected_index()
+            .map(|index| self.actions[index].logs.len())
+            .unwrap_or_default();
+        let max = total.saturating_sub(self.log_height);
+
+        self.log_offset.map(|offset| offset.min(max)).unwrap_or(max)
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(index) = self.get_selected_index() {
+            self.select(index.saturating_sub(1));
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(index) = self.get_selected_index() {
+            self.select((index + 1).min(self.actions.len() - 1));
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            self.log_offset = None;
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.log_offset = Some(self.get_log_offset().saturating_sub(self.log_height.max(1)));
+    }
+
+    pub fn scroll_down(&mut self) {
+        let offset = self.get_log_offset() + self.log_height.max(1);
+
+        self.log_offset = Some(offset);
+
+        // Follow the end again once reached
+        if self.get_log_offset() < offset {
+            self.log_offset = None;
+        }
+    }
+}
+
+fn get_status_color(status: ActionStatus) -> Color {
+    match status {
+        ActionStatus::Running => Color::Cyan,
+        ActionStatus::Passed => Color::Green,
+        ActionStatus::Cached | ActionStatus::CachedFromRemote => Color::Blue,
+        ActionStatus::Failed | ActionStatus::FailedAndAbort | ActionStatus::Invalid => Color::Red,
+        ActionStatus::Skipped => Color::DarkGray,
+    }
+}
+
+fn render<B: Backend>(frame: &mut Frame<B>, state: &mut DashboardState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(rows[1]);
+
+    // Counts
+    let muted = Style::default().fg(Color::DarkGray);
+    let count = |label: &str, value: usize, color: Color| {
+        vec![
+            Span::styled(value.to_string(), Style::default().fg(color)),
+            Span::styled(format!(" {label}  "), muted),
+        ]
+    };
+
+    frame.render_widget(
+        Paragraph::new(Line::from(
+            [
+                count("running", state.get_running_count(), Color::Cyan),
+                count("queued", state.get_queued_count(), Color::Yellow),
+                count("completed", state.get_completed_count(), Color::Green),
+                count("cached", state.get_cached_count(), Color::Blue),
+                count("failed", state.get_failed_count(), Color::Red),
+            ]
+            .concat(),
+        )),
+        rows[0],
+    );
+
+    // Actions, with the elapsed time of those running
+    let selected = state.get_selected_index();
+    let items = state
+        .actions
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if item.is_running() { "● " } else { "■ " },
+                    Style::default().fg(get_status_color(item.status)),
+                ),
+                Span::raw(item.label.clone()),
+                Span::styled(format!(" {}", time::elapsed(item.get_elapsed())), muted),
+            ]))
+        })
+        .collect::<Vec<_>>();
+    let mut list_state = ListState::default();
+    list_state.select(selected);
+
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" Actions "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        columns[0],
+        &mut list_state,
+    );
+
+    // Logs of the selected action
+    state.log_height = columns[1].height.saturating_sub(2) as usize;
+
+    let offset = state.get_log_offset();
+    let (title, lines) = match selected {
+        Some(index) => {
+            let item = &state.actions[index];
+
+            (
+                format!(" {} ", item.label),
+                item.logs
+                    .iter()
+                    .skip(offset)
+                    .take(state.log_height)
+                    .map(|line| Line::from(line.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        None => (" Logs ".into(), vec![]),
+    };
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        columns[1],
+    );
+
+    frame.render_widget(
+        Paragraph::new(Line::styled(
+            "↑/↓ select action · PgUp/PgDn scroll logs · End follow · q quit",
+            muted,
+        )),
+        rows[2],
+    );
+}
+
+/// A full-screen terminal interface that renders the progress of the
+/// pipeline, and the logs of each action, in place of the usual output.
+pub struct Dashboard {
+    log_handle: JoinHandle<()>,
+
+    log_sender: OutputListener,
+
+    render_handle: Option<std::thread::JoinHandle<io::Result<()>>>,
+
+    state: Arc<Mutex<DashboardState>>,
+
+    stop_token: CancellationToken,
+}
+
+impl Dashboard {
+    /// Take over the terminal and start rendering. Quitting the dashboard
+    /// will cancel the provided token, which aborts the pipeline.
+    pub fn start(total_count: usize, cancel_token: CancellationToken) -> miette::Result<Self> {
+        let state = Arc::new(Mutex::new(DashboardState::new(total_count)));
+        let stop_token = CancellationToken::new();
+
+        // Collect output from the runner
+        let (log_sender, mut log_receiver) = mpsc::unbounded_channel::<(Target, String)>();
+        let log_state = Arc::clone(&state);
+        let log_handle = tokio::spawn(async move {
+            while let Some((target, line)) = log_receiver.recv().await {
+                log_state.lock().unwrap().append_log(&target, &line);
+            }
+        });
+
+        enable_raw_mode().into_diagnostic()?;
+        io::stdout()
+            .execute(EnterAlternateScreen)
+            .into_diagnostic()?;
+
+        let render_state = Arc::clone(&state);
+        let render_stop_token = stop_token.clone();
+        let render_handle = std::thread::spawn(move || {
+            let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+            terminal.hide_cursor()?;
+
+            while !render_stop_token.is_cancelled() {
+                {
+                    let mut state = render_state.lock().unwrap();
+
+                    if state.repaint {
+                        state.repaint = false;
+                        terminal.clear()?;
+                    }
+
+                    terminal.draw(|frame| render(frame, &mut state))?;
+                }
+
+                if !event::poll(TICK)? {
+                    continue;
+                }
+
+                let InputEvent::Key(key) = event::read()? else {
+                    continue;
+                };
+
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                let mut state = render_state.lock().unwrap();
+
+                match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        cancel_token.cancel();
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        cancel_token.cancel();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => state.select_previous(),
+                    KeyCode::Down | KeyCode::Char('j') => state.select_next(),
+                    KeyCode::PageUp => state.scroll_up(),
+                    KeyCode::PageDown => state.scroll_down(),
+                    KeyCode::End => {
+                        state.selected = None;
+                        state.log_offset = None;
+                    }
+                    _ => {}
+                };
+            }
+
+            terminal.show_cursor()?;
+
+            Ok(())
+        });
+
+        Ok(Dashboard {
+            log_handle,
+            log_sender,
+            render_handle: Some(render_handle),
+            state,
+            stop_token,
+        })
+    }
+
+    pub fn get_output_listener(&self) -> OutputListener {
+        self.log_sender.clone()
+    }
+
+    pub fn get_state(&self) -> Arc<Mutex<DashboardState>> {
+        Arc::clone(&self.state)
+    }
+
+    /// Stop rendering, and restore the terminal.
+    pub fn stop(&mut self) {
+        let Some(handle) = self.render_handle.take() else {
+            return;
+        };
+
+        self.stop_token.cancel();
+        self.log_handle.abort();
+
+        let _ = handle.join();
+        let _ = io::stdout().execute(LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,164 @@
+use crate::pipeline::get_node_target;
+use moon_action::ActionNode;
+use moon_process::Command;
+use moon_target::Target;
+use serde::Serialize;
+use starbase_styles::color;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlannedStatus {
+    /// The tool has not been installed, and would be.
+    Install,
+
+    /// Outputs would be hydrated from the local cache.
+    LocalCache,
+
+    /// Outputs of the previous run still exist, and would be reused.
+    PreviousOutput,
+
+    /// Outputs would be downloaded from the remote cache.
+    RemoteCache,
+
+    /// The action would run.
+    Run,
+
+    /// The action would be skipped, because its task condition was not met,
+    /// or because one of its dependencies would be skipped.
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedCommand {
+    /// The fully expanded command line, including the shell (if any).
+    pub args: Vec<String>,
+
+    pub cwd: Option<PathBuf>,
+
+    pub env: BTreeMap<String, String>,
+}
+
+impl PlannedCommand {
+    pub fn new(command: &Command) -> Self {
+        PlannedCommand {
+            args: command
+                .inspect()
+                .get_command_line()
+                .command
+                .iter()
+                .map(|arg| command.mask_secrets(arg))
+                .collect(),
+            cwd: command.cwd.clone(),
+            env: command
+                .env
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_string_lossy().to_string(),
+                        command.mask_secrets(&value.to_string_lossy()),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedAction {
+    /// Batch in which the action would run, starting at 1.
+    pub batch: usize,
+
+    pub command: Option<PlannedCommand>,
+
+    pub hash: Option<String>,
+
+    pub label: String,
+
+    /// Why the action would be skipped, if applicable.
+    pub skip_reason: Option<String>,
+
+    pub status: PlannedStatus,
+
+    pub target: Option<Target>,
+}
+
+impl PlannedAction {
+    pub fn new(batch: usize, node: &ActionNode) -> Self {
+        PlannedAction {
+            batch,
+            command: None,
+            hash: None,
+            label: node.label(),
+            skip_reason: None,
+            status: PlannedStatus::Run,
+            target: get_node_target(node).cloned(),
+        }
+    }
+}
+
+/// What a pipeline would do, in the order it would do it, without running anything.
+#[derive(Debug, Default, Serialize)]
+pub struct DryRun {
+    pub actions: Vec<PlannedAction>,
+}
+
+impl DryRun {
+    pub fn render(&self) -> String {
+        let mut lines = vec![];
+        let mut batch = 0;
+
+        for action in &self.actions {
+            if action.batch != batch {
+                batch = action.batch;
+
+                if !lines.is_empty() {
+                    lines.push(String::new());
+                }
+
+                lines.push(color::muted(format!("Batch {batch}")));
+            }
+
+            let status = match action.status {
+                PlannedStatus::Install => color::muted_light("would install"),
+                PlannedStatus::LocalCache => color::success("cached"),
+                PlannedStatus::PreviousOutput => color::success("cached from previous run"),
+                PlannedStatus::RemoteCache => color::success("cached from remote"),
+                PlannedStatus::Run => color::muted_light("run"),
+                PlannedStatus::Skipped => color::muted(format!(
+                    "skipped, {}",
+                    action.skip_reason.as_deref().unwrap_or_default()
+                )),
+            };
+
+            lines.push(match &action.hash {
+                Some(hash) => format!(
+                    "  {} {} {}",
+                    color::label(&action.label),
+                    status,
+                    color::hash(hash)
+                ),
+                None => format!("  {} {}", color::label(&action.label), status),
+            });
+
+            let Some(command) = &action.command else {
+                continue;
+            };
+
+            lines.push(format!("    {}", color::shell(command.args.join(" "))));
+
+            if let Some(cwd) = &command.cwd {
+                lines.push(format!("    {} {}", color::muted("cwd"), color::path(cwd)));
+            }
+
+            for (key, value) in &command.env {
+                lines.push(format!("    {} {key}={value}", color::muted("env")));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
@@ -1,9 +1,15 @@
 mod actions;
+mod cleanup;
+pub mod dashboard;
+pub mod dry_run;
 mod errors;
 pub mod estimator;
 mod pipeline;
 mod processor;
-mod run_report;
+mod resource_locks;
+pub mod run_report;
+pub mod scheduler;
+mod skip;
 mod subscribers;
 
 pub use errors::*;
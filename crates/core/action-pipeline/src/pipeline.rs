@@ -1,24 +1,43 @@
+use crate::cleanup::run_pending_cleanup;
+use crate::dashboard::Dashboard;
+use crate::dry_run::{DryRun, PlannedAction, PlannedCommand, PlannedStatus};
 use crate::errors::PipelineError;
 use crate::estimator::Estimator;
-use crate::processor::process_action;
+use crate::processor::ActionProcessor;
+use crate::resource_locks::ResourceLocks;
 use crate::run_report::RunReport;
+use crate::scheduler::Scheduler;
+use crate::skip::{skip_action, SkippedNodes};
+use crate::subscribers::dashboard::DashboardSubscriber;
 use crate::subscribers::local_cache::LocalCacheSubscriber;
 use crate::subscribers::moonbase::MoonbaseSubscriber;
+use crate::subscribers::ready_check::ReadyCheckSubscriber;
+use crate::subscribers::reapi_cache::ReapiCacheSubscriber;
+use crate::subscribers::remote_cache::RemoteCacheSubscriber;
 use console::Term;
 use moon_action::{Action, ActionNode, ActionStatus};
-use moon_action_context::ActionContext;
+use moon_action_context::{ActionContext, TargetState};
 use moon_dep_graph::DepGraph;
 use moon_emitter::{Emitter, Event};
-use moon_logger::{debug, error, trace};
-use moon_notifier::WebhooksSubscriber;
+use moon_logger::{debug, error, trace, warn};
+use moon_notifier::{collect_secrets, to_masked_json, OpenTelemetrySubscriber, WebhooksSubscriber};
+use moon_platform::{PlatformManager, Runtime};
+use moon_process::mask_secrets;
 use moon_project_graph::ProjectGraph;
+use moon_remote_cache::{create_remote_cache, ReapiRemoteCache};
+use moon_runner::{HydrateFrom, Runner};
+use moon_target::Target;
 use moon_terminal::{label_checkpoint, label_to_the_moon, Checkpoint, ExtendedTerm};
 use moon_utils::{is_ci, is_test_env, time};
 use moon_workspace::Workspace;
+use petgraph::graph::NodeIndex;
+use rustc_hash::{FxHashMap, FxHashSet};
 use starbase_styles::color;
+use starbase_utils::fs;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 
 const LOG_TARGET: &str = "moon:action-pipeline";
@@ -30,6 +49,8 @@ pub struct Pipeline {
 
     concurrency: Option<usize>,
 
+    dashboard: bool,
+
     duration: Option<Duration>,
 
     project_graph: Arc<RwLock<ProjectGraph>>,
@@ -41,11 +62,21 @@ pub struct Pipeline {
 
 impl Pipeline {
     pub fn new(workspace: Workspace, project_graph: ProjectGraph) -> Self {
+        Self::with_shared_graph(workspace, Arc::new(RwLock::new(project_graph)))
+    }
+
+    /// Create a pipeline that shares an in-memory project graph with other
+    /// pipelines, for example, when re-running targets in watch mode.
+    pub fn with_shared_graph(
+        workspace: Workspace,
+        project_graph: Arc<RwLock<ProjectGraph>>,
+    ) -> Self {
         Pipeline {
             bail: false,
             concurrency: None,
+            dashboard: false,
             duration: None,
-            project_graph: Arc::new(RwLock::new(project_graph)),
+            project_graph,
             report_name: None,
             workspace: Arc::new(RwLock::new(workspace)),
         }
@@ -61,6 +92,13 @@ impl Pipeline {
         self
     }
 
+    /// Render a full-screen dashboard while running, instead of the usual
+    /// output. This is ignored when stdout is not a terminal.
+    pub fn show_dashboard(&mut self) -> &mut Self {
+        self.dashboard = true;
+        self
+    }
+
     pub fn generate_report(&mut self, name: &str) -> &mut Self {
         self.report_name = Some(name.to_owned());
         self
@@ -73,26 +111,62 @@ impl Pipeline {
     ) -> miette::Result<ActionResults> {
         let start = Instant::now();
         let context = Arc::new(RwLock::new(context.unwrap_or_default()));
-        let emitter = Arc::new(RwLock::new(
-            create_emitter(Arc::clone(&self.workspace)).await,
-        ));
+        let cancel_token = CancellationToken::new();
+        let (ready_sender, mut ready_receiver) = mpsc::unbounded_channel();
+        let secrets = collect_pipeline_secrets(
+            &dep_graph,
+            &*self.project_graph.read().await,
+            &*self.workspace.read().await,
+        )?;
+        let mut emitter = create_emitter(Arc::clone(&self.workspace), &secrets).await;
+
+        emitter.subscribers.insert(
+            0,
+            Arc::new(RwLock::new(ReadyCheckSubscriber::new(ready_sender))),
+        );
+
+        let mut dashboard =
+            self.start_dashboard(&mut emitter, dep_graph.get_node_count(), &cancel_token)?;
+
+        let emitter = Arc::new(RwLock::new(emitter));
         let workspace = Arc::clone(&self.workspace);
         let project_graph = Arc::clone(&self.project_graph);
+        let processor = ActionProcessor {
+            context: Arc::clone(&context),
+            emitter: Arc::clone(&emitter),
+            output_listener: dashboard
+                .as_ref()
+                .map(|dashboard| dashboard.get_output_listener()),
+            project_graph: Arc::clone(&project_graph),
+            workspace: Arc::clone(&workspace),
+        };
         let mut results: ActionResults = vec![];
         let mut passed_count = 0;
         let mut cached_count = 0;
         let mut failed_count = 0;
 
-        // Queue actions in topological order that need to be processed,
-        // grouped into batches based on dependency requirements
+        // Schedule actions as soon as their dependencies have completed,
+        // prioritizing those on the critical path based on previous runs
         let total_actions_count = dep_graph.get_node_count();
-        let batches = dep_graph.sort_batched_topological()?;
-        let batches_count = batches.len();
+        let duration_history = workspace.read().await.cache_engine.get_target_durations()?;
+        let ready_checked = get_ready_checked_nodes(&dep_graph, &*project_graph.read().await)?;
+        let mut scheduler = Scheduler::new(
+            &dep_graph,
+            &duration_history,
+            &ready_checked.values().copied().collect(),
+        )?;
+        let resource_locks = ResourceLocks::new(
+            dep_graph
+                .get_node_indices()
+                .iter()
+                .filter_map(|index| dep_graph.get_node_from_index(index)),
+            &*project_graph.read().await,
+        )?;
         let local_emitter = emitter.read().await;
 
         debug!(
             target: LOG_TARGET,
-            "Running {} actions across {} batches", total_actions_count, batches_count
+            "Running {} actions, prioritized by critical path", total_actions_count
         );
 
         local_emitter
@@ -103,7 +177,6 @@ impl Pipeline {
             .await?;
 
         // Launch a separate thread to listen for ctrl+c
-        let cancel_token = CancellationToken::new();
         let ctrl_c_token = cancel_token.clone();
 
         tokio::spawn(async move {
@@ -113,124 +186,207 @@ impl Pipeline {
         });
 
         // This limits how many tasks can run in parallel
-        let semaphore = Arc::new(Semaphore::new(
-            self.concurrency.unwrap_or_else(num_cpus::get),
-        ));
-
-        for (b, batch) in batches.into_iter().enumerate() {
-            let batch_index = b + 1;
-            let batch_target_name = format!("{LOG_TARGET}:batch:{batch_index}");
-            let actions_count = batch.len();
-            let mut action_handles = vec![];
-
-            trace!(
-                target: &batch_target_name,
-                "Running {} actions in batch {}",
-                actions_count,
-                batch_index
-            );
+        let concurrency = self.concurrency.unwrap_or_else(num_cpus::get);
+        let mut action_handles = JoinSet::new();
+        let mut action_index = 0;
+        let mut create_action = |node: &ActionNode| {
+            action_index += 1;
+
+            let mut action = Action::new(node.to_owned());
+            action.log_target = format!("{LOG_TARGET}:{action_index}");
+            action
+        };
+
+        let mut skipped_nodes = SkippedNodes::new(&dep_graph);
+
+        // Actions that have been started, so that their cleanup targets can run when bailing
+        let mut started_nodes = FxHashSet::<NodeIndex>::default();
+
+        loop {
+            while action_handles.len() < concurrency {
+                // Skip actions that are waiting on a resource, so that they
+                // don't take up a slot that another action could use
+                let Some((node_index, resource_permits)) = scheduler.next(|index| {
+                    dep_graph
+                        .get_node_from_index(&index)
+                        .and_then(|node| resource_locks.try_acquire(node))
+                }) else {
+                    break;
+                };
+
+                let Some(node) = dep_graph.get_node_from_index(&node_index) else {
+                    return Err(PipelineError::UnknownActionNode.into());
+                };
+
+                // Don't run actions with an unmet condition,
+                // or against the stale outputs of a failed dependency
+                if let Some(reason) = skipped_nodes.take(&node_index) {
+                    skipped_nodes.skip_dependents(&dep_graph, &scheduler, node_index, || {
+                        format!("dependency {} was skipped", get_node_name(node))
+                    });
+
+                    results.push(skip_action(node, reason, &context, dashboard.as_ref()).await?);
+                    scheduler.complete(node_index);
+
+                    continue;
+                }
+
+                let processor_clone = processor.clone();
+                let cancel_token_clone = cancel_token.clone();
+                let action = create_action(node);
 
-            for (i, node_index) in batch.into_iter().enumerate() {
-                let action_index = i + 1;
+                started_nodes.insert(node_index);
 
-                if let Some(node) = dep_graph.get_node_from_index(&node_index) {
-                    let context_clone = Arc::clone(&context);
-                    let emitter_clone = Arc::clone(&emitter);
-                    let workspace_clone = Arc::clone(&workspace);
-                    let project_graph_clone = Arc::clone(&project_graph);
-                    let cancel_token_clone = cancel_token.clone();
+                trace!(
+                    target: &action.log_target,
+                    "Starting action {} (critical path {:?})",
+                    color::muted_light(&action.label),
+                    scheduler.get_priority(&node_index)
+                );
 
-                    let mut action = Action::new(node.to_owned());
-                    action.log_target = format!("{batch_target_name}:{action_index}");
+                action_handles.spawn(async move {
+                    let result = tokio::select! {
+                        biased;
 
-                    let Ok(permit) = semaphore.clone().acquire_owned().await else {
-                        break; // Should error?
+                        _ = cancel_token_clone.cancelled() => {
+                            Err(PipelineError::Aborted("Received ctrl + c, shutting down".into()).into())
+                        }
+                        res = processor_clone.process(action) => res
                     };
 
-                    action_handles.push(tokio::spawn(async move {
-                        let result = tokio::select! {
-                            biased;
-
-                            _ = cancel_token_clone.cancelled() => {
-                                Err(PipelineError::Aborted("Received ctrl + c, shutting down".into()).into())
-                            }
-                            res = process_action(
-                                action,
-                                context_clone,
-                                emitter_clone,
-                                workspace_clone,
-                                project_graph_clone,
-                            ) => res
-                        };
-
-                        drop(permit);
-
-                        result
-                    }));
-                } else {
-                    return Err(PipelineError::UnknownActionNode.into());
-                }
+                    drop(resource_permits);
+
+                    (node_index, result)
+                });
             }
 
-            // Wait for all actions in this batch to complete
+            // Wait for the next action to complete, or for a persistent
+            // action to become ready, which may unblock its dependents
+            let handle = tokio::select! {
+                handle = action_handles.join_next() => handle,
+                Some(target) = ready_receiver.recv() => {
+                    if let Some(index) = ready_checked.get(&target) {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Target {} is ready, releasing its dependents",
+                            color::label(&target),
+                        );
+
+                        scheduler.ready(*index);
+                    }
+
+                    continue;
+                }
+            };
+
+            let Some(handle) = handle else {
+                break;
+            };
+
             let mut abort_error: Option<miette::Report> = None;
             let mut show_abort_log = false;
-
-            for handle in action_handles {
-                if abort_error.is_some() {
-                    if !handle.is_finished() {
-                        handle.abort();
+            let mut run_cleanup = false;
+
+            match handle {
+                Ok((node_index, Ok(mut result))) => {
+                    scheduler.complete(node_index);
+
+                    if result.has_failed() {
+                        failed_count += 1;
+                    } else if result.was_cached() {
+                        cached_count += 1;
+                    } else {
+                        passed_count += 1;
                     }
-                } else {
-                    match handle.await {
-                        Ok(Ok(mut result)) => {
-                            if result.has_failed() {
-                                failed_count += 1;
-                            } else if result.was_cached() {
-                                cached_count += 1;
-                            } else {
-                                passed_count += 1;
-                            }
 
-                            show_abort_log = result.should_abort();
+                    show_abort_log = result.should_abort();
+
+                    if self.bail && result.should_bail() || result.should_abort() {
+                        abort_error = Some(result.get_error());
+                        run_cleanup = true;
+                    } else if result.should_bail() {
+                        // Continue running everything that is not downstream of the failure
+                        let name = result
+                            .node
+                            .as_ref()
+                            .map_or(result.label.clone(), get_node_name);
+
+                        skipped_nodes.skip_dependents(&dep_graph, &scheduler, node_index, || {
+                            format!("dependency {name} failed")
+                        });
+                    }
 
-                            if self.bail && result.should_bail() || result.should_abort() {
-                                abort_error = Some(result.get_error());
-                            } else {
-                                results.push(result);
-                            }
-                        }
-                        Ok(Err(error)) => {
-                            abort_error = Some(error);
-                        }
-                        _ => {
-                            abort_error =
-                                Some(PipelineError::Aborted("Unknown error!".into()).into());
-                        }
-                    };
+                    results.push(result);
                 }
-            }
+                // Cancelled (ctrl + c) or errored actions abort the pipeline,
+                // but cleanup targets must still run
+                Ok((_, Err(error))) => {
+                    abort_error = Some(error);
+                    run_cleanup = true;
+                }
+                _ => {
+                    abort_error = Some(PipelineError::Aborted("Unknown error!".into()).into());
+                    run_cleanup = true;
+                }
+            };
 
             if let Some(abort_error) = abort_error {
+                action_handles.abort_all();
+
                 if show_abort_log {
                     error!(
-                        target: &batch_target_name,
+                        target: LOG_TARGET,
                         "Encountered a critical error, aborting the action pipeline"
                     );
                 }
 
+                if run_cleanup {
+                    run_pending_cleanup(
+                        &dep_graph,
+                        &started_nodes,
+                        &processor,
+                        &mut create_action,
+                        &mut results,
+                    )
+                    .await;
+                }
+
                 local_emitter
                     .emit(Event::PipelineAborted {
                         error: abort_error.to_string(),
                     })
                     .await?;
 
+                // Reports are most useful when something failed, so write
+                // them with the results so far, before returning the error
+                let context = context.read().await.clone();
+
+                self.write_aborted_report(&results, &context, start.elapsed(), &secrets)
+                    .await;
+
                 return Err(abort_error);
             }
         }
 
+        if let Some(dashboard) = &mut dashboard {
+            dashboard.stop();
+        }
+
+        if !scheduler.is_complete() {
+            return Err(PipelineError::Aborted(
+                "Unable to schedule all actions, some were never started".into(),
+            )
+            .into());
+        }
+
+        track_ran_durations(&results, &*workspace.read().await)?;
+
         let duration = start.elapsed();
         let estimate = Estimator::calculate(&results, duration);
+
+        // The processor holds a reference to the context
+        drop(processor);
+
         let context = Arc::into_inner(context).unwrap().into_inner();
 
         debug!(
@@ -251,15 +407,197 @@ impl Pipeline {
             .await?;
 
         self.duration = Some(duration);
-        self.create_run_report(&results, &context, estimate).await?;
+        self.create_run_report(&results, &context, estimate, &secrets)
+            .await?;
 
         Ok(results)
     }
 
+    /// Start the dashboard when enabled, and stdout is a terminal,
+    /// and subscribe it to the pipeline's events.
+    fn start_dashboard(
+        &self,
+        emitter: &mut Emitter,
+        actions_count: usize,
+        cancel_token: &CancellationToken,
+    ) -> miette::Result<Option<Dashboard>> {
+        if !self.dashboard || !Term::stdout().is_term() {
+            return Ok(None);
+        }
+
+        let dashboard = Dashboard::start(actions_count, cancel_token.clone())?;
+
+        emitter.subscribers.insert(
+            0,
+            Arc::new(RwLock::new(DashboardSubscriber::new(dashboard.get_state()))),
+        );
+
+        Ok(Some(dashboard))
+    }
+
+    /// Write the run report with the results so far, but don't let
+    /// a failure to do so replace the error that aborted the pipeline.
+    async fn write_aborted_report(
+        &mut self,
+        results: &ActionResults,
+        context: &ActionContext,
+        duration: Duration,
+        secrets: &[String],
+    ) {
+        let estimate = Estimator::calculate(results, duration);
+
+        self.duration = Some(duration);
+
+        if let Err(report_error) = self
+            .create_run_report(results, context, estimate, secrets)
+            .await
+        {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to write run report: {}", report_error
+            );
+        }
+    }
+
+    /// Plan the actions in the graph without running them. Targets are hashed
+    /// and checked against the cache, and their commands are resolved, but
+    /// nothing is executed, installed, or written to the cache.
+    pub async fn dry_run(
+        &mut self,
+        dep_graph: DepGraph,
+        context: Option<ActionContext>,
+    ) -> miette::Result<DryRun> {
+        let context = Arc::new(RwLock::new(context.unwrap_or_default()));
+        let mut emitter = Emitter::new(Arc::clone(&self.workspace));
+
+        add_cache_subscribers(&mut emitter, &*self.workspace.read().await);
+
+        let project_graph = self.project_graph.read().await;
+        let mut plan = DryRun::default();
+        let mut skipped_nodes = FxHashSet::<NodeIndex>::default();
+        let mut missing_tools = vec![];
+
+        debug!(
+            target: LOG_TARGET,
+            "Planning {} actions without running them",
+            dep_graph.get_node_count()
+        );
+
+        for (batch_index, batch) in dep_graph
+            .sort_batched_topological()?
+            .into_iter()
+            .enumerate()
+        {
+            for node_index in batch {
+                let Some(node) = dep_graph.get_node_from_index(&node_index) else {
+                    return Err(PipelineError::UnknownActionNode.into());
+                };
+
+                let mut planned = PlannedAction::new(batch_index + 1, node);
+
+                // Mirror the skipping behavior of the pipeline, where cleanup
+                // targets still run when their owner is skipped
+                let skip_reason = match dep_graph.get_unmet_conditions().get(&node_index) {
+                    Some(reason) => Some(format!("condition not met, {reason}")),
+                    None => dep_graph
+                        .get_dependencies_of(&node_index)
+                        .into_iter()
+                        .find(|dep_index| {
+                            skipped_nodes.contains(dep_index)
+                                && !dep_graph.is_finally_edge(&node_index, dep_index)
+                        })
+                        .and_then(|dep_index| dep_graph.get_node_from_index(&dep_index))
+                        .map(|dep| format!("dependency {} was skipped", get_node_name(dep))),
+                };
+
+                if let Some(reason) = skip_reason {
+                    planned.status = PlannedStatus::Skipped;
+                    planned.skip_reason = Some(reason);
+                    plan.actions.push(planned);
+                    skipped_nodes.insert(node_index);
+
+                    continue;
+                }
+
+                match node {
+                    // Tools must exist to resolve the commands of targets,
+                    // but are never installed, only reported as such
+                    ActionNode::SetupTool(runtime) => {
+                        if !matches!(runtime, Runtime::System)
+                            && !PlatformManager::write()
+                                .get_mut(runtime)?
+                                .is_tool_setup(runtime)
+                                .await?
+                        {
+                            planned.status = PlannedStatus::Install;
+                            missing_tools.push(runtime.to_owned());
+                        }
+                    }
+                    ActionNode::RunTarget(runtime, target)
+                    | ActionNode::RunInteractiveTarget(runtime, target)
+                    | ActionNode::RunPersistentTarget(runtime, target) => {
+                        let workspace = self.workspace.read().await;
+                        let project = project_graph.get(target.scope_id.as_ref().unwrap())?;
+                        let task = project.get_task(&target.task_id)?;
+                        let runner = Runner::new(&emitter, &workspace, &project, task)?;
+                        let mut context = context.write().await;
+
+                        if task.options.cache && workspace.vcs.is_enabled() {
+                            let (hash, hasher) = runner.generate_hash(&context, runtime).await?;
+
+                            context
+                                .target_states
+                                .insert(target.clone(), TargetState::Completed(hash.clone()));
+
+                            // Remote caches require the manifest, so keep it in memory
+                            workspace.hash_engine.hold_manifest(hasher)?;
+
+                            planned.status = if runner.is_previous_output(&hash)? {
+                                PlannedStatus::PreviousOutput
+                            } else {
+                                match runner.check_output_cache(&hash).await? {
+                                    Some(HydrateFrom::LocalCache) => PlannedStatus::LocalCache,
+                                    Some(HydrateFrom::RemoteCache) => PlannedStatus::RemoteCache,
+                                    _ => PlannedStatus::Run,
+                                }
+                            };
+                            planned.hash = Some(hash);
+                        } else {
+                            context
+                                .target_states
+                                .insert(target.clone(), TargetState::Passthrough);
+                        }
+
+                        // Commands can't be resolved until their tool is installed
+                        if !missing_tools.contains(runtime) {
+                            planned.command = Some(PlannedCommand::new(
+                                &runner.create_command(&context, runtime).await?,
+                            ));
+                        }
+                    }
+                    _ => {}
+                };
+
+                plan.actions.push(planned);
+            }
+        }
+
+        Ok(plan)
+    }
+
     pub fn render_summary(&self, results: &ActionResults) -> miette::Result<()> {
         let term = Term::buffered_stdout();
         term.line("")?;
 
+        let get_label = |result: &Action| {
+            result
+                .node
+                .as_ref()
+                .and_then(get_node_target)
+                .map(|target| target.as_str().to_owned())
+                .unwrap_or_else(|| result.label.clone())
+        };
+
         let mut count = 0;
 
         for result in results {
@@ -267,15 +605,7 @@ impl Pipeline {
                 continue;
             }
 
-            term.line(label_checkpoint(
-                match &result.node {
-                    Some(ActionNode::RunTarget(_, target)) => target.as_str(),
-                    Some(ActionNode::RunInteractiveTarget(_, target)) => target.as_str(),
-                    Some(ActionNode::RunPersistentTarget(_, target)) => target.as_str(),
-                    _ => &result.label,
-                },
-                Checkpoint::RunFailed,
-            ))?;
+            term.line(label_checkpoint(get_label(result), Checkpoint::RunFailed))?;
 
             if let Some(attempts) = &result.attempts {
                 if let Some(attempt) = attempts.iter().find(|a| a.has_failed()) {
@@ -308,6 +638,28 @@ impl Pipeline {
             term.line("No failed actions to summarize.")?;
         }
 
+        // List skipped actions separately, as they never ran
+        let mut skipped_count = 0;
+
+        for result in results {
+            let Some(reason) = &result.skipped_reason else {
+                continue;
+            };
+
+            if skipped_count == 0 {
+                term.line("")?;
+                term.line("Skipped actions:")?;
+            }
+
+            term.line(format!(
+                "{} {}",
+                label_checkpoint(get_label(result), Checkpoint::RunSkipped),
+                color::muted(format!("({reason})"))
+            ))?;
+
+            skipped_count += 1;
+        }
+
         term.line("")?;
         term.flush_lines()?;
 
@@ -458,21 +810,133 @@ impl Pipeline {
         actions: &ActionResults,
         context: &ActionContext,
         estimate: Estimator,
+        secrets: &[String],
     ) -> miette::Result<()> {
         if let Some(name) = &self.report_name {
             let workspace = self.workspace.read().await;
             let duration = self.duration.unwrap();
 
-            workspace
-                .cache_engine
-                .write(name, &RunReport::new(actions, context, duration, estimate))?;
+            let report = RunReport::new(actions, context, duration, estimate);
+            let cache_dir = &workspace.cache_engine.cache_dir;
+            let stem = name.strip_suffix(".json").unwrap_or(name);
+
+            // Passthrough args and outputs may contain secrets
+            fs::write_file(cache_dir.join(name), to_masked_json(&report, secrets))?;
+
+            // Also export in formats that other tools understand
+            fs::write_file(
+                cache_dir.join(format!("{stem}.junit.xml")),
+                mask_secrets(&report.to_junit_xml(), secrets),
+            )?;
+
+            fs::write_file(
+                cache_dir.join(format!("{stem}.trace.json")),
+                to_masked_json(&report.to_chrome_trace(), secrets),
+            )?;
         }
 
         Ok(())
     }
 }
 
-async fn create_emitter(workspace: Arc<RwLock<Workspace>>) -> Emitter {
+/// Record how long each target took to run, so that
+/// future runs can prioritize the critical path.
+fn track_ran_durations(results: &ActionResults, workspace: &Workspace) -> miette::Result<()> {
+    let mut ran_durations = vec![];
+
+    for result in results {
+        if let (Some(ActionNode::RunTarget(_, target)), Some(duration)) =
+            (&result.node, &result.duration)
+        {
+            if matches!(result.status, ActionStatus::Passed | ActionStatus::Failed) {
+                ran_durations.push((target.id.clone(), *duration));
+            }
+        }
+    }
+
+    workspace
+        .cache_engine
+        .track_target_durations(&ran_durations)
+}
+
+/// Persistent targets with a ready check, mapped to their node.
+fn get_ready_checked_nodes(
+    dep_graph: &DepGraph,
+    project_graph: &ProjectGraph,
+) -> miette::Result<FxHashMap<Target, NodeIndex>> {
+    let mut nodes = FxHashMap::default();
+
+    for index in dep_graph.get_node_indices() {
+        if let Some(ActionNode::RunPersistentTarget(_, target)) =
+            dep_graph.get_node_from_index(&index)
+        {
+            let project = project_graph.get(target.scope_id.as_ref().unwrap())?;
+
+            if project
+                .get_task(&target.task_id)?
+                .options
+                .ready_check
+                .is_some()
+            {
+                nodes.insert(target.to_owned(), index);
+            }
+        }
+    }
+
+    Ok(nodes)
+}
+
+pub(crate) fn get_node_target(node: &ActionNode) -> Option<&Target> {
+    match node {
+        ActionNode::RunTarget(_, target)
+        | ActionNode::RunInteractiveTarget(_, target)
+        | ActionNode::RunPersistentTarget(_, target) => Some(target),
+        _ => None,
+    }
+}
+
+// Targets are more recognizable than action labels in messages
+pub(crate) fn get_node_name(node: &ActionNode) -> String {
+    get_node_target(node).map_or_else(|| node.label(), |target| target.id.clone())
+}
+
+/// Secret values of the tasks within every project in the graph, so that they
+/// can be masked in webhook payloads and run reports.
+fn collect_pipeline_secrets(
+    dep_graph: &DepGraph,
+    project_graph: &ProjectGraph,
+    workspace: &Workspace,
+) -> miette::Result<Vec<String>> {
+    let mut project_ids = FxHashSet::default();
+
+    for index in dep_graph.get_node_indices() {
+        let Some(node) = dep_graph.get_node_from_index(&index) else {
+            continue;
+        };
+
+        match node {
+            ActionNode::InstallProjectDeps(_, id) | ActionNode::SyncProject(_, id) => {
+                project_ids.insert(id.to_owned());
+            }
+            _ => {
+                if let Some(id) = get_node_target(node).and_then(|target| target.scope_id.as_ref())
+                {
+                    project_ids.insert(id.to_owned());
+                }
+            }
+        };
+    }
+
+    let mut projects = vec![];
+
+    for id in project_ids {
+        projects.push(project_graph.get(&id)?);
+    }
+
+    collect_secrets(projects.iter().map(|project| project.as_ref()), workspace)
+}
+
+async fn create_emitter(workspace: Arc<RwLock<Workspace>>, secrets: &[String]) -> Emitter {
     let mut emitter = Emitter::new(Arc::clone(&workspace));
 
     {
@@ -485,21 +949,70 @@ async fn create_emitter(workspace: Arc<RwLock<Workspace>>) -> Emitter {
                     .subscribers
                     .push(Arc::new(RwLock::new(WebhooksSubscriber::new(
                         webhook_url.to_owned(),
+                        secrets.to_vec(),
                     ))));
             }
         }
 
-        if local_workspace.session.is_some() {
+        // Before cache subscribers, as they may stop propagation of cache events
+        if local_workspace.config.open_telemetry.is_enabled() {
             emitter
                 .subscribers
-                .push(Arc::new(RwLock::new(MoonbaseSubscriber::new())));
+                .push(Arc::new(RwLock::new(OpenTelemetrySubscriber::new(
+                    local_workspace.config.open_telemetry.clone(),
+                ))));
         }
     }
 
+    add_cache_subscribers(&mut emitter, &*workspace.read().await);
+
+    emitter
+}
+
+/// Add subscribers that check for, hydrate, and archive outputs.
+fn add_cache_subscribers(emitter: &mut Emitter, workspace: &Workspace) {
+    if workspace.session.is_some() {
+        emitter
+            .subscribers
+            .push(Arc::new(RwLock::new(MoonbaseSubscriber::new())));
+    }
+
+    // If the remote cache can't be created, continue with a local-only cache
+    match create_remote_cache(&workspace.config.remote_cache, &workspace.root) {
+        Ok(Some(backend)) => {
+            emitter
+                .subscribers
+                .push(Arc::new(RwLock::new(RemoteCacheSubscriber::new(backend))));
+        }
+        Ok(None) => {}
+        Err(error) => {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to create remote cache, falling back to local cache only. Failure: {}",
+                color::muted_light(error.to_string())
+            );
+        }
+    };
+
+    if let Some(reapi_config) = &workspace.config.remote_cache.reapi {
+        match ReapiRemoteCache::new(reapi_config) {
+            Ok(cache) => {
+                emitter
+                    .subscribers
+                    .push(Arc::new(RwLock::new(ReapiCacheSubscriber::new(cache))));
+            }
+            Err(error) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to create REAPI remote cache, falling back to local cache only. Failure: {}",
+                    color::muted_light(error.to_string())
+                );
+            }
+        };
+    }
+
     // Must be last as its the final line of defense
     emitter
         .subscribers
         .push(Arc::new(RwLock::new(LocalCacheSubscriber::new())));
-
-    emitter
 }
@@ -8,6 +8,7 @@ use moon_action_context::ActionContext;
 use moon_emitter::{Emitter, Event};
 use moon_logger::trace;
 use moon_project_graph::ProjectGraph;
+use moon_runner::OutputListener;
 use moon_workspace::Workspace;
 use starbase_styles::color;
 use std::sync::Arc;
@@ -20,12 +21,37 @@ fn extract_error<T>(result: &miette::Result<T>) -> Option<String> {
     }
 }
 
+/// Shared state that every action within the pipeline is processed with.
+#[derive(Clone)]
+pub struct ActionProcessor {
+    pub context: Arc<RwLock<ActionContext>>,
+    pub emitter: Arc<RwLock<Emitter>>,
+    pub output_listener: Option<OutputListener>,
+    pub project_graph: Arc<RwLock<ProjectGraph>>,
+    pub workspace: Arc<RwLock<Workspace>>,
+}
+
+impl ActionProcessor {
+    pub async fn process(self, action: Action) -> miette::Result<Action> {
+        process_action(
+            action,
+            self.context,
+            self.emitter,
+            self.workspace,
+            self.project_graph,
+            self.output_listener,
+        )
+        .await
+    }
+}
+
 pub async fn process_action(
     mut action: Action,
     context: Arc<RwLock<ActionContext>>,
     emitter: Arc<RwLock<Emitter>>,
     workspace: Arc<RwLock<Workspace>>,
     project_graph: Arc<RwLock<ProjectGraph>>,
+    output_listener: Option<OutputListener>,
 ) -> miette::Result<Action> {
     action.start();
 
@@ -180,6 +206,7 @@ pub async fn process_action(
                 &project,
                 target,
                 runtime,
+                output_listener,
             )
             .await;
 
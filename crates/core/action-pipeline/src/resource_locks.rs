@@ -0,0 +1,122 @@
+use moon_action::ActionNode;
+use moon_logger::{debug, warn};
+use moon_project_graph::ProjectGraph;
+use moon_target::Target;
+use rustc_hash::FxHashMap;
+use starbase_styles::color;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const LOG_TARGET: &str = "moon:action-pipeline:resources";
+
+/// Limits how many targets that declare the same named resource
+/// (via the `resources` task option) can run at the same time.
+#[derive(Default)]
+pub struct ResourceLocks {
+    semaphores: FxHashMap<String, Arc<Semaphore>>,
+
+    /// Resource names required by each target.
+    targets: FxHashMap<Target, Vec<String>>,
+}
+
+impl ResourceLocks {
+    pub fn new<'a>(
+        nodes: impl IntoIterator<Item = &'a ActionNode>,
+        project_graph: &ProjectGraph,
+    ) -> miette::Result<Self> {
+        let mut capacities: BTreeMap<String, u16> = BTreeMap::new();
+        let mut targets = FxHashMap::default();
+
+        for node in nodes {
+            let (ActionNode::RunTarget(_, target)
+            | ActionNode::RunInteractiveTarget(_, target)
+            | ActionNode::RunPersistentTarget(_, target)) = node
+            else {
+                continue;
+            };
+
+            let project = project_graph.get(target.scope_id.as_ref().unwrap())?;
+            let task = project.get_task(&target.task_id)?;
+
+            if task.options.resources.is_empty() {
+                continue;
+            }
+
+            let mut names = vec![];
+
+            for resource in &task.options.resources {
+                // When tasks declare different capacities for the same
+                // resource, the most restrictive one wins
+                capacities
+                    .entry(resource.name.clone())
+                    .and_modify(|cap| {
+                        if resource.capacity != *cap {
+                            warn!(
+                                target: LOG_TARGET,
+                                "Resource {} has been declared with conflicting capacities, using the smallest",
+                                color::id(&resource.name),
+                            );
+
+                            *cap = (*cap).min(resource.capacity);
+                        }
+                    })
+                    .or_insert(resource.capacity);
+
+                names.push(resource.name.clone());
+            }
+
+            names.sort();
+            names.dedup();
+
+            targets.insert(target.to_owned(), names);
+        }
+
+        let semaphores = capacities
+            .into_iter()
+            .map(|(name, capacity)| {
+                debug!(
+                    target: LOG_TARGET,
+                    "Limiting resource {} to {} concurrent target(s)",
+                    color::id(&name),
+                    capacity
+                );
+
+                (name, Arc::new(Semaphore::new(capacity as usize)))
+            })
+            .collect();
+
+        Ok(ResourceLocks {
+            semaphores,
+            targets,
+        })
+    }
+
+    /// Attempt to acquire a permit for every resource the node's target holds.
+    /// Returns `None` if any resource is at capacity, in which case no permits
+    /// are held. Permits are released when the returned list is dropped.
+    pub fn try_acquire(&self, node: &ActionNode) -> Option<Vec<OwnedSemaphorePermit>> {
+        let target = match node {
+            ActionNode::RunTarget(_, target)
+            | ActionNode::RunInteractiveTarget(_, target)
+            | ActionNode::RunPersistentTarget(_, target) => target,
+            _ => return Some(vec![]),
+        };
+
+        let Some(names) = self.targets.get(target) else {
+            return Some(vec![]);
+        };
+
+        let mut permits = Vec::with_capacity(names.len());
+
+        for name in names {
+            permits.push(
+                Arc::clone(self.semaphores.get(name)?)
+                    .try_acquire_owned()
+                    .ok()?,
+            );
+        }
+
+        Some(permits)
+    }
+}
@@ -1,7 +1,9 @@
 use crate::estimator::Estimator;
-use moon_action::Action;
+use moon_action::{Action, ActionNode, ActionStatus};
 use moon_action_context::ActionContext;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::time::Duration;
 
 #[derive(Serialize)]
@@ -14,6 +16,13 @@ pub struct RunReport<'a> {
     /// How long the pipeline took to execute all actions.
     pub duration: Duration,
 
+    /// Labels of actions that failed.
+    pub failed: Vec<&'a str>,
+
+    /// Labels of actions that were skipped, because their condition was not met,
+    /// or a dependency failed or was skipped, mapped to the reason they were skipped.
+    pub skipped: BTreeMap<&'a str, &'a str>,
+
     /// Estimates around how much time was saved using moon,
     /// compared to another product or baseline.
     pub comparison_estimate: Estimator,
@@ -26,11 +35,229 @@ impl<'a> RunReport<'a> {
         duration: Duration,
         estimate: Estimator,
     ) -> Self {
+        let mut failed = vec![];
+        let mut skipped = BTreeMap::new();
+
+        for action in actions {
+            if action.has_failed() {
+                failed.push(action.label.as_str());
+            } else if let (ActionStatus::Skipped, Some(reason)) =
+                (&action.status, &action.skipped_reason)
+            {
+                skipped.insert(action.label.as_str(), reason.as_str());
+            }
+        }
+
         RunReport {
             actions,
             context,
             duration,
+            failed,
+            skipped,
             comparison_estimate: estimate,
         }
     }
+
+    /// Render the actions as a JUnit XML document, with a test case per action,
+    /// so that results can be displayed in a CI provider's test UI.
+    pub fn to_junit_xml(&self) -> String {
+        let skipped_count = self
+            .actions
+            .iter()
+            .filter(|action| matches!(action.status, ActionStatus::Skipped))
+            .count();
+
+        let mut cases = String::new();
+
+        for action in self.actions {
+            let (class_name, name) = match &action.node {
+                Some(
+                    ActionNode::RunTarget(_, target)
+                    | ActionNode::RunInteractiveTarget(_, target)
+                    | ActionNode::RunPersistentTarget(_, target),
+                ) => (
+                    target
+                        .scope_id
+                        .as_ref()
+                        .map(|id| id.to_string())
+                        .unwrap_or_else(|| "moon".into()),
+                    target.task_id.to_string(),
+                ),
+                _ => ("moon".into(), action.label.clone()),
+            };
+
+            let _ = write!(
+                cases,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape_xml(&name),
+                escape_xml(&class_name),
+                format_seconds(action.duration),
+            );
+
+            let last_attempt = action
+                .attempts
+                .as_ref()
+                .and_then(|attempts| attempts.last());
+
+            if action.has_failed() {
+                let message = action.error.as_deref().unwrap_or("Action failed");
+
+                let _ = write!(
+                    cases,
+                    ">\n      <failure message=\"{}\"/>\n",
+                    escape_xml(message)
+                );
+
+                if let Some(attempt) = last_attempt {
+                    if let Some(stdout) = &attempt.stdout {
+                        let _ = writeln!(
+                            cases,
+                            "      <system-out>{}</system-out>",
+                            escape_xml(stdout)
+                        );
+                    }
+
+                    if let Some(stderr) = &attempt.stderr {
+                        let _ = writeln!(
+                            cases,
+                            "      <system-err>{}</system-err>",
+                            escape_xml(stderr)
+                        );
+                    }
+                }
+
+                cases.push_str("    </testcase>\n");
+            } else if matches!(action.status, ActionStatus::Skipped) {
+                let message = action.skipped_reason.as_deref().unwrap_or("No changes");
+
+                let _ = write!(
+                    cases,
+                    ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                    escape_xml(message)
+                );
+            } else {
+                cases.push_str("/>\n");
+            }
+        }
+
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\"",
+            self.actions.len(),
+            self.failed.len(),
+            skipped_count,
+            format_seconds(Some(self.duration)),
+        );
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"moon\" {counts}>\n  <testsuite name=\"moon\" {counts}>\n{cases}  </testsuite>\n</testsuites>\n"
+        )
+    }
+
+    /// Convert the actions to the Chrome trace event format, which can be loaded
+    /// into `chrome://tracing` or Perfetto to visualize pipeline parallelism.
+    /// Actions that ran concurrently are placed on separate threads (lanes).
+    pub fn to_chrome_trace(&self) -> ChromeTrace {
+        let mut actions = self
+            .actions
+            .iter()
+            .filter(|action| action.started_at.is_some() && action.duration.is_some())
+            .collect::<Vec<_>>();
+
+        actions.sort_by_key(|action| action.started_at);
+
+        let Some(pipeline_start) = actions.first().and_then(|action| action.started_at) else {
+            return ChromeTrace::default();
+        };
+
+        // End timestamp of the last action in each lane
+        let mut lanes: Vec<u64> = vec![];
+        let mut events = vec![];
+
+        for action in actions {
+            let ts = (action.started_at.unwrap() - pipeline_start)
+                .num_microseconds()
+                .unwrap_or_default() as u64;
+            let dur = action.duration.unwrap().as_micros() as u64;
+
+            let lane = match lanes.iter().position(|end| *end <= ts) {
+                Some(lane) => lane,
+                None => {
+                    lanes.push(0);
+                    lanes.len() - 1
+                }
+            };
+
+            lanes[lane] = ts + dur;
+
+            events.push(TraceEvent {
+                args: TraceEventArgs {
+                    status: action.status,
+                },
+                cat: "action",
+                dur,
+                name: action.label.clone(),
+                ph: "X",
+                pid: 1,
+                tid: lane + 1,
+                ts,
+            });
+        }
+
+        ChromeTrace {
+            display_time_unit: "ms",
+            trace_events: events,
+        }
+    }
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromeTrace {
+    pub display_time_unit: &'static str,
+
+    pub trace_events: Vec<TraceEvent>,
+}
+
+/// A "complete" event (`ph: X`) with a start timestamp
+/// and duration, both in microseconds.
+#[derive(Serialize)]
+pub struct TraceEvent {
+    pub args: TraceEventArgs,
+    pub cat: &'static str,
+    pub dur: u64,
+    pub name: String,
+    pub ph: &'static str,
+    pub pid: u32,
+    pub tid: usize,
+    pub ts: u64,
+}
+
+#[derive(Serialize)]
+pub struct TraceEventArgs {
+    pub status: ActionStatus,
+}
+
+fn format_seconds(duration: Option<Duration>) -> String {
+    format!("{:.3}", duration.unwrap_or_default().as_secs_f64())
+}
+
+fn escape_xml(value: &str) -> String {
+    let value = console::strip_ansi_codes(value);
+    let mut escaped = String::with_capacity(value.len());
+
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            ch if ch.is_control() => {}
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
 }
@@ -0,0 +1,290 @@
+use moon_action::ActionNode;
+use moon_dep_graph::DepGraph;
+use petgraph::graph::NodeIndex;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::Duration;
+
+/// Schedules actions as soon as all of their dependencies have completed,
+/// instead of in strict batches. When multiple actions are ready, the action
+/// with the longest remaining path through the graph (its critical path),
+/// based on historical target durations, is started first.
+pub struct Scheduler {
+    /// Nodes that depend on a node (reverse edges).
+    dependents: FxHashMap<NodeIndex, Vec<NodeIndex>>,
+
+    /// Interactive nodes must run on their own for stdin to work correctly.
+    interactive: FxHashSet<NodeIndex>,
+
+    /// Count of incomplete dependencies for each node.
+    pending: FxHashMap<NodeIndex, usize>,
+
+    /// Persistent nodes never complete, so they're all started last.
+    persistent: Vec<NodeIndex>,
+
+    /// Persistent nodes with a ready check. These are started as soon as their
+    /// dependencies complete, and release their dependents once they're ready.
+    probed: FxHashSet<NodeIndex>,
+
+    /// Critical path duration of each node (its own duration plus
+    /// the longest duration of its dependents).
+    priorities: FxHashMap<NodeIndex, Duration>,
+
+    ready: BinaryHeap<(Duration, Reverse<NodeIndex>)>,
+
+    ready_interactive: Vec<NodeIndex>,
+
+    /// Probed nodes that have released their dependents.
+    released: FxHashSet<NodeIndex>,
+
+    /// Count of non-persistent nodes that have not completed.
+    remaining: usize,
+
+    running: usize,
+
+    running_interactive: bool,
+}
+
+impl Scheduler {
+    pub fn new(
+        dep_graph: &DepGraph,
+        durations: &FxHashMap<String, Duration>,
+        probed: &FxHashSet<NodeIndex>,
+    ) -> miette::Result<Self> {
+        // Also detects cycles, and orders dependencies before their dependents
+        let sorted = dep_graph.sort_topological()?;
+
+        // Targets without history are treated as an average target
+        let default_duration = if durations.is_empty() {
+            Duration::ZERO
+        } else {
+            durations.values().sum::<Duration>() / durations.len() as u32
+        };
+
+        let mut scheduler = Scheduler {
+            dependents: FxHashMap::default(),
+            interactive: FxHashSet::default(),
+            pending: FxHashMap::default(),
+            persistent: vec![],
+            probed: probed.to_owned(),
+            priorities: FxHashMap::default(),
+            ready: BinaryHeap::new(),
+            ready_interactive: vec![],
+            released: FxHashSet::default(),
+            remaining: 0,
+            running: 0,
+            running_interactive: false,
+        };
+
+        for index in &sorted {
+            let node = dep_graph.get_node_from_index(index).unwrap();
+
+            match node {
+                ActionNode::RunPersistentTarget(_, _) if !probed.contains(index) => {
+                    scheduler.persistent.push(*index);
+                    continue;
+                }
+                ActionNode::RunInteractiveTarget(_, _) => {
+                    scheduler.interactive.insert(*index);
+                }
+                _ => {}
+            };
+
+            let mut pending = 0;
+
+            for dep_index in dep_graph.get_dependencies_of(index) {
+                // Persistent nodes are started last, so they can't block other nodes,
+                // unless they have a ready check that their dependents can wait on
+                if matches!(
+                    dep_graph.get_node_from_index(&dep_index),
+                    Some(ActionNode::RunPersistentTarget(_, _))
+                ) && !probed.contains(&dep_index)
+                {
+                    continue;
+                }
+
+                scheduler
+                    .dependents
+                    .entry(dep_index)
+                    .or_default()
+                    .push(*index);
+
+                pending += 1;
+            }
+
+            scheduler.pending.insert(*index, pending);
+
+            // Probed nodes never complete either
+            if !probed.contains(index) {
+                scheduler.remaining += 1;
+            }
+        }
+
+        // Walk from the leaves of the graph (nodes that nothing depends on) back
+        // to the roots, so that dependents are prioritized before their dependencies
+        for index in sorted.iter().rev() {
+            let duration = match dep_graph.get_node_from_index(index).unwrap() {
+                ActionNode::RunTarget(_, target) | ActionNode::RunInteractiveTarget(_, target) => {
+                    durations
+                        .get(target.as_str())
+                        .copied()
+                        .unwrap_or(default_duration)
+                }
+                _ => Duration::ZERO,
+            };
+
+            let downstream = scheduler
+                .dependents
+                .get(index)
+                .and_then(|dependents| {
+                    dependents
+                        .iter()
+                        .filter_map(|dependent| scheduler.priorities.get(dependent))
+                        .max()
+                        .copied()
+                })
+                .unwrap_or_default();
+
+            scheduler.priorities.insert(*index, duration + downstream);
+        }
+
+        let roots = scheduler
+            .pending
+            .iter()
+            .filter(|(_, pending)| **pending == 0)
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+
+        for index in roots {
+            scheduler.push_ready(index);
+        }
+
+        Ok(scheduler)
+    }
+
+    /// Return the highest priority node that is ready to be started, and that
+    /// the provided callback accepts. Returns `None` if no node can be started
+    /// until a running node completes.
+    pub fn next<T>(
+        &mut self,
+        mut can_start: impl FnMut(NodeIndex) -> Option<T>,
+    ) -> Option<(NodeIndex, T)> {
+        if self.running_interactive {
+            return None;
+        }
+
+        // Wait for running nodes to drain before starting an interactive node
+        if !self.ready_interactive.is_empty() {
+            if self.running > 0 {
+                return None;
+            }
+
+            let index = self.ready_interactive.remove(0);
+            let value = can_start(index)?;
+
+            self.running += 1;
+            self.running_interactive = true;
+
+            return Some((index, value));
+        }
+
+        let mut skipped = vec![];
+        let mut next = None;
+
+        while let Some(item) = self.ready.pop() {
+            let Reverse(index) = item.1;
+
+            if let Some(value) = can_start(index) {
+                next = Some((index, value));
+                break;
+            }
+
+            skipped.push(item);
+        }
+
+        self.ready.extend(skipped);
+
+        if next.is_none() && self.remaining == 0 && !self.persistent.is_empty() {
+            let index = self.persistent.remove(0);
+
+            if let Some(value) = can_start(index) {
+                next = Some((index, value));
+            } else {
+                self.persistent.insert(0, index);
+            }
+        }
+
+        if next.is_some() {
+            self.running += 1;
+        }
+
+        next
+    }
+
+    /// Mark the node as complete, which may unblock its dependents.
+    pub fn complete(&mut self, index: NodeIndex) {
+        self.running -= 1;
+
+        if self.interactive.contains(&index) {
+            self.running_interactive = false;
+        }
+
+        if !self.pending.contains_key(&index) {
+            return;
+        }
+
+        // A probed node that exits before it's ready must still release its
+        // dependents, so that they can be skipped or ran
+        if self.probed.contains(&index) {
+            self.ready(index);
+
+            return;
+        }
+
+        self.remaining -= 1;
+        self.release_dependents(index);
+    }
+
+    /// Mark a probed node as ready, which may unblock its dependents.
+    pub fn ready(&mut self, index: NodeIndex) {
+        if self.probed.contains(&index) && self.released.insert(index) {
+            self.release_dependents(index);
+        }
+    }
+
+    pub fn get_dependents(&self, index: &NodeIndex) -> &[NodeIndex] {
+        self.dependents
+            .get(index)
+            .map(|dependents| dependents.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn get_priority(&self, index: &NodeIndex) -> Duration {
+        self.priorities.get(index).copied().unwrap_or_default()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.remaining == 0 && self.running == 0 && self.persistent.is_empty()
+    }
+
+    fn release_dependents(&mut self, index: NodeIndex) {
+        for dependent in self.dependents.get(&index).cloned().unwrap_or_default() {
+            if let Some(pending) = self.pending.get_mut(&dependent) {
+                *pending -= 1;
+
+                if *pending == 0 {
+                    self.push_ready(dependent);
+                }
+            }
+        }
+    }
+
+    fn push_ready(&mut self, index: NodeIndex) {
+        if self.interactive.contains(&index) {
+            self.ready_interactive.push(index);
+        } else {
+            self.ready.push((self.get_priority(&index), Reverse(index)));
+        }
+    }
+}
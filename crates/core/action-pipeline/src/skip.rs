@@ -0,0 +1,99 @@
+use crate::dashboard::Dashboard;
+use crate::pipeline::get_node_target;
+use crate::scheduler::Scheduler;
+use console::Term;
+use moon_action::{Action, ActionNode};
+use moon_action_context::{ActionContext, TargetState};
+use moon_dep_graph::DepGraph;
+use moon_logger::debug;
+use moon_terminal::{label_checkpoint, Checkpoint};
+use petgraph::graph::NodeIndex;
+use rustc_hash::FxHashMap;
+use starbase_styles::color;
+use tokio::sync::RwLock;
+
+const LOG_TARGET: &str = "moon:action-pipeline:skip";
+
+/// Actions whose task condition was not met, or that are downstream
+/// of a failure, and the reason they will be skipped.
+pub struct SkippedNodes {
+    reasons: FxHashMap<NodeIndex, String>,
+}
+
+impl SkippedNodes {
+    pub fn new(dep_graph: &DepGraph) -> Self {
+        SkippedNodes {
+            reasons: dep_graph
+                .get_unmet_conditions()
+                .iter()
+                .map(|(index, reason)| (*index, format!("condition not met, {reason}")))
+                .collect(),
+        }
+    }
+
+    pub fn take(&mut self, index: &NodeIndex) -> Option<String> {
+        self.reasons.remove(index)
+    }
+
+    /// Skip the dependents of the node, except for its cleanup targets, which
+    /// always run. Dependents that are already skipped keep their first reason.
+    pub fn skip_dependents<F>(
+        &mut self,
+        dep_graph: &DepGraph,
+        scheduler: &Scheduler,
+        index: NodeIndex,
+        reason: F,
+    ) where
+        F: Fn() -> String,
+    {
+        for dependent in scheduler
+            .get_dependents(&index)
+            .iter()
+            .filter(|dependent| !dep_graph.is_finally_edge(dependent, &index))
+        {
+            self.reasons.entry(*dependent).or_insert_with(&reason);
+        }
+    }
+}
+
+/// Create a skipped action for the node, mark its target as skipped,
+/// so that dependents don't run against stale outputs, and render it.
+pub async fn skip_action(
+    node: &ActionNode,
+    reason: String,
+    context: &RwLock<ActionContext>,
+    dashboard: Option<&Dashboard>,
+) -> miette::Result<Action> {
+    let mut action = Action::new(node.to_owned());
+
+    debug!(
+        target: LOG_TARGET,
+        "Skipping action {} because {}",
+        color::muted_light(&action.label),
+        reason
+    );
+
+    if let Some(target) = get_node_target(node) {
+        context
+            .write()
+            .await
+            .target_states
+            .insert(target.to_owned(), TargetState::Skipped);
+
+        if dashboard.is_none() {
+            Term::stdout().write_line(&format!(
+                "{} {}",
+                label_checkpoint(target, Checkpoint::RunSkipped),
+                color::muted(format!("(skipped, {reason})"))
+            ))?;
+        }
+    }
+
+    if let Some(dashboard) = dashboard {
+        dashboard.get_state().lock().unwrap().skip_action();
+    }
+
+    action.skip(reason);
+
+    Ok(action)
+}
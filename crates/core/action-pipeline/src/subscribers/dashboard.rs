@@ -0,0 +1,45 @@
+use crate::dashboard::DashboardState;
+use moon_emitter::{Event, EventFlow, Subscriber};
+use moon_utils::async_trait;
+use moon_workspace::Workspace;
+use std::sync::{Arc, Mutex};
+
+/// Feeds the progress of the pipeline into the dashboard.
+pub struct DashboardSubscriber {
+    state: Arc<Mutex<DashboardState>>,
+}
+
+impl DashboardSubscriber {
+    pub fn new(state: Arc<Mutex<DashboardState>>) -> Self {
+        DashboardSubscriber { state }
+    }
+}
+
+#[async_trait]
+impl Subscriber for DashboardSubscriber {
+    async fn on_emit<'a>(
+        &mut self,
+        event: &Event<'a>,
+        _workspace: &Workspace,
+    ) -> miette::Result<EventFlow> {
+        let mut state = self.state.lock().unwrap();
+
+        match event {
+            Event::ActionStarted { action, node } => {
+                state.start_action(action, node);
+            }
+            Event::ActionFinished { action, .. } => {
+                state.finish_action(action);
+            }
+            Event::TargetOutputHydrated { hash, target, .. } => {
+                state.append_log(target, &format!("Hydrated outputs from cache ({hash})"));
+            }
+            Event::TargetReady { target } => {
+                state.append_log(target, "Ready");
+            }
+            _ => {}
+        };
+
+        Ok(EventFlow::Continue)
+    }
+}
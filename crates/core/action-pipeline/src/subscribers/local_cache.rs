@@ -1,21 +1,40 @@
 use moon_cache_item::get_cache_mode;
+use moon_config::HydrationMode;
 use moon_emitter::{Event, EventFlow, Subscriber};
-use moon_runner::{archive_outputs, hydrate_outputs};
+use moon_runner::{archive_outputs, hydrate_outputs, link_outputs, store_outputs};
 use moon_utils::{async_trait, path};
 use moon_workspace::Workspace;
 
+fn is_link_mode(workspace: &Workspace) -> bool {
+    matches!(workspace.config.runner.hydration_mode, HydrationMode::Link)
+}
+
+// Remote caches share archives, so they must still be created in link mode
+fn is_archive_required(workspace: &Workspace) -> bool {
+    !is_link_mode(workspace)
+        || workspace.session.is_some()
+        || workspace.config.remote_cache.is_enabled()
+}
+
 /// The local cache subscriber is in charge of managing archives
 /// (task output's archived as tarballs), by reading and writing them
-/// to the `.moon/cache/{outputs,hashes}` directories.
+/// to the `.moon/cache/{outputs,hashes}` directories. When using the
+/// link hydration mode, outputs are also stored unpacked in the
+/// `.moon/cache/store` directory, and linked into place when hydrating.
 ///
 /// This is the last subscriber amongst all subscribers, as local
 /// cache is the last line of defense. However, other subscribers
 /// will piggyback off of it, like remote cache.
-pub struct LocalCacheSubscriber {}
+pub struct LocalCacheSubscriber {
+    // Hashes of outputs that were hydrated during the run
+    hydrated_hashes: Vec<String>,
+}
 
 impl LocalCacheSubscriber {
     pub fn new() -> Self {
-        LocalCacheSubscriber {}
+        LocalCacheSubscriber {
+            hydrated_hashes: vec![],
+        }
     }
 }
 
@@ -31,7 +50,9 @@ impl Subscriber for LocalCacheSubscriber {
             // We only check for the archive, as the manifest is purely for local debugging!
             Event::TargetOutputCacheCheck { hash, .. } => {
                 if get_cache_mode().is_readable()
-                    && workspace.hash_engine.get_archive_path(hash).exists()
+                    && (workspace.hash_engine.get_archive_path(hash).exists()
+                        || (is_link_mode(workspace)
+                            && workspace.hash_engine.get_store_manifest_path(hash).exists()))
                 {
                     return Ok(EventFlow::Return("local-cache".into()));
                 }
@@ -46,18 +67,40 @@ impl Subscriber for LocalCacheSubscriber {
             } => {
                 let state_dir = workspace.cache_engine.states_dir.join(task.get_cache_dir());
                 let archive_path = workspace.hash_engine.get_archive_path(hash);
+                let digest_path = workspace.hash_engine.get_digest_path(hash);
                 let output_paths = task
                     .outputs
                     .iter()
                     .filter_map(|o| o.to_workspace_relative(&project.source))
                     .collect::<Vec<_>>();
 
-                if archive_outputs(&state_dir, &archive_path, &workspace.root, &output_paths)? {
+                if is_link_mode(workspace) {
+                    store_outputs(
+                        &state_dir,
+                        &workspace.hash_engine.get_store_manifest_path(hash),
+                        &workspace.hash_engine.get_store_objects_dir(),
+                        &workspace.root,
+                        &output_paths,
+                    )?;
+                }
+
+                if is_archive_required(workspace)
+                    && archive_outputs(
+                        &state_dir,
+                        &archive_path,
+                        &digest_path,
+                        &workspace.root,
+                        &output_paths,
+                    )?
+                {
                     return Ok(EventFlow::Return(path::to_string(archive_path)?));
                 }
             }
 
-            // Hydrate the cached archive into the task's outputs.
+            // Hydrate the cached archive into the task's outputs. If the archive
+            // fails verification, it's evicted and treated as a cache miss.
+            // In link mode, the store is preferred, but archives downloaded
+            // from a remote cache are still unpacked.
             Event::TargetOutputHydrating {
                 hash,
                 project,
@@ -66,22 +109,60 @@ impl Subscriber for LocalCacheSubscriber {
             } => {
                 let state_dir = workspace.cache_engine.states_dir.join(task.get_cache_dir());
                 let archive_path = workspace.hash_engine.get_archive_path(hash);
+                let digest_path = workspace.hash_engine.get_digest_path(hash);
                 let output_paths = task
                     .outputs
                     .iter()
                     .filter_map(|o| o.to_workspace_relative(&project.source))
                     .collect::<Vec<_>>();
 
-                if hydrate_outputs(&state_dir, &archive_path, &workspace.root, &output_paths)? {
+                if is_link_mode(workspace) {
+                    let manifest_path = workspace.hash_engine.get_store_manifest_path(hash);
+
+                    if link_outputs(
+                        &state_dir,
+                        &manifest_path,
+                        &workspace.hash_engine.get_store_objects_dir(),
+                        &workspace.root,
+                        &output_paths,
+                    )? {
+                        self.hydrated_hashes.push((*hash).to_owned());
+
+                        return Ok(EventFlow::Return(path::to_string(manifest_path)?));
+                    }
+                }
+
+                if hydrate_outputs(
+                    &state_dir,
+                    &archive_path,
+                    &digest_path,
+                    &workspace.root,
+                    &output_paths,
+                )? {
+                    self.hydrated_hashes.push((*hash).to_owned());
+
                     return Ok(EventFlow::Return(path::to_string(archive_path)?));
                 }
             }
 
-            // After the run has finished, clean any stale archives.
+            // After the run has finished, clean any stale archives,
+            // and evict the least recently used if over the max size.
             Event::PipelineFinished { .. } => {
+                workspace
+                    .cache_engine
+                    .track_hydrated_outputs(&self.hydrated_hashes)?;
+
                 workspace
                     .cache_engine
                     .clean_stale_cache(&workspace.config.runner.cache_lifetime)?;
+
+                if let Some(max_size) = &workspace.config.runner.cache_max_size {
+                    workspace.cache_engine.clean_outputs_over_size(
+                        &workspace.hash_engine.outputs_dir,
+                        &workspace.hash_engine.store_dir,
+                        max_size,
+                    )?;
+                }
             }
             _ => {}
         }
@@ -1,2 +1,6 @@
+pub mod dashboard;
 pub mod local_cache;
 pub mod moonbase;
+pub mod ready_check;
+pub mod reapi_cache;
+pub mod remote_cache;
@@ -24,6 +24,9 @@ const LOG_TARGET: &str = "moonbase";
 pub struct MoonbaseSubscriber {
     download_urls: FxHashMap<String, Option<String>>,
 
+    // Whether an archive without a digest has been hydrated in this run
+    hydrated_without_digest: bool,
+
     // Mapping of actions to job IDs
     job_ids: FxHashMap<String, i64>,
 
@@ -38,6 +41,7 @@ impl MoonbaseSubscriber {
     pub fn new() -> Self {
         MoonbaseSubscriber {
             download_urls: FxHashMap::default(),
+            hydrated_without_digest: false,
             job_ids: FxHashMap::default(),
             run_id: None,
             requests: vec![],
@@ -503,6 +507,17 @@ impl Subscriber for MoonbaseSubscriber {
                                 .await
                             {
                                 log_failure(error);
+                            } else if !self.hydrated_without_digest {
+                                // Moonbase does not store digests, so there's nothing to verify
+                                // archives against before they're hydrated. This applies to
+                                // every archive, so only log it once per run.
+                                self.hydrated_without_digest = true;
+
+                                debug!(
+                                    target: LOG_TARGET,
+                                    "Archives downloaded from moonbase have no digest, and will be hydrated without verifying their integrity (first was {})",
+                                    color::file(hash),
+                                );
                             }
 
                             // Fallthrough to local cache to handle the actual hydration
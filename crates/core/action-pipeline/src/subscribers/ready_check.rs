@@ -0,0 +1,32 @@
+use moon_emitter::{Event, EventFlow, Subscriber};
+use moon_target::Target;
+use moon_utils::async_trait;
+use moon_workspace::Workspace;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Forwards targets that have passed their ready check back to the
+/// pipeline, so that their dependents can be scheduled.
+pub struct ReadyCheckSubscriber {
+    sender: UnboundedSender<Target>,
+}
+
+impl ReadyCheckSubscriber {
+    pub fn new(sender: UnboundedSender<Target>) -> Self {
+        ReadyCheckSubscriber { sender }
+    }
+}
+
+#[async_trait]
+impl Subscriber for ReadyCheckSubscriber {
+    async fn on_emit<'a>(
+        &mut self,
+        event: &Event<'a>,
+        _workspace: &Workspace,
+    ) -> miette::Result<EventFlow> {
+        if let Event::TargetReady { target } = event {
+            let _ = self.sender.send((*target).to_owned());
+        }
+
+        Ok(EventFlow::Continue)
+    }
+}
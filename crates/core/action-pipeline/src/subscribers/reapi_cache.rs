@@ -0,0 +1,221 @@
+use moon_cache_item::get_cache_mode;
+use moon_emitter::{Event, EventFlow, Subscriber};
+use moon_logger::{trace, warn};
+use moon_remote_cache::reapi_proto::ActionResult;
+use moon_remote_cache::{collect_output_files, create_action_digest, ReapiRemoteCache};
+use moon_runner::get_output_logs;
+use moon_utils::async_trait;
+use moon_workspace::Workspace;
+use rustc_hash::FxHashMap;
+use starbase_styles::color;
+use tokio::task::JoinHandle;
+
+const LOG_TARGET: &str = "moon:reapi-cache";
+
+// We don't want errors to bubble up and crash the program,
+// so instead, we log the error (as a warning) to the console!
+fn log_failure(error: miette::Report) {
+    warn!(
+        target: LOG_TARGET,
+        "Remote caching failure: {}",
+        error.to_string()
+    );
+}
+
+/// The REAPI cache subscriber shares task outputs through a Bazel Remote
+/// Execution API compatible server. Unlike other remote caches, outputs are
+/// not archived, but are stored as individual blobs and written directly
+/// into the workspace when hydrating.
+pub struct ReapiCacheSubscriber {
+    cache: ReapiRemoteCache,
+
+    // Action results for hashes that exist in the remote but not the local cache
+    results: FxHashMap<String, ActionResult>,
+
+    // In-flight requests
+    requests: Vec<JoinHandle<()>>,
+}
+
+impl ReapiCacheSubscriber {
+    pub fn new(cache: ReapiRemoteCache) -> Self {
+        ReapiCacheSubscriber {
+            cache,
+            results: FxHashMap::default(),
+            requests: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl Subscriber for ReapiCacheSubscriber {
+    async fn on_emit<'e>(
+        &mut self,
+        event: &Event<'e>,
+        workspace: &Workspace,
+    ) -> miette::Result<EventFlow> {
+        match event {
+            // Check if an action result exists for the hash. If the archive already
+            // exists locally, avoid the request and let the local cache handle it.
+            Event::TargetOutputCacheCheck { hash, .. } => {
+                if get_cache_mode().is_readable()
+                    && !workspace.hash_engine.get_archive_path(hash).exists()
+                {
+                    let action_digest = match workspace
+                        .hash_engine
+                        .read_manifest(hash)
+                        .and_then(|manifest| create_action_digest(hash, manifest.as_deref()))
+                    {
+                        Ok(digest) => digest,
+                        Err(error) => {
+                            log_failure(error);
+
+                            return Ok(EventFlow::Continue);
+                        }
+                    };
+
+                    match self.cache.read_action_result(&action_digest).await {
+                        Ok(Some(result)) => {
+                            self.results.insert((*hash).to_owned(), result);
+
+                            return Ok(EventFlow::Return("remote-cache".into()));
+                        }
+                        Ok(None) => {
+                            // Not remote cached
+                        }
+                        Err(error) => {
+                            log_failure(error);
+
+                            // Fallthrough and check local cache
+                        }
+                    }
+                }
+            }
+
+            // Upload the outputs *after* the local archive has been created,
+            // so that we know the outputs exist and are cacheable.
+            Event::TargetOutputArchived {
+                hash,
+                project,
+                task,
+                ..
+            } => {
+                if get_cache_mode().is_writable() {
+                    let output_paths = task
+                        .outputs
+                        .iter()
+                        .filter_map(|o| o.to_workspace_relative(&project.source))
+                        .collect::<Vec<_>>();
+                    let digest_and_files = workspace
+                        .hash_engine
+                        .read_manifest(hash)
+                        .and_then(|manifest| create_action_digest(hash, manifest.as_deref()))
+                        .and_then(|digest| {
+                            Ok((
+                                digest,
+                                collect_output_files(&workspace.root, &output_paths)?,
+                            ))
+                        });
+
+                    let (action_digest, output_files) = match digest_and_files {
+                        Ok(result) => result,
+                        Err(error) => {
+                            log_failure(error);
+
+                            return Ok(EventFlow::Continue);
+                        }
+                    };
+                    let (stdout_log, stderr_log) = get_output_logs(
+                        &workspace.cache_engine.states_dir.join(task.get_cache_dir()),
+                    );
+                    let workspace_root = workspace.root.clone();
+                    let cache = self.cache.clone();
+
+                    trace!(
+                        target: LOG_TARGET,
+                        "Uploading {} outputs for {} to remote cache",
+                        output_files.len(),
+                        color::hash(hash),
+                    );
+
+                    // Run this in the background so we don't slow down the pipeline
+                    // while waiting for very large outputs to upload
+                    self.requests.push(tokio::spawn(async move {
+                        if let Err(error) = cache
+                            .write_action_result(
+                                &action_digest,
+                                &workspace_root,
+                                &output_files,
+                                (&stdout_log, &stderr_log),
+                            )
+                            .await
+                        {
+                            log_failure(error);
+                        }
+                    }));
+                }
+            }
+
+            // Write the outputs directly into the workspace. There's no archive to
+            // unpack, so we must return early to avoid the local cache hydrating.
+            Event::TargetOutputHydrating {
+                hash,
+                project,
+                task,
+                ..
+            } => {
+                if get_cache_mode().is_readable() {
+                    if let Some(result) = self.results.remove(*hash) {
+                        let output_paths = task
+                            .outputs
+                            .iter()
+                            .filter_map(|o| o.to_workspace_relative(&project.source))
+                            .collect::<Vec<_>>();
+                        let (stdout_log, stderr_log) = get_output_logs(
+                            &workspace.cache_engine.states_dir.join(task.get_cache_dir()),
+                        );
+
+                        trace!(
+                            target: LOG_TARGET,
+                            "Downloading {} outputs for {} from remote cache",
+                            result.output_files.len(),
+                            color::hash(hash),
+                        );
+
+                        match self
+                            .cache
+                            .hydrate_action_result(
+                                &result,
+                                &workspace.root,
+                                &output_paths,
+                                (&stdout_log, &stderr_log),
+                            )
+                            .await
+                        {
+                            // No archive was hydrated, so return an empty path,
+                            // which is emitted as a `null` archive path
+                            Ok(_) => {
+                                return Ok(EventFlow::Return(String::new()));
+                            }
+                            Err(error) => {
+                                log_failure(error);
+
+                                // Fallthrough to local cache
+                            }
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        // For the last event, we want to ensure that all requests have been completed!
+        if event.is_end() {
+            for future in self.requests.drain(0..) {
+                let _ = future.await;
+            }
+        }
+
+        Ok(EventFlow::Continue)
+    }
+}
@@ -0,0 +1,197 @@
+use moon_cache_item::get_cache_mode;
+use moon_emitter::{Event, EventFlow, Subscriber};
+use moon_logger::{trace, warn};
+use moon_remote_cache::{get_archive_key, get_digest_key, get_manifest_key, BoxedRemoteCache};
+use moon_utils::async_trait;
+use moon_workspace::Workspace;
+use rustc_hash::FxHashSet;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+const LOG_TARGET: &str = "moon:remote-cache";
+
+// We don't want errors to bubble up and crash the program,
+// so instead, we log the error (as a warning) to the console!
+fn log_failure(error: miette::Report) {
+    warn!(
+        target: LOG_TARGET,
+        "Remote caching failure: {}",
+        error.to_string()
+    );
+}
+
+/// The remote cache subscriber shares archives through a self-hosted
+/// backend (configured with `remoteCache`), and piggybacks off of the
+/// local cache subscriber for the actual archiving and hydrating.
+pub struct RemoteCacheSubscriber {
+    backend: BoxedRemoteCache,
+
+    // Hashes that exist in the remote but not the local cache
+    remote_hashes: FxHashSet<String>,
+
+    // In-flight requests
+    requests: Vec<JoinHandle<()>>,
+}
+
+impl RemoteCacheSubscriber {
+    pub fn new(backend: BoxedRemoteCache) -> Self {
+        RemoteCacheSubscriber {
+            backend,
+            remote_hashes: FxHashSet::default(),
+            requests: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl Subscriber for RemoteCacheSubscriber {
+    async fn on_emit<'e>(
+        &mut self,
+        event: &Event<'e>,
+        workspace: &Workspace,
+    ) -> miette::Result<EventFlow> {
+        match event {
+            // Check if the archive exists in the remote. If it already exists locally,
+            // avoid the request and let the local cache subscriber handle it.
+            Event::TargetOutputCacheCheck { hash, .. } => {
+                if get_cache_mode().is_readable()
+                    && !workspace.hash_engine.get_archive_path(hash).exists()
+                {
+                    match self.backend.exists(&get_archive_key(hash)).await {
+                        Ok(true) => {
+                            self.remote_hashes.insert((*hash).to_owned());
+
+                            return Ok(EventFlow::Return("remote-cache".into()));
+                        }
+                        Ok(false) => {
+                            // Not remote cached
+                        }
+                        Err(error) => {
+                            log_failure(error);
+
+                            // Fallthrough and check local cache
+                        }
+                    }
+                }
+            }
+
+            // The local cache subscriber uses the `TargetOutputArchiving` event to create
+            // the tarball. This runs *after* it's been created so that we can upload it.
+            Event::TargetOutputArchived {
+                archive_path, hash, ..
+            } => {
+                if get_cache_mode().is_writable() && archive_path.exists() {
+                    let backend = Arc::clone(&self.backend);
+                    let archive_path = archive_path.to_owned();
+                    let digest_path = workspace.hash_engine.get_digest_path(hash);
+                    let manifest_path = workspace.hash_engine.get_manifest_path(hash);
+                    let hash = (*hash).to_owned();
+
+                    trace!(
+                        target: LOG_TARGET,
+                        "Uploading artifact {} to {} remote cache",
+                        color::file(&hash),
+                        backend.get_name(),
+                    );
+
+                    // Run this in the background so we don't slow down the pipeline
+                    // while waiting for very large archives to upload. The digest is
+                    // uploaded first, as archives without one will never be hydrated.
+                    self.requests.push(tokio::spawn(async move {
+                        if let Err(error) =
+                            backend.upload(&get_digest_key(&hash), &digest_path).await
+                        {
+                            log_failure(error);
+
+                            return;
+                        }
+
+                        if let Err(error) =
+                            backend.upload(&get_archive_key(&hash), &archive_path).await
+                        {
+                            log_failure(error);
+
+                            return;
+                        }
+
+                        if manifest_path.exists() {
+                            if let Err(error) = backend
+                                .upload(&get_manifest_key(&hash), &manifest_path)
+                                .await
+                            {
+                                log_failure(error);
+                            }
+                        }
+                    }));
+                }
+            }
+
+            // Attempt to download the artifact from the remote cache to `.moon/outputs/<hash>`.
+            // This runs *before* the local cache, which will handle the actual hydration.
+            Event::TargetOutputHydrating { hash, .. } => {
+                if get_cache_mode().is_readable() && self.remote_hashes.remove(*hash) {
+                    let archive_path = workspace.hash_engine.get_archive_path(hash);
+
+                    trace!(
+                        target: LOG_TARGET,
+                        "Downloading artifact {} from {} remote cache",
+                        color::file(hash),
+                        self.backend.get_name(),
+                    );
+
+                    if let Err(error) = self
+                        .backend
+                        .download(&get_archive_key(hash), &archive_path)
+                        .await
+                    {
+                        log_failure(error);
+
+                        return Ok(EventFlow::Continue);
+                    }
+
+                    // An archive from a remote can't be trusted without a digest to verify
+                    // it against, so it's discarded, and the task runs as a cache miss
+                    let digest_path = workspace.hash_engine.get_digest_path(hash);
+
+                    if let Err(error) = self
+                        .backend
+                        .download(&get_digest_key(hash), &digest_path)
+                        .await
+                    {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Unable to download the digest for archive {} from {} remote cache, so it can't be verified and will not be hydrated: {}",
+                            color::file(hash),
+                            self.backend.get_name(),
+                            error.to_string()
+                        );
+
+                        // Failing to clean up must not fail the run, as it's only a cache miss
+                        for path in [&archive_path, &digest_path] {
+                            if let Err(error) = fs::remove(path) {
+                                log_failure(error.into());
+                            }
+                        }
+
+                        return Ok(EventFlow::Continue);
+                    }
+
+                    // Fallthrough to local cache to handle the actual hydration
+                }
+            }
+
+            _ => {}
+        }
+
+        // For the last event, we want to ensure that all requests have been completed!
+        if event.is_end() {
+            for future in self.requests.drain(0..) {
+                let _ = future.await;
+            }
+        }
+
+        Ok(EventFlow::Continue)
+    }
+}
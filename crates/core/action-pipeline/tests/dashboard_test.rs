@@ -0,0 +1,128 @@
+use moon_action::{Action, ActionNode, ActionStatus};
+use moon_action_pipeline::dashboard::DashboardState;
+use moon_platform::Runtime;
+use moon_target::Target;
+
+fn create_node(target: &str) -> ActionNode {
+    ActionNode::RunTarget(Runtime::System, target.into())
+}
+
+fn start(state: &mut DashboardState, target: &str) -> Action {
+    let action = Action::new(create_node(target));
+
+    state.start_action(&action, &create_node(target));
+    action
+}
+
+fn finish(state: &mut DashboardState, mut action: Action, status: ActionStatus) {
+    action.status = status;
+    state.finish_action(&action);
+}
+
+mod counts {
+    use super::*;
+
+    #[test]
+    fn tracks_actions_as_they_progress() {
+        let mut state = DashboardState::new(5);
+
+        let a = start(&mut state, "app:a");
+        let b = start(&mut state, "app:b");
+        let c = start(&mut state, "app:c");
+
+        assert_eq!(state.get_running_count(), 3);
+        assert_eq!(state.get_queued_count(), 2);
+
+        finish(&mut state, a, ActionStatus::Passed);
+        finish(&mut state, b, ActionStatus::Cached);
+        finish(&mut state, c, ActionStatus::Failed);
+        state.skip_action();
+
+        assert_eq!(state.get_running_count(), 0);
+        assert_eq!(state.get_queued_count(), 1);
+        assert_eq!(state.get_completed_count(), 3);
+        assert_eq!(state.get_cached_count(), 1);
+        assert_eq!(state.get_failed_count(), 1);
+    }
+
+    #[test]
+    fn repaints_after_non_target_actions() {
+        let mut state = DashboardState::new(1);
+        let action = Action::new(ActionNode::SyncWorkspace);
+
+        state.start_action(&action, &ActionNode::SyncWorkspace);
+        state.finish_action(&action);
+
+        assert!(state.repaint);
+    }
+}
+
+mod logs {
+    use super::*;
+
+    #[test]
+    fn appends_lines_to_the_target() {
+        let mut state = DashboardState::new(2);
+
+        start(&mut state, "app:a");
+        start(&mut state, "app:b");
+
+        state.append_log(
+            &Target::parse("app:a").unwrap(),
+            "\u{1b}[32mone\u{1b}[0m\ntwo",
+        );
+
+        assert_eq!(state.actions[0].logs, vec!["one", "two"]);
+        assert!(state.actions[1].logs.is_empty());
+    }
+
+    #[test]
+    fn follows_the_latest_running_action() {
+        let mut state = DashboardState::new(2);
+
+        let a = start(&mut state, "app:a");
+        start(&mut state, "app:b");
+
+        assert_eq!(state.get_selected_index(), Some(1));
+
+        finish(&mut state, a, ActionStatus::Passed);
+
+        assert_eq!(state.get_selected_index(), Some(1));
+
+        state.select_previous();
+
+        assert_eq!(state.get_selected_index(), Some(0));
+    }
+
+    #[test]
+    fn scrolls_and_follows_the_end() {
+        let mut state = DashboardState::new(1);
+
+        start(&mut state, "app:a");
+
+        state.log_height = 10;
+        state.append_log(
+            &Target::parse("app:a").unwrap(),
+            &(0..25)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+
+        assert_eq!(state.get_log_offset(), 15);
+
+        state.scroll_up();
+
+        assert_eq!(state.get_log_offset(), 5);
+
+        state.scroll_up();
+
+        assert_eq!(state.get_log_offset(), 0);
+
+        state.scroll_down();
+        state.scroll_down();
+
+        assert_eq!(state.log_offset, None);
+        assert_eq!(state.get_log_offset(), 15);
+    }
+}
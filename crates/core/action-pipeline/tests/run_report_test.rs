@@ -0,0 +1,122 @@
+use moon_action::{Action, ActionNode, ActionStatus, Attempt};
+use moon_action_context::ActionContext;
+use moon_action_pipeline::estimator::Estimator;
+use moon_action_pipeline::run_report::RunReport;
+use moon_platform::Runtime;
+use moon_utils::time::chrono::{self, NaiveDateTime};
+use std::time::Duration;
+
+fn create_action(target: &str, status: ActionStatus, start: u64, secs: u64) -> Action {
+    let started_at = NaiveDateTime::default() + chrono::Duration::seconds(start as i64);
+
+    Action {
+        duration: Some(Duration::from_secs(secs)),
+        label: format!("RunTarget({target})"),
+        node: Some(ActionNode::RunTarget(Runtime::System, target.into())),
+        started_at: Some(started_at),
+        status,
+        ..Action::default()
+    }
+}
+
+fn create_report<'a>(actions: &'a Vec<Action>, context: &'a ActionContext) -> RunReport<'a> {
+    RunReport::new(
+        actions,
+        context,
+        Duration::from_secs(10),
+        Estimator::calculate(actions, Duration::from_secs(10)),
+    )
+}
+
+mod junit {
+    use super::*;
+
+    #[test]
+    fn renders_a_testcase_per_action() {
+        let actions = vec![
+            create_action("app:build", ActionStatus::Passed, 0, 2),
+            create_action("app:lint", ActionStatus::Cached, 0, 1),
+        ];
+        let context = ActionContext::default();
+        let xml = create_report(&actions, &context).to_junit_xml();
+
+        assert!(xml.contains(r#"<testsuite name="moon" tests="2" failures="0" skipped="0""#));
+        assert!(xml.contains(r#"<testcase name="build" classname="app" time="2.000"/>"#));
+        assert!(xml.contains(r#"<testcase name="lint" classname="app" time="1.000"/>"#));
+    }
+
+    #[test]
+    fn includes_output_of_failures() {
+        let mut action = create_action("app:test", ActionStatus::Failed, 0, 1);
+        action.error = Some("Failed to run \u{1b}[33mjest\u{1b}[0m".into());
+        action.attempts = Some(vec![Attempt {
+            stdout: Some("1 passed".into()),
+            stderr: Some("expected <a> & <b>".into()),
+            ..Attempt::new(1)
+        }]);
+
+        let actions = vec![action];
+        let context = ActionContext::default();
+        let xml = create_report(&actions, &context).to_junit_xml();
+
+        assert!(xml.contains(r#"failures="1""#));
+        assert!(xml.contains(r#"<failure message="Failed to run jest"/>"#));
+        assert!(xml.contains("<system-out>1 passed</system-out>"));
+        assert!(xml.contains("<system-err>expected &lt;a&gt; &amp; &lt;b&gt;</system-err>"));
+    }
+
+    #[test]
+    fn marks_skipped_actions() {
+        let mut action = create_action("app:test", ActionStatus::Running, 0, 0);
+        action.skip("dependency app:build failed".into());
+
+        let actions = vec![action];
+        let context = ActionContext::default();
+        let xml = create_report(&actions, &context).to_junit_xml();
+
+        assert!(xml.contains(r#"skipped="1""#));
+        assert!(xml.contains(r#"<skipped message="dependency app:build failed"/>"#));
+    }
+}
+
+mod chrome_trace {
+    use super::*;
+
+    #[test]
+    fn places_concurrent_actions_on_separate_lanes() {
+        let actions = vec![
+            create_action("app:a", ActionStatus::Passed, 0, 4),
+            create_action("app:b", ActionStatus::Passed, 1, 2),
+            create_action("app:c", ActionStatus::Passed, 4, 1),
+        ];
+        let context = ActionContext::default();
+        let trace = create_report(&actions, &context).to_chrome_trace();
+
+        let events = trace
+            .trace_events
+            .iter()
+            .map(|event| (event.name.as_str(), event.ts, event.dur, event.tid))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            events,
+            vec![
+                ("RunTarget(app:a)", 0, 4_000_000, 1),
+                ("RunTarget(app:b)", 1_000_000, 2_000_000, 2),
+                ("RunTarget(app:c)", 4_000_000, 1_000_000, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_actions_that_never_started() {
+        let actions = vec![Action {
+            label: "SyncWorkspace".into(),
+            ..Action::default()
+        }];
+        let context = ActionContext::default();
+        let trace = create_report(&actions, &context).to_chrome_trace();
+
+        assert!(trace.trace_events.is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+use moon_action::ActionNode;
+use moon_action_pipeline::scheduler::Scheduler;
+use moon_dep_graph::{DepGraph, DepGraphType};
+use moon_platform::Runtime;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::time::Duration;
+
+fn run_target(id: &str) -> ActionNode {
+    ActionNode::RunTarget(Runtime::System, id.into())
+}
+
+fn create_durations(list: &[(&str, u64)]) -> FxHashMap<String, Duration> {
+    FxHashMap::from_iter(
+        list.iter()
+            .map(|(id, secs)| (id.to_string(), Duration::from_secs(*secs))),
+    )
+}
+
+mod scheduler {
+    use super::*;
+
+    #[test]
+    fn starts_critical_path_first() {
+        let mut graph = DepGraphType::new();
+        let a = graph.add_node(run_target("app:a"));
+        let b = graph.add_node(run_target("app:b"));
+        let c = graph.add_node(run_target("app:c"));
+
+        // c depends on a
+        graph.add_edge(c, a, ());
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler = Scheduler::new(
+            &dep_graph,
+            &create_durations(&[("app:a", 1), ("app:b", 10), ("app:c", 20)]),
+            &FxHashSet::default(),
+        )
+        .unwrap();
+
+        assert_eq!(scheduler.get_priority(&a), Duration::from_secs(21));
+        assert_eq!(scheduler.get_priority(&b), Duration::from_secs(10));
+        assert_eq!(scheduler.get_priority(&c), Duration::from_secs(20));
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((a, ())));
+        assert_eq!(scheduler.next(|_| Some(())), Some((b, ())));
+    }
+
+    #[test]
+    fn waits_for_dependencies_to_complete() {
+        let mut graph = DepGraphType::new();
+        let a = graph.add_node(run_target("app:a"));
+        let b = graph.add_node(run_target("app:b"));
+
+        graph.add_edge(b, a, ());
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler =
+            Scheduler::new(&dep_graph, &FxHashMap::default(), &FxHashSet::default()).unwrap();
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((a, ())));
+        assert_eq!(scheduler.next(|_| Some(())), None);
+
+        scheduler.complete(a);
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((b, ())));
+
+        scheduler.complete(b);
+
+        assert!(scheduler.is_complete());
+    }
+
+    #[test]
+    fn uses_average_duration_without_history() {
+        let mut graph = DepGraphType::new();
+        let a = graph.add_node(run_target("app:a"));
+        let b = graph.add_node(run_target("app:b"));
+        let c = graph.add_node(run_target("app:c"));
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let scheduler = Scheduler::new(
+            &dep_graph,
+            &create_durations(&[("app:a", 2), ("app:b", 4)]),
+            &FxHashSet::default(),
+        )
+        .unwrap();
+
+        assert_eq!(scheduler.get_priority(&a), Duration::from_secs(2));
+        assert_eq!(scheduler.get_priority(&b), Duration::from_secs(4));
+        assert_eq!(scheduler.get_priority(&c), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn skips_nodes_that_cannot_start() {
+        let mut graph = DepGraphType::new();
+        let a = graph.add_node(run_target("app:a"));
+        let b = graph.add_node(run_target("app:b"));
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler = Scheduler::new(
+            &dep_graph,
+            &create_durations(&[("app:a", 10), ("app:b", 1)]),
+            &FxHashSet::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            scheduler.next(|index| if index == a { None } else { Some(()) }),
+            Some((b, ()))
+        );
+        assert_eq!(scheduler.next(|_| Some(())), Some((a, ())));
+    }
+
+    #[test]
+    fn runs_interactive_targets_alone() {
+        let mut graph = DepGraphType::new();
+        let a = graph.add_node(run_target("app:a"));
+        let b = graph.add_node(ActionNode::RunInteractiveTarget(
+            Runtime::System,
+            "app:b".into(),
+        ));
+        let c = graph.add_node(run_target("app:c"));
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler = Scheduler::new(
+            &dep_graph,
+            &create_durations(&[("app:a", 1), ("app:c", 2)]),
+            &FxHashSet::default(),
+        )
+        .unwrap();
+
+        // Nothing else is running, so the interactive target starts first
+        assert_eq!(scheduler.next(|_| Some(())), Some((b, ())));
+        assert_eq!(scheduler.next(|_| Some(())), None);
+
+        scheduler.complete(b);
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((c, ())));
+        assert_eq!(scheduler.next(|_| Some(())), Some((a, ())));
+    }
+
+    #[test]
+    fn starts_persistent_targets_last() {
+        let mut graph = DepGraphType::new();
+        let a = graph.add_node(ActionNode::RunPersistentTarget(
+            Runtime::System,
+            "app:a".into(),
+        ));
+        let b = graph.add_node(run_target("app:b"));
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler =
+            Scheduler::new(&dep_graph, &FxHashMap::default(), &FxHashSet::default()).unwrap();
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((b, ())));
+        assert_eq!(scheduler.next(|_| Some(())), None);
+
+        scheduler.complete(b);
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((a, ())));
+    }
+
+    #[test]
+    fn releases_dependents_of_probed_targets_once_ready() {
+        let mut graph = DepGraphType::new();
+        let server = graph.add_node(ActionNode::RunPersistentTarget(
+            Runtime::System,
+            "app:server".into(),
+        ));
+        let e2e = graph.add_node(run_target("app:e2e"));
+        let build = graph.add_node(run_target("app:build"));
+
+        graph.add_edge(server, build, ());
+        graph.add_edge(e2e, server, ());
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler = Scheduler::new(
+            &dep_graph,
+            &FxHashMap::default(),
+            &FxHashSet::from_iter([server]),
+        )
+        .unwrap();
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((build, ())));
+        assert_eq!(scheduler.next(|_| Some(())), None);
+
+        scheduler.complete(build);
+
+        // Started before its dependents, instead of last
+        assert_eq!(scheduler.next(|_| Some(())), Some((server, ())));
+        assert_eq!(scheduler.next(|_| Some(())), None);
+
+        scheduler.ready(server);
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((e2e, ())));
+
+        scheduler.complete(e2e);
+
+        assert!(!scheduler.is_complete());
+    }
+
+    #[test]
+    fn releases_dependents_of_probed_targets_that_exit() {
+        let mut graph = DepGraphType::new();
+        let server = graph.add_node(ActionNode::RunPersistentTarget(
+            Runtime::System,
+            "app:server".into(),
+        ));
+        let e2e = graph.add_node(run_target("app:e2e"));
+
+        graph.add_edge(e2e, server, ());
+
+        let dep_graph = DepGraph::new(graph, FxHashMap::default());
+        let mut scheduler = Scheduler::new(
+            &dep_graph,
+            &FxHashMap::default(),
+            &FxHashSet::from_iter([server]),
+        )
+        .unwrap();
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((server, ())));
+
+        scheduler.complete(server);
+
+        assert_eq!(scheduler.next(|_| Some(())), Some((e2e, ())));
+
+        scheduler.complete(e2e);
+
+        assert!(scheduler.is_complete());
+    }
+}
@@ -102,6 +102,8 @@ pub struct Action {
     #[serde(skip)]
     pub node: Option<ActionNode>,
 
+    pub skipped_reason: Option<String>,
+
     pub started_at: Option<NaiveDateTime>,
 
     #[serde(skip)]
@@ -124,6 +126,7 @@ impl Action {
             label: node.label(),
             log_target: String::new(),
             node: Some(node),
+            skipped_reason: None,
             started_at: None,
             start_time: None,
             status: ActionStatus::Running,
@@ -154,6 +157,13 @@ impl Action {
         self.finish(ActionStatus::Failed);
     }
 
+    /// Skip the action without processing it, for example,
+    /// when one of its dependencies has failed.
+    pub fn skip(&mut self, reason: String) {
+        self.skipped_reason = Some(reason);
+        self.finish(ActionStatus::Skipped);
+    }
+
     pub fn has_failed(&self) -> bool {
         has_failed(&self.status)
     }
@@ -1,4 +1,5 @@
-use crate::dep_graph::{DepGraph, DepGraphType, IndicesType};
+use crate::dep_graph::{DepGraph, DepGraphType, EdgesType, IndicesType, ReasonsType};
+use crate::task_condition::check_task_condition;
 use moon_action::ActionNode;
 use moon_common::path::WorkspaceRelativePathBuf;
 use moon_common::Id;
@@ -25,10 +26,12 @@ type TouchedFilePaths = FxHashSet<WorkspaceRelativePathBuf>;
 /// be confused with our tasks) or a "dependency graph".
 pub struct DepGraphBuilder<'ws> {
     all_query: Option<Criteria>,
+    finally_edges: EdgesType,
     graph: DepGraphType,
     indices: IndicesType,
     project_graph: &'ws ProjectGraph,
     runtimes: FxHashMap<String, RuntimePair>,
+    unmet_conditions: ReasonsType,
 }
 
 impl<'ws> DepGraphBuilder<'ws> {
@@ -51,15 +54,19 @@ impl<'ws> DepGraphBuilder<'ws> {
 
         DepGraphBuilder {
             all_query: None,
+            finally_edges: FxHashSet::default(),
             graph,
             indices,
             project_graph,
             runtimes: FxHashMap::default(),
+            unmet_conditions: FxHashMap::default(),
         }
     }
 
     pub fn build(&mut self) -> DepGraph {
         DepGraph::new(mem::take(&mut self.graph), mem::take(&mut self.indices))
+            .with_finally_edges(mem::take(&mut self.finally_edges))
+            .with_unmet_conditions(mem::take(&mut self.unmet_conditions))
     }
 
     pub fn set_query(&mut self, input: &str) -> miette::Result<()> {
@@ -320,6 +327,25 @@ impl<'ws> DepGraphBuilder<'ws> {
             }
         }
 
+        // Tasks with an unmet condition are still added to the graph, so that
+        // they can be reported as skipped, but their dependencies are not
+        if let Some(reason) =
+            check_task_condition(task, project, self.project_graph.get_workspace_root())?
+        {
+            trace!(
+                target: LOG_TARGET,
+                "Condition for target {} not met ({}), skipping",
+                color::label(&target.id),
+                reason,
+            );
+
+            let index = self.insert_node(&node);
+
+            self.unmet_conditions.insert(index, reason);
+
+            return Ok(Some(index));
+        }
+
         trace!(
             target: LOG_TARGET,
             "Adding {} to graph",
@@ -350,6 +376,23 @@ impl<'ws> DepGraphBuilder<'ws> {
             }
         }
 
+        // Cleanup targets run after this target, whether it passed or failed
+        if !task.options.finally.is_empty() {
+            trace!(
+                target: LOG_TARGET,
+                "Adding cleanup targets {} for target {}",
+                map_list(&task.options.finally, |f| color::symbol(f)),
+                color::label(target),
+            );
+
+            for finally_target in &task.options.finally {
+                for finally_index in self.run_target(finally_target, None)?.1 {
+                    self.graph.add_edge(finally_index, index, ());
+                    self.finally_edges.insert((finally_index, index));
+                }
+            }
+        }
+
         Ok(Some(index))
     }
 
@@ -9,20 +9,76 @@ use rustc_hash::{FxHashMap, FxHashSet};
 
 pub type DepGraphType = DiGraph<ActionNode, ()>;
 pub type IndicesType = FxHashMap<ActionNode, NodeIndex>;
+pub type EdgesType = FxHashSet<(NodeIndex, NodeIndex)>;
+pub type ReasonsType = FxHashMap<NodeIndex, String>;
 pub type BatchedTopoSort = Vec<Vec<NodeIndex>>;
 
 /// A directed acyclic graph (DAG) for the work that needs to be processed, based on a
 /// project or task's dependency chain. This is also known as a "task graph" (not to
 /// be confused with our tasks) or a "dependency graph".
 pub struct DepGraph {
+    /// Edges (from cleanup to owner) that must be followed regardless
+    /// of whether the owner passed, failed, or was skipped.
+    finally_edges: EdgesType,
+
     graph: DepGraphType,
 
     indices: IndicesType,
+
+    /// Nodes whose task condition was not met, and the reason why,
+    /// that must be skipped instead of ran.
+    unmet_conditions: ReasonsType,
 }
 
 impl DepGraph {
     pub fn new(graph: DepGraphType, indices: IndicesType) -> Self {
-        DepGraph { graph, indices }
+        DepGraph {
+            finally_edges: FxHashSet::default(),
+            graph,
+            indices,
+            unmet_conditions: FxHashMap::default(),
+        }
+    }
+
+    pub fn with_finally_edges(mut self, edges: EdgesType) -> Self {
+        self.finally_edges = edges;
+        self
+    }
+
+    pub fn with_unmet_conditions(mut self, conditions: ReasonsType) -> Self {
+        self.unmet_conditions = conditions;
+        self
+    }
+
+    /// Return the indices of cleanup nodes that always run after the provided node.
+    pub fn get_finally_of(&self, index: &NodeIndex) -> Vec<NodeIndex> {
+        let mut finally = self
+            .finally_edges
+            .iter()
+            .filter(|(_, owner)| owner == index)
+            .map(|(cleanup, _)| *cleanup)
+            .collect::<Vec<_>>();
+        finally.sort();
+        finally
+    }
+
+    /// Return true if the dependent runs after the dependency as cleanup,
+    /// and must not be skipped when the dependency fails.
+    pub fn is_finally_edge(&self, dependent: &NodeIndex, dependency: &NodeIndex) -> bool {
+        self.finally_edges.contains(&(*dependent, *dependency))
+    }
+
+    /// Return the indices of all nodes that the provided node directly depends on.
+    pub fn get_dependencies_of(&self, index: &NodeIndex) -> Vec<NodeIndex> {
+        let mut deps = self.graph.neighbors(*index).collect::<Vec<_>>();
+        deps.sort();
+        deps.dedup();
+        deps
+    }
+
+    /// Return the nodes whose task condition was not met, mapped to the reason.
+    pub fn get_unmet_conditions(&self) -> &ReasonsType {
+        &self.unmet_conditions
     }
 
     pub fn get_index_from_node(&self, node: &ActionNode) -> Option<&NodeIndex> {
@@ -37,6 +93,10 @@ impl DepGraph {
         self.graph.node_weight(*index)
     }
 
+    pub fn get_node_indices(&self) -> Vec<NodeIndex> {
+        self.graph.node_indices().collect()
+    }
+
     pub fn sort_topological(&self) -> miette::Result<Vec<NodeIndex>> {
         let list = match toposort(&self.graph, None) {
             Ok(nodes) => nodes,
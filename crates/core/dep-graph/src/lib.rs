@@ -1,7 +1,9 @@
 mod dep_builder;
 mod dep_graph;
 mod errors;
+mod task_condition;
 
 pub use dep_builder::DepGraphBuilder;
 pub use dep_graph::*;
 pub use errors::DepGraphError;
+pub use task_condition::*;
@@ -0,0 +1,74 @@
+use moon_project::Project;
+use moon_query::{build_query, Queryable};
+use moon_task::Task;
+use starbase_utils::glob;
+use std::env::{self, consts};
+use std::path::Path;
+
+/// Check whether the task's condition (if any) is met by the current host,
+/// and return the reason it was not met, so that the task can be skipped.
+pub fn check_task_condition(
+    task: &Task,
+    project: &Project,
+    workspace_root: &Path,
+) -> miette::Result<Option<String>> {
+    let Some(condition) = &task.condition else {
+        return Ok(None);
+    };
+
+    if !condition.os.is_empty() && !condition.os.iter().any(|os| os == consts::OS) {
+        return Ok(Some(format!(
+            "host OS {} is not {}",
+            consts::OS,
+            condition.os.join(" or ")
+        )));
+    }
+
+    if !condition.arch.is_empty() && !condition.arch.iter().any(|arch| arch == consts::ARCH) {
+        return Ok(Some(format!(
+            "host arch {} is not {}",
+            consts::ARCH,
+            condition.arch.join(" or ")
+        )));
+    }
+
+    for var in &condition.env {
+        match var.split_once('=') {
+            Some((name, expected)) => {
+                if env::var(name).ok().as_deref() != Some(expected) {
+                    return Ok(Some(format!("env var {name} is not {expected}")));
+                }
+            }
+            None => {
+                if env::var(var).map(|value| value.is_empty()).unwrap_or(true) {
+                    return Ok(Some(format!("env var {var} is not set")));
+                }
+            }
+        };
+    }
+
+    for file in &condition.files {
+        let (root, pattern) = match file.strip_prefix('/') {
+            Some(pattern) => (workspace_root, pattern),
+            None => (project.root.as_path(), file.as_str()),
+        };
+
+        let exists = if glob::is_glob(pattern) {
+            !glob::walk(root, &[pattern])?.is_empty()
+        } else {
+            root.join(pattern).exists()
+        };
+
+        if !exists {
+            return Ok(Some(format!("no files match {file}")));
+        }
+    }
+
+    if let Some(query) = &condition.query {
+        if !project.matches_criteria(&build_query(query)?)? {
+            return Ok(Some(format!("project does not match {query}")));
+        }
+    }
+
+    Ok(None)
+}
@@ -77,6 +77,9 @@ pub enum Event<'e> {
         error: Option<String>,
         target: &'e Target,
     },
+    TargetReady {
+        target: &'e Target,
+    },
     TargetOutputArchiving {
         hash: &'e str,
         project: &'e Project,
@@ -99,7 +102,9 @@ pub enum Event<'e> {
     },
     #[serde(rename_all = "camelCase")]
     TargetOutputHydrated {
-        archive_path: PathBuf,
+        // Not set when outputs were hydrated without an archive,
+        // for example, when written directly from a REAPI server
+        archive_path: Option<PathBuf>,
         hash: &'e str,
         project: &'e Project,
         target: &'e Target,
@@ -134,6 +139,7 @@ impl<'e> Event<'e> {
             Event::PipelineFinished { .. } => "pipeline.finished",
             Event::TargetRunning { .. } => "target.running",
             Event::TargetRan { .. } => "target.ran",
+            Event::TargetReady { .. } => "target.ready",
             Event::TargetOutputArchiving { .. } => "target-output.archiving",
             Event::TargetOutputArchived { .. } => "target-output.archived",
             Event::TargetOutputHydrating { .. } => "target-output.hydrating",
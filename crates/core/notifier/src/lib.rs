@@ -1,3 +1,5 @@
+mod open_telemetry;
 mod webhooks;
 
+pub use open_telemetry::*;
 pub use webhooks::*;
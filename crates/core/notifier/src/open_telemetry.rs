@@ -0,0 +1,412 @@
+use moon_action::{Action, ActionNode};
+use moon_config::OpenTelemetryConfig;
+use moon_emitter::{Event, EventFlow, Subscriber};
+use moon_logger::{trace, warn};
+use moon_workspace::Workspace;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+const LOG_TARGET: &str = "moon:notifier:open-telemetry";
+
+// https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OtlpAnyValue {
+    BoolValue(bool),
+    // 64-bit integers are encoded as strings
+    IntValue(String),
+    StringValue(String),
+}
+
+#[derive(Serialize)]
+pub struct OtlpKeyValue {
+    pub key: String,
+    pub value: OtlpAnyValue,
+}
+
+#[derive(Serialize)]
+pub struct OtlpStatus {
+    /// 0 = unset, 1 = ok, 2 = error
+    pub code: u8,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtlpSpan {
+    pub attributes: Vec<OtlpKeyValue>,
+
+    pub end_time_unix_nano: String,
+
+    /// 1 = internal
+    pub kind: u8,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+
+    pub span_id: String,
+
+    pub start_time_unix_nano: String,
+
+    pub status: OtlpStatus,
+
+    pub trace_id: String,
+}
+
+impl OtlpSpan {
+    fn new(name: String, trace_id: &str, parent_span_id: Option<&str>) -> Self {
+        OtlpSpan {
+            attributes: vec![],
+            end_time_unix_nano: String::new(),
+            kind: 1,
+            name,
+            parent_span_id: parent_span_id.map(|id| id.to_owned()),
+            span_id: Uuid::new_v4().simple().to_string()[0..16].to_owned(),
+            start_time_unix_nano: now_unix_nano(),
+            status: OtlpStatus {
+                code: 0,
+                message: None,
+            },
+            trace_id: trace_id.to_owned(),
+        }
+    }
+
+    fn attr(&mut self, key: &str, value: OtlpAnyValue) {
+        self.attributes.push(OtlpKeyValue {
+            key: key.to_owned(),
+            value,
+        });
+    }
+
+    fn end(&mut self, error: Option<&String>) {
+        self.end_time_unix_nano = now_unix_nano();
+        self.status = match error {
+            Some(error) => OtlpStatus {
+                code: 2,
+                message: Some(error.to_owned()),
+            },
+            None => OtlpStatus {
+                code: 1,
+                message: None,
+            },
+        };
+    }
+}
+
+#[derive(Serialize)]
+pub struct OtlpResource {
+    pub attributes: Vec<OtlpKeyValue>,
+}
+
+#[derive(Serialize)]
+pub struct OtlpScope {
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct OtlpScopeSpans {
+    pub scope: OtlpScope,
+    pub spans: Vec<OtlpSpan>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtlpResourceSpans {
+    pub resource: OtlpResource,
+    pub scope_spans: Vec<OtlpScopeSpans>,
+}
+
+/// Body of an OTLP/HTTP `ExportTraceServiceRequest`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtlpTraceRequest {
+    pub resource_spans: Vec<OtlpResourceSpans>,
+}
+
+fn now_unix_nano() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn get_node_type(node: &ActionNode) -> &'static str {
+    match node {
+        ActionNode::InstallDeps(_) => "InstallDeps",
+        ActionNode::InstallProjectDeps(_, _) => "InstallProjectDeps",
+        ActionNode::RunTarget(_, _) => "RunTarget",
+        ActionNode::RunInteractiveTarget(_, _) => "RunInteractiveTarget",
+        ActionNode::RunPersistentTarget(_, _) => "RunPersistentTarget",
+        ActionNode::SetupTool(_) => "SetupTool",
+        ActionNode::SyncProject(_, _) => "SyncProject",
+        ActionNode::SyncWorkspace => "SyncWorkspace",
+    }
+}
+
+fn get_node_target(node: &ActionNode) -> Option<&str> {
+    match node {
+        ActionNode::RunTarget(_, target)
+        | ActionNode::RunInteractiveTarget(_, target)
+        | ActionNode::RunPersistentTarget(_, target) => Some(target.as_str()),
+        _ => None,
+    }
+}
+
+/// Records the pipeline as a root span, with a child span for each action,
+/// and exports them as OTLP JSON once the pipeline has finished.
+pub struct OpenTelemetrySubscriber {
+    config: OpenTelemetryConfig,
+
+    /// Hashes of targets, captured while checking the cache.
+    hashes: FxHashMap<String, String>,
+
+    /// Action spans that have started but not finished, by action label.
+    pending: FxHashMap<String, OtlpSpan>,
+
+    root: Option<OtlpSpan>,
+
+    spans: Vec<OtlpSpan>,
+
+    trace_id: String,
+}
+
+impl OpenTelemetrySubscriber {
+    pub fn new(config: OpenTelemetryConfig) -> Self {
+        OpenTelemetrySubscriber {
+            config,
+            hashes: FxHashMap::default(),
+            pending: FxHashMap::default(),
+            root: None,
+            spans: vec![],
+            trace_id: Uuid::new_v4().simple().to_string(),
+        }
+    }
+
+    fn start_action(&mut self, action: &Action, node: &ActionNode) {
+        let mut span = OtlpSpan::new(
+            action.label.clone(),
+            &self.trace_id,
+            self.root.as_ref().map(|root| root.span_id.as_str()),
+        );
+
+        span.attr(
+            "moon.action.type",
+            OtlpAnyValue::StringValue(get_node_type(node).into()),
+        );
+
+        if let Some(target) = get_node_target(node) {
+            span.attr("moon.target", OtlpAnyValue::StringValue(target.into()));
+        }
+
+        self.pending.insert(action.label.clone(), span);
+    }
+
+    fn finish_action(&mut self, action: &Action, error: Option<&String>, node: &ActionNode) {
+        let Some(mut span) = self.pending.remove(&action.label) else {
+            return;
+        };
+
+        if let Ok(serde_json::Value::String(status)) = serde_json::to_value(action.status) {
+            span.attr("moon.action.status", OtlpAnyValue::StringValue(status));
+        }
+
+        span.attr(
+            "moon.action.attempts",
+            OtlpAnyValue::IntValue(
+                action
+                    .attempts
+                    .as_ref()
+                    .map(|attempts| attempts.len())
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        );
+
+        span.attr("moon.action.flaky", OtlpAnyValue::BoolValue(action.flaky));
+
+        if let Some(hash) = get_node_target(node).and_then(|target| self.hashes.get(target)) {
+            span.attr("moon.hash", OtlpAnyValue::StringValue(hash.to_owned()));
+        }
+
+        span.end(error);
+
+        self.spans.push(span);
+    }
+
+    fn finish_pipeline(&mut self, error: Option<&String>) -> OtlpTraceRequest {
+        let mut spans = std::mem::take(&mut self.spans);
+
+        // Actions may still be running when the pipeline is aborted
+        for (_, mut span) in self.pending.drain() {
+            span.end(error);
+            spans.push(span);
+        }
+
+        if let Some(mut root) = self.root.take() {
+            root.end(error);
+            spans.insert(0, root);
+        }
+
+        OtlpTraceRequest {
+            resource_spans: vec![OtlpResourceSpans {
+                resource: OtlpResource {
+                    attributes: vec![OtlpKeyValue {
+                        key: "service.name".into(),
+                        value: OtlpAnyValue::StringValue(self.config.service_name.clone()),
+                    }],
+                },
+                scope_spans: vec![OtlpScopeSpans {
+                    scope: OtlpScope {
+                        name: "moon".into(),
+                    },
+                    spans,
+                }],
+            }],
+        }
+    }
+
+    async fn export(&self, request: OtlpTraceRequest, workspace: &Workspace) {
+        let body = match serde_json::to_string(&request) {
+            Ok(body) => body,
+            Err(error) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to serialize spans. Failure: {}",
+                    color::muted_light(error.to_string())
+                );
+
+                return;
+            }
+        };
+
+        if let Some(file) = &self.config.file {
+            let path = workspace.root.join(file);
+
+            trace!(
+                target: LOG_TARGET,
+                "Writing spans to {}",
+                color::path(&path)
+            );
+
+            if let Err(error) = fs::write_file(&path, &body) {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to write spans to {}. Failure: {}",
+                    color::path(&path),
+                    color::muted_light(error.to_string())
+                );
+            }
+        }
+
+        if let Some(endpoint) = &self.config.endpoint {
+            trace!(
+                target: LOG_TARGET,
+                "Exporting spans to {}",
+                color::url(endpoint)
+            );
+
+            let mut request = reqwest::Client::new()
+                .post(endpoint)
+                .header("Content-Type", "application/json")
+                .body(body);
+
+            for (key, value) in &self.config.headers {
+                request = request.header(key, value);
+            }
+
+            let failure = match request.send().await {
+                Ok(response) if response.status().is_success() => None,
+                Ok(response) => Some(response.status().to_string()),
+                Err(error) => Some(error.to_string()),
+            };
+
+            if let Some(failure) = failure {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to export spans to {}. Failure: {}",
+                    color::url(endpoint),
+                    color::muted_light(failure)
+                );
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber for OpenTelemetrySubscriber {
+    async fn on_emit<'a>(
+        &mut self,
+        event: &Event<'a>,
+        workspace: &Workspace,
+    ) -> miette::Result<EventFlow> {
+        match event {
+            Event::PipelineStarted { actions_count, .. } => {
+                let mut root = OtlpSpan::new("pipeline".into(), &self.trace_id, None);
+
+                root.attr(
+                    "moon.actions_count",
+                    OtlpAnyValue::IntValue(actions_count.to_string()),
+                );
+
+                self.root = Some(root);
+            }
+            Event::ActionStarted { action, node } => {
+                self.start_action(action, node);
+            }
+            Event::TargetOutputCacheCheck { hash, target } => {
+                self.hashes
+                    .insert(target.as_str().to_owned(), (*hash).to_owned());
+            }
+            Event::ActionFinished {
+                action,
+                error,
+                node,
+            } => {
+                self.finish_action(action, error.as_ref(), node);
+            }
+            Event::PipelineFinished {
+                cached_count,
+                failed_count,
+                passed_count,
+                ..
+            } => {
+                if let Some(root) = &mut self.root {
+                    root.attr(
+                        "moon.cached_count",
+                        OtlpAnyValue::IntValue(cached_count.to_string()),
+                    );
+                    root.attr(
+                        "moon.failed_count",
+                        OtlpAnyValue::IntValue(failed_count.to_string()),
+                    );
+                    root.attr(
+                        "moon.passed_count",
+                        OtlpAnyValue::IntValue(passed_count.to_string()),
+                    );
+                }
+
+                let request = self.finish_pipeline(None);
+
+                self.export(request, workspace).await;
+            }
+            Event::PipelineAborted { error } => {
+                let request = self.finish_pipeline(Some(error));
+
+                self.export(request, workspace).await;
+            }
+            _ => {}
+        };
+
+        Ok(EventFlow::Continue)
+    }
+}
@@ -1,6 +1,8 @@
 use ci_env::{get_environment, CiEnvironment};
 use moon_emitter::{Event, EventFlow, Subscriber};
 use moon_logger::{error, trace};
+use moon_process::mask_secrets;
+use moon_project::Project;
 use moon_utils::time::{chrono::prelude::*, now_timestamp};
 use moon_workspace::Workspace;
 use serde::{Deserialize, Serialize};
@@ -27,6 +29,50 @@ pub struct WebhookPayload<T: Serialize> {
     pub uuid: String,
 }
 
+/// Payloads and run reports include projects, tasks, and passthrough args,
+/// which may contain secrets, so collect the secret values of every task within
+/// the provided projects, once for the entire pipeline.
+pub fn collect_secrets<'a>(
+    projects: impl IntoIterator<Item = &'a Project>,
+    workspace: &Workspace,
+) -> miette::Result<Vec<String>> {
+    let mut secrets = vec![];
+
+    for project in projects {
+        for task in project.tasks.values() {
+            for value in task
+                .get_secret_env(&workspace.config.runner.secret_env_vars)?
+                .into_values()
+            {
+                if !value.is_empty() && !secrets.contains(&value) {
+                    secrets.push(value);
+                }
+            }
+        }
+    }
+
+    // Longest first, so that overlapping secrets are fully masked
+    secrets.sort_by(|a, b| b.len().cmp(&a.len()));
+
+    Ok(secrets)
+}
+
+/// Serialize the value to JSON, with the JSON encoded values of secrets masked.
+pub fn to_masked_json<T: Serialize>(value: &T, secrets: &[String]) -> String {
+    let mut encoded = secrets
+        .iter()
+        .map(|secret| {
+            let secret = serde_json::to_string(secret).unwrap();
+
+            secret[1..secret.len() - 1].to_owned()
+        })
+        .collect::<Vec<_>>();
+
+    encoded.sort_by(|a, b| b.len().cmp(&a.len()));
+
+    mask_secrets(&serde_json::to_string(value).unwrap(), &encoded)
+}
+
 pub async fn notify_webhook(
     url: String,
     body: String,
@@ -46,16 +92,18 @@ pub struct WebhooksSubscriber {
     enabled: bool,
     environment: Option<CiEnvironment>,
     requests: Vec<JoinHandle<()>>,
+    secrets: Vec<String>,
     url: String,
     uuid: String,
 }
 
 impl WebhooksSubscriber {
-    pub fn new(url: String) -> Self {
+    pub fn new(url: String, secrets: Vec<String>) -> Self {
         WebhooksSubscriber {
             enabled: true,
             environment: get_environment(),
             requests: vec![],
+            secrets,
             uuid: if url.contains("127.0.0.1") {
                 "XXXX-XXXX-XXXX-XXXX".into()
             } else {
@@ -91,7 +139,7 @@ impl Subscriber for WebhooksSubscriber {
             color::id(&payload.type_of),
         );
 
-        let body = serde_json::to_string(&payload).unwrap();
+        let body = to_masked_json(&payload, &self.secrets);
 
         // For the first event, we want to ensure that the webhook URL is valid
         // by sending the request and checking for a failure. If failed,
@@ -108,6 +108,12 @@ pub trait Platform: Send + Sync {
         Ok(0)
     }
 
+    /// Register the tool with the provided version (if it hasn't already been registered),
+    /// and return true if it has been installed. Unlike `setup_tool`, nothing is installed.
+    async fn is_tool_setup(&mut self, runtime: &Runtime) -> miette::Result<bool> {
+        Ok(true)
+    }
+
     /// Install dependencies in the target working directory with a tool and its
     /// dependency manager using the provided version.
     async fn install_deps(
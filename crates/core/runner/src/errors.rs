@@ -18,6 +18,44 @@ pub enum RunnerError {
         error: ProcessError,
     },
 
+    #[diagnostic(code(target_runner::sandbox_violation))]
+    #[error(
+        "Task {} failed after accessing paths outside of its sandbox. Declare them as inputs or outputs of the task.\n{}",
+        .target.style(Style::Label),
+        .paths,
+    )]
+    SandboxViolation { target: String, paths: String },
+
+    #[diagnostic(code(target_runner::sandbox_undedicated_output))]
+    #[error(
+        "Task {} declares output {}, which can't be written to within its sandbox, as it's not within a directory dedicated to outputs. Move it into a directory that doesn't contain inputs, like {}.",
+        .target.style(Style::Label),
+        .output.style(Style::File),
+        "dist".style(Style::File),
+    )]
+    SandboxUndedicatedOutput { target: String, output: String },
+
+    #[diagnostic(code(target_runner::timed_out))]
+    #[error(
+        "Task {} timed out after {} seconds and was terminated.",
+        .target.style(Style::Label),
+        .timeout.style(Style::Symbol),
+    )]
+    TimedOut { target: String, timeout: u64 },
+
+    #[diagnostic(code(target_runner::not_ready))]
+    #[error(
+        "Task {} did not become ready within {} seconds, while waiting for {}.",
+        .target.style(Style::Label),
+        .timeout.style(Style::Symbol),
+        .condition,
+    )]
+    NotReady {
+        target: String,
+        condition: String,
+        timeout: u64,
+    },
+
     #[diagnostic(code(target_runner::missing_dep_hash))]
     #[error(
         "Encountered a missing hash for target {}, which is a dependency of {}.\nThis either means the dependency hasn't ran, has failed, or there's a misconfiguration.\n\nTry disabling the target's cache, or marking it as local.",
@@ -26,6 +64,14 @@ pub enum RunnerError {
     )]
     MissingDependencyHash(String, String),
 
+    #[diagnostic(code(target_runner::undeclared_outputs))]
+    #[error(
+        "Task {} created or modified files that are not declared as outputs, and will be missing from the cache.\n{}",
+        .target.style(Style::Label),
+        .paths,
+    )]
+    UndeclaredOutputs { target: String, paths: String },
+
     #[diagnostic(code(target_runner::missing_output))]
     #[error("Target {} defines outputs, but none exist after being ran.", .0.style(Style::Label))]
     MissingOutput(String),
@@ -0,0 +1,129 @@
+use moon_common::consts::CONFIG_DIRNAME;
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_task::Task;
+use moon_vcs::BoxedVcs;
+use rustc_hash::FxHashMap;
+use starbase_utils::glob::GlobSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+#[derive(Default)]
+struct ProjectRuns {
+    running: usize,
+    started: usize,
+}
+
+fn get_project_runs() -> &'static Mutex<FxHashMap<String, ProjectRuns>> {
+    static PROJECT_RUNS: OnceLock<Mutex<FxHashMap<String, ProjectRuns>>> = OnceLock::new();
+
+    PROJECT_RUNS.get_or_init(Mutex::default)
+}
+
+/// Tracks a task running within a project, so that files changed in the project
+/// are only attributed to the task when no other task of the project ran alongside it.
+pub struct ProjectRun {
+    exclusive: bool,
+    project: String,
+    started: usize,
+}
+
+impl ProjectRun {
+    pub fn start(project: &str) -> ProjectRun {
+        let mut runs = get_project_runs().lock().unwrap();
+        let runs = runs.entry(project.to_owned()).or_default();
+
+        runs.running += 1;
+        runs.started += 1;
+
+        ProjectRun {
+            exclusive: runs.running == 1,
+            project: project.to_owned(),
+            started: runs.started,
+        }
+    }
+
+    /// Whether no other task of the project was running when this task started,
+    /// and no other task of the project has started since.
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+            && get_project_runs()
+                .lock()
+                .unwrap()
+                .get(&self.project)
+                .is_some_and(|runs| runs.started == self.started)
+    }
+}
+
+impl Drop for ProjectRun {
+    fn drop(&mut self) {
+        if let Some(runs) = get_project_runs().lock().unwrap().get_mut(&self.project) {
+            runs.running -= 1;
+        }
+    }
+}
+
+/// The modified times of all files within a directory, as known by VCS,
+/// so that files that were created or modified can be detected.
+#[derive(Debug, Default)]
+pub struct FileSnapshot {
+    files: FxHashMap<WorkspaceRelativePathBuf, Option<SystemTime>>,
+}
+
+impl FileSnapshot {
+    #[allow(clippy::borrowed_box)]
+    pub async fn capture(
+        vcs: &BoxedVcs,
+        dir: &str,
+        workspace_root: &Path,
+    ) -> miette::Result<FileSnapshot> {
+        let mut files = FxHashMap::default();
+
+        for file in vcs.get_file_tree_uncached(dir).await? {
+            let modified = fs::metadata(file.to_path(workspace_root))
+                .and_then(|meta| meta.modified())
+                .ok();
+
+            files.insert(file, modified);
+        }
+
+        Ok(FileSnapshot { files })
+    }
+
+    /// Return files that were created or modified since the previous snapshot.
+    pub fn get_changed_files(&self, previous: &FileSnapshot) -> Vec<WorkspaceRelativePathBuf> {
+        let mut changed = self
+            .files
+            .iter()
+            .filter(|(file, modified)| {
+                // Deleted files are not outputs
+                modified.is_some() && previous.files.get(*file) != Some(*modified)
+            })
+            .map(|(file, _)| file.to_owned())
+            .collect::<Vec<_>>();
+
+        changed.sort();
+        changed
+    }
+}
+
+/// Filter the changed files down to those that are not declared as outputs.
+pub fn find_undeclared_outputs(
+    task: &Task,
+    changed_files: Vec<WorkspaceRelativePathBuf>,
+) -> miette::Result<Vec<WorkspaceRelativePathBuf>> {
+    let globset = GlobSet::new(&task.output_globs)?;
+
+    Ok(changed_files
+        .into_iter()
+        .filter(|file| {
+            !file.starts_with(CONFIG_DIRNAME)
+                && !task
+                    .output_files
+                    .iter()
+                    .any(|output| file == output || file.starts_with(output))
+                && !globset.matches(file.as_str())
+        })
+        .collect())
+}
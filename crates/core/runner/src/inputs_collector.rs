@@ -101,16 +101,16 @@ fn is_valid_input_source(
     task.input_files.contains(&workspace_relative_path) || globset.matches(workspace_relative_input)
 }
 
-// Hash all inputs for a task, but exclude outputs
-// and moon specific configuration files!
+// Collect all inputs for a task as workspace relative paths,
+// but exclude outputs and moon specific configuration files!
 #[allow(clippy::borrowed_box)]
-pub async fn collect_and_hash_inputs(
+pub async fn collect_inputs(
     vcs: &BoxedVcs,
     task: &Task,
     project_root: &Path,
     workspace_root: &Path,
     hasher_config: &HasherConfig,
-) -> miette::Result<HashedInputs> {
+) -> miette::Result<Vec<String>> {
     let mut files_to_hash = FxHashSet::default(); // Absolute paths
     let globset = task.create_globset()?;
     let use_globs = project_root == workspace_root
@@ -178,6 +178,22 @@ pub async fn collect_and_hash_inputs(
 
     files_to_hash.retain(|f| is_valid_input_source(task, &globset, f));
 
+    Ok(files_to_hash)
+}
+
+// Hash all inputs for a task, but exclude outputs
+// and moon specific configuration files!
+#[allow(clippy::borrowed_box)]
+pub async fn collect_and_hash_inputs(
+    vcs: &BoxedVcs,
+    task: &Task,
+    project_root: &Path,
+    workspace_root: &Path,
+    hasher_config: &HasherConfig,
+) -> miette::Result<HashedInputs> {
+    let files_to_hash =
+        collect_inputs(vcs, task, project_root, workspace_root, hasher_config).await?;
+
     // 3: Extract hashes
 
     let mut hashed_inputs: HashedInputs = BTreeMap::new();
@@ -1,9 +1,17 @@
 mod errors;
+mod file_snapshot;
 pub mod inputs_collector;
+mod log_group;
+mod output_store;
+mod ready_check;
 mod run_state;
 mod runner;
+mod sandbox;
 mod target_hash;
 
 pub use errors::*;
+pub use file_snapshot::*;
+pub use log_group::*;
+pub use output_store::*;
 pub use run_state::*;
 pub use runner::*;
@@ -0,0 +1,79 @@
+use ci_env::CiProvider;
+use moon_utils::{is_ci, is_test_env};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Collapsible sections within the logs of a CI provider,
+/// used to group the buffered output of each target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogGroup {
+    // https://learn.microsoft.com/en-us/azure/devops/pipelines/scripts/logging-commands#formatting-commands
+    Azure,
+    // https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions#grouping-log-lines
+    GithubActions,
+    // https://docs.gitlab.com/ee/ci/jobs/#custom-collapsible-sections
+    Gitlab,
+}
+
+impl LogGroup {
+    pub fn detect() -> Option<LogGroup> {
+        if !is_ci() || is_test_env() {
+            return None;
+        }
+
+        match ci_env::detect_provider() {
+            CiProvider::AzurePipelines => Some(LogGroup::Azure),
+            CiProvider::GithubActions => Some(LogGroup::GithubActions),
+            CiProvider::Gitlab => Some(LogGroup::Gitlab),
+            _ => None,
+        }
+    }
+
+    /// Return the line that opens a section. Expanded sections are only
+    /// supported by GitLab, so other providers return nothing, and the
+    /// output should be printed as-is.
+    pub fn open(&self, name: &str, title: &str, expanded: bool) -> Option<String> {
+        match self {
+            LogGroup::Azure if !expanded => Some(format!("##[group]{title}")),
+            LogGroup::GithubActions if !expanded => Some(format!("::group::{title}")),
+            LogGroup::Gitlab => Some(format!(
+                "\x1b[0Ksection_start:{}:{}[collapsed={}]\r\x1b[0K{title}",
+                now_secs(),
+                to_section_name(name),
+                !expanded
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn close(&self, name: &str) -> String {
+        match self {
+            LogGroup::Azure => "##[endgroup]".into(),
+            LogGroup::GithubActions => "::endgroup::".into(),
+            LogGroup::Gitlab => format!(
+                "\x1b[0Ksection_end:{}:{}\r\x1b[0K",
+                now_secs(),
+                to_section_name(name)
+            ),
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// GitLab only supports letters, numbers, and `_`, `.`, or `-`
+fn to_section_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
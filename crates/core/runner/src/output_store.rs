@@ -0,0 +1,392 @@
+use crate::run_state::{get_output_logs, hash_contents};
+use miette::IntoDiagnostic;
+use moon_cache_item::{get_cache_mode, StoreManifest};
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_logger::{debug, warn};
+use moon_utils::path;
+use starbase_styles::color;
+use starbase_utils::{fs, glob, json};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::{process, thread};
+
+pub fn get_store_object_path(objects_dir: &Path, digest: &str) -> PathBuf {
+    objects_dir.join(&digest[0..2]).join(digest)
+}
+
+/// Verify the object's contents still match its digest, which is its file name.
+pub fn verify_store_object(object_file: &Path) -> miette::Result<bool> {
+    let digest = hash_contents(fs::open_file(object_file)?).into_diagnostic()?;
+
+    Ok(fs::file_name(object_file) == digest)
+}
+
+fn collect_output_files(
+    workspace_root: &Path,
+    output_paths: &[WorkspaceRelativePathBuf],
+) -> miette::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut globs = vec![];
+
+    for output in output_paths {
+        if glob::is_glob(output) {
+            globs.push(output.as_str());
+            continue;
+        }
+
+        let output_path = output.to_path(workspace_root);
+
+        if output_path.is_file() {
+            files.push(output_path);
+        } else if output_path.is_dir() {
+            for entry in fs::read_dir_all(&output_path)? {
+                let entry_path = entry.path();
+
+                if entry_path.is_file() {
+                    files.push(entry_path);
+                }
+            }
+        }
+    }
+
+    if !globs.is_empty() {
+        files.extend(glob::walk_files(workspace_root, &globs)?);
+    }
+
+    Ok(files)
+}
+
+#[cfg(target_os = "linux")]
+fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let src = std::fs::File::open(from)?;
+    let dest = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)?;
+
+    // https://man7.org/linux/man-pages/man2/ioctl_ficlone.2.html
+    if unsafe { libc::ioctl(dest.as_raw_fd(), libc::FICLONE as _, src.as_raw_fd()) } == 0 {
+        return dest.set_permissions(src.metadata()?.permissions());
+    }
+
+    let error = io::Error::last_os_error();
+
+    drop(dest);
+    std::fs::remove_file(to)?;
+
+    Err(error)
+}
+
+#[cfg(target_os = "macos")]
+fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(from.as_os_str().as_bytes())?;
+    let dest = CString::new(to.as_os_str().as_bytes())?;
+
+    // https://www.manpagez.com/man/2/clonefile/
+    if unsafe { libc::clonefile(src.as_ptr(), dest.as_ptr(), 0) } == 0 {
+        return Ok(());
+    }
+
+    Err(io::Error::last_os_error())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn clone_file(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(unix)]
+fn set_writable(path: &Path, writable: bool) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = std::fs::metadata(path)?.permissions();
+    let mode = perms.mode();
+
+    perms.set_mode(if writable {
+        mode | 0o200
+    } else {
+        mode & !0o222
+    });
+
+    std::fs::set_permissions(path, perms)
+}
+
+// Read-only files can't be removed on Windows, which would break cleaning
+// the cache, so objects are left writable, and are never hardlinked
+#[cfg(not(unix))]
+fn set_writable(_path: &Path, _writable: bool) -> io::Result<()> {
+    Ok(())
+}
+
+/// Objects are read-only, so one that is writable may have been modified.
+#[cfg(unix)]
+fn is_object_intact(object_path: &Path) -> bool {
+    std::fs::metadata(object_path)
+        .map(|meta| meta.permissions().readonly())
+        .unwrap_or_default()
+}
+
+#[cfg(not(unix))]
+fn is_object_intact(object_path: &Path) -> bool {
+    object_path.exists()
+}
+
+#[cfg(unix)]
+fn is_hardlinked_object(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::symlink_metadata(path)
+        .map(|meta| meta.nlink() > 1 && meta.permissions().readonly())
+        .unwrap_or_default()
+}
+
+#[cfg(not(unix))]
+fn is_hardlinked_object(_path: &Path) -> bool {
+    false
+}
+
+// Clones are copy-on-write, so the file can be modified without affecting
+// the source, and the copy is always writable, even if the source is not.
+fn copy_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if clone_file(from, to).is_err() {
+        std::fs::copy(from, to)?;
+    }
+
+    set_writable(to, true)
+}
+
+// Prefer clones, and otherwise hardlink the object, which is safe as objects
+// are read-only, and links are broken before a task writes to its outputs.
+// Hardlinks fail across file systems, in which case the object is copied.
+fn link_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if clone_file(from, to).is_ok() {
+        return set_writable(to, true);
+    }
+
+    #[cfg(unix)]
+    if std::fs::hard_link(from, to).is_ok() {
+        return Ok(());
+    }
+
+    std::fs::copy(from, to)?;
+
+    set_writable(to, true)
+}
+
+fn remove_output_files(
+    workspace_root: &Path,
+    output_paths: &[WorkspaceRelativePathBuf],
+) -> miette::Result<()> {
+    let mut globs = vec![];
+
+    for output in output_paths {
+        if glob::is_glob(output) {
+            globs.push(output.as_str());
+        } else {
+            fs::remove(output.to_path(workspace_root))?;
+        }
+    }
+
+    if !globs.is_empty() {
+        for file in glob::walk_files(workspace_root, &globs)? {
+            fs::remove_file(file)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn store_object(objects_dir: &Path, file: &Path) -> miette::Result<Option<String>> {
+    let digest = hash_contents(fs::open_file(file)?).into_diagnostic()?;
+    let object_path = get_store_object_path(objects_dir, &digest);
+
+    // Objects are immutable, so identical files are only stored once
+    if !object_path.exists() {
+        fs::create_dir_all(object_path.parent().unwrap())?;
+
+        // Write to a temporary file first, that's unique to this process and thread,
+        // so that other processes never observe or write to a partial object
+        let temp_path = object_path.with_file_name(format!(
+            "{digest}.{}-{:?}.tmp",
+            process::id(),
+            thread::current().id()
+        ));
+
+        copy_file(file, &temp_path).into_diagnostic()?;
+
+        // Verify the object once when it's stored, instead of on every hydrate,
+        // as the file may have been modified while it was being copied
+        if hash_contents(fs::open_file(&temp_path)?).into_diagnostic()? != digest {
+            warn!(
+                "Output {} was modified while being stored, skipping the store",
+                color::path(file),
+            );
+
+            fs::remove_file(&temp_path)?;
+
+            return Ok(None);
+        }
+
+        set_writable(&temp_path, false).into_diagnostic()?;
+        fs::rename(&temp_path, &object_path)?;
+    }
+
+    Ok(Some(digest))
+}
+
+/// Store the task's outputs, and its stdout/stderr logs, as unpacked
+/// content-addressed objects, and write a manifest of them for the hash.
+pub fn store_outputs(
+    state_dir: &Path,
+    manifest_file: &Path,
+    objects_dir: &Path,
+    workspace_root: &Path,
+    output_paths: &[WorkspaceRelativePathBuf],
+) -> miette::Result<bool> {
+    if !get_cache_mode().is_writable() || manifest_file.exists() {
+        return Ok(false);
+    }
+
+    let mut manifest = StoreManifest::default();
+
+    for file in collect_output_files(workspace_root, output_paths)? {
+        let Ok(rel_file) = file.strip_prefix(workspace_root) else {
+            continue;
+        };
+
+        let Some(digest) = store_object(objects_dir, &file)? else {
+            return Ok(false);
+        };
+
+        manifest
+            .files
+            .insert(path::to_virtual_string(rel_file)?, digest);
+    }
+
+    let (stdout_path, stderr_path) = get_output_logs(state_dir);
+
+    for (log_path, log_digest) in [
+        (stdout_path, &mut manifest.stdout),
+        (stderr_path, &mut manifest.stderr),
+    ] {
+        if log_path.exists() {
+            let Some(digest) = store_object(objects_dir, &log_path)? else {
+                return Ok(false);
+            };
+
+            *log_digest = Some(digest);
+        }
+    }
+
+    json::write_file(manifest_file, &manifest, false)?;
+
+    Ok(true)
+}
+
+/// Hydrate the task's outputs by linking the stored objects into place.
+/// Returns false if there's no manifest, or its objects no longer exist
+/// or are no longer read-only.
+pub fn link_outputs(
+    state_dir: &Path,
+    manifest_file: &Path,
+    objects_dir: &Path,
+    workspace_root: &Path,
+    output_paths: &[WorkspaceRelativePathBuf],
+) -> miette::Result<bool> {
+    if !get_cache_mode().is_readable() || !manifest_file.exists() {
+        return Ok(false);
+    }
+
+    let manifest: StoreManifest = json::read_file(manifest_file)?;
+    let digests = manifest
+        .files
+        .values()
+        .chain(manifest.stdout.iter())
+        .chain(manifest.stderr.iter())
+        .collect::<Vec<_>>();
+
+    // Objects may have been cleaned up as stale, or modified in place. Objects are
+    // verified when stored, and are read-only, so rehashing them here would only
+    // make hydration slower than unpacking an archive. Use `moon cache verify`.
+    for digest in &digests {
+        let object_path = get_store_object_path(objects_dir, digest);
+
+        if !is_object_intact(&object_path) {
+            warn!(
+                "Store manifest {} references a missing or modified object, evicting from cache",
+                color::path(manifest_file),
+            );
+
+            fs::remove(object_path)?;
+            fs::remove(manifest_file)?;
+
+            return Ok(false);
+        }
+    }
+
+    // Remove stale outputs first, so that only the stored files exist
+    remove_output_files(workspace_root, output_paths)?;
+
+    for (file, digest) in &manifest.files {
+        link_file(
+            &get_store_object_path(objects_dir, digest),
+            &workspace_root.join(file),
+        )
+        .into_diagnostic()?;
+    }
+
+    // Logs are written to by the runner, so are never linked
+    let (stdout_path, stderr_path) = get_output_logs(state_dir);
+
+    for (digest, log_path) in [
+        (&manifest.stdout, stdout_path),
+        (&manifest.stderr, stderr_path),
+    ] {
+        if let Some(digest) = digest {
+            fs::remove(&log_path)?;
+            copy_file(&get_store_object_path(objects_dir, digest), &log_path).into_diagnostic()?;
+        }
+    }
+
+    debug!(
+        "Linked {} outputs from store manifest {}",
+        manifest.files.len(),
+        color::path(manifest_file)
+    );
+
+    Ok(true)
+}
+
+/// Replace outputs that are hardlinked to store objects with writable copies,
+/// so that the task can write to its outputs without modifying the store.
+pub fn break_output_links(
+    workspace_root: &Path,
+    output_paths: &[WorkspaceRelativePathBuf],
+) -> miette::Result<()> {
+    for file in collect_output_files(workspace_root, output_paths)? {
+        if !is_hardlinked_object(&file) {
+            continue;
+        }
+
+        let temp_path =
+            file.with_file_name(format!("{}.{}.tmp", fs::file_name(&file), process::id()));
+
+        copy_file(&file, &temp_path).into_diagnostic()?;
+        fs::rename(&temp_path, &file)?;
+    }
+
+    Ok(())
+}
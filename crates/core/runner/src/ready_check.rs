@@ -0,0 +1,145 @@
+use crate::errors::RunnerError;
+use moon_config::TaskReadyCheckConfig;
+use moon_logger::debug;
+use regex::Regex;
+use starbase_styles::color;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{sleep, timeout_at, Duration, Instant};
+
+const LOG_TARGET: &str = "moon:runner:ready-check";
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+async fn wait_for_file(path: &Path) {
+    while !path.exists() {
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn wait_for_output(pattern: &Regex, output: &mut UnboundedReceiver<String>) -> bool {
+    while let Some(line) = output.recv().await {
+        if pattern.is_match(&console::strip_ansi_codes(&line)) {
+            return true;
+        }
+    }
+
+    // The process has exited without ever matching
+    false
+}
+
+async fn is_port_open(port: u16) -> bool {
+    // Servers may only listen on one of the loopback addresses
+    // (for example, `localhost` may resolve to IPv6), so try both
+    for host in [
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(Ipv6Addr::LOCALHOST),
+    ] {
+        if TcpStream::connect((host, port)).await.is_ok() {
+            return true;
+        }
+    }
+
+    false
+}
+
+async fn wait_for_port(port: u16) {
+    while !is_port_open(port).await {
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn wait_for_url(url: &str) {
+    let client = reqwest::Client::new();
+
+    loop {
+        if let Ok(response) = client.get(url).send().await {
+            if response.status().is_success() {
+                return;
+            }
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Wait until every condition of the task's ready check has been met,
+/// or fail if they haven't all been met within the configured timeout.
+pub async fn wait_until_ready(
+    target: &str,
+    check: &TaskReadyCheckConfig,
+    project_root: &Path,
+    mut output: Option<UnboundedReceiver<String>>,
+) -> Result<(), RunnerError> {
+    let deadline = Instant::now() + Duration::from_secs(check.timeout);
+
+    let fail = |condition: String| RunnerError::NotReady {
+        target: target.to_owned(),
+        condition,
+        timeout: check.timeout,
+    };
+
+    async fn wait<F: Future>(deadline: Instant, future: F) -> Option<F::Output> {
+        timeout_at(deadline, future).await.ok()
+    }
+
+    if let Some(port) = check.port {
+        debug!(
+            target: LOG_TARGET,
+            "Waiting for port {} to accept connections",
+            color::symbol(port.to_string())
+        );
+
+        if wait(deadline, wait_for_port(port)).await.is_none() {
+            return Err(fail(format!("port {port} to accept connections")));
+        }
+    }
+
+    if let Some(url) = &check.url {
+        debug!(
+            target: LOG_TARGET,
+            "Waiting for {} to respond successfully",
+            color::url(url)
+        );
+
+        if wait(deadline, wait_for_url(url)).await.is_none() {
+            return Err(fail(format!("{url} to respond successfully")));
+        }
+    }
+
+    if let Some(file) = &check.file {
+        let path = project_root.join(file);
+
+        debug!(
+            target: LOG_TARGET,
+            "Waiting for file {} to exist",
+            color::path(&path)
+        );
+
+        if wait(deadline, wait_for_file(&path)).await.is_none() {
+            return Err(fail(format!("file {file} to exist")));
+        }
+    }
+
+    if let (Some(pattern), Some(output)) = (&check.output, &mut output) {
+        // Validated when the config was loaded
+        let regex = Regex::new(pattern).unwrap();
+
+        debug!(
+            target: LOG_TARGET,
+            "Waiting for output to match {}",
+            color::shell(pattern)
+        );
+
+        if !wait(deadline, wait_for_output(&regex, output))
+            .await
+            .unwrap_or_default()
+        {
+            return Err(fail(format!("output to match /{pattern}/")));
+        }
+    }
+
+    Ok(())
+}
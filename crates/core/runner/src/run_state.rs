@@ -1,10 +1,15 @@
+use flate2::read::GzDecoder;
+use miette::IntoDiagnostic;
 use moon_cache_item::{cache_item, get_cache_mode};
 use moon_common::path::WorkspaceRelativePathBuf;
-use moon_logger::{map_list, warn};
+use moon_logger::{debug, map_list, warn};
+use sha2::{Digest, Sha256};
 use starbase_archive::tar::{TarPacker, TarUnpacker};
 use starbase_archive::Archiver;
 use starbase_styles::color;
-use starbase_utils::{fs, glob};
+use starbase_utils::{fs, glob, json};
+use std::collections::BTreeMap;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 
 cache_item!(
@@ -16,6 +21,68 @@ cache_item!(
     }
 );
 
+cache_item!(
+    pub struct ArchiveDigest {
+        pub archive: String,
+        pub files: BTreeMap<String, String>,
+    }
+);
+
+pub(crate) fn hash_contents(mut reader: impl Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+
+    io::copy(&mut reader, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Create a digest of the archive itself, and of every file within it.
+pub fn create_archive_digest(archive_file: &Path) -> miette::Result<ArchiveDigest> {
+    let mut digest = ArchiveDigest {
+        archive: hash_contents(fs::open_file(archive_file)?).into_diagnostic()?,
+        ..ArchiveDigest::default()
+    };
+
+    let mut archive = tar::Archive::new(GzDecoder::new(fs::open_file(archive_file)?));
+
+    for entry in archive.entries().into_diagnostic()? {
+        let mut entry = entry.into_diagnostic()?;
+
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry
+            .path()
+            .into_diagnostic()?
+            .to_string_lossy()
+            .to_string();
+
+        digest
+            .files
+            .insert(path, hash_contents(&mut entry).into_diagnostic()?);
+    }
+
+    Ok(digest)
+}
+
+/// Verify the archive matches the digest that was created when it was packed.
+/// Returns true if there's no digest to verify against.
+pub fn verify_archive_digest(archive_file: &Path, digest_file: &Path) -> miette::Result<bool> {
+    if !digest_file.exists() {
+        return Ok(true);
+    }
+
+    let expected: ArchiveDigest = json::read_file(digest_file)?;
+
+    // Corrupted archives may fail to decompress entirely
+    let Ok(actual) = create_archive_digest(archive_file) else {
+        return Ok(false);
+    };
+
+    Ok(expected == actual)
+}
+
 fn create_archive<'o>(
     workspace_root: &'o Path,
     archive_file: &'o Path,
@@ -40,6 +107,7 @@ fn create_archive<'o>(
 pub fn archive_outputs(
     state_dir: &Path,
     archive_file: &Path,
+    digest_file: &Path,
     workspace_root: &Path,
     output_paths: &[WorkspaceRelativePathBuf],
 ) -> miette::Result<bool> {
@@ -59,6 +127,8 @@ pub fn archive_outputs(
 
         archive.pack(TarPacker::new_gz)?;
 
+        json::write_file(digest_file, &create_archive_digest(archive_file)?, false)?;
+
         return Ok(true);
     }
 
@@ -68,10 +138,27 @@ pub fn archive_outputs(
 pub fn hydrate_outputs(
     state_dir: &Path,
     archive_file: &Path,
+    digest_file: &Path,
     workspace_root: &Path,
     output_paths: &[WorkspaceRelativePathBuf],
 ) -> miette::Result<bool> {
     if get_cache_mode().is_readable() && archive_file.exists() {
+        // A truncated or tampered archive must not be hydrated, so treat it
+        // as a cache miss, and evict it so that it's recreated on the next run
+        if !verify_archive_digest(archive_file, digest_file)? {
+            warn!(
+                "Archive {} does not match its digest, evicting from cache",
+                color::path(archive_file),
+            );
+
+            fs::remove(archive_file)?;
+            fs::remove(digest_file)?;
+
+            return Ok(false);
+        }
+
+        debug!("Verified archive {}", color::path(archive_file));
+
         let archive = create_archive(workspace_root, archive_file, output_paths);
         let cache_logs = get_output_logs(state_dir);
         let stdout_log = workspace_root.join("stdout.log");
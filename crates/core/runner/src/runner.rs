@@ -1,4 +1,9 @@
+use crate::file_snapshot::{find_undeclared_outputs, FileSnapshot, ProjectRun};
+use crate::log_group::LogGroup;
+use crate::output_store::break_output_links;
+use crate::ready_check::wait_until_ready;
 use crate::run_state::{load_output_logs, save_output_logs, RunTargetState};
+use crate::sandbox::create_sandbox;
 use crate::target_hash::TargetHasher;
 use crate::{errors::RunnerError, inputs_collector};
 use console::Term;
@@ -6,29 +11,35 @@ use miette::IntoDiagnostic;
 use moon_action::{ActionStatus, Attempt};
 use moon_action_context::{ActionContext, TargetState};
 use moon_cache_item::CacheItem;
-use moon_config::{TaskOptionAffectedFiles, TaskOutputStyle};
+use moon_config::{HydrationMode, TaskOptionAffectedFiles, TaskOutputStyle, UndeclaredOutputsMode};
 use moon_emitter::{Emitter, Event, EventFlow};
-use moon_hash::ContentHasher;
+use moon_hash::{diff_manifests, ContentHasher, ManifestChangeKind};
 use moon_logger::{debug, warn};
 use moon_platform::PlatformManager;
 use moon_platform_runtime::Runtime;
-use moon_process::{args, output_to_error, output_to_string, Command, Output};
+use moon_process::{
+    args, output_to_error, output_to_string, Command, Output, ProcessError, Sandbox,
+};
 use moon_project::Project;
-use moon_target::{TargetError, TargetScope};
+use moon_target::{Target, TargetError, TargetScope};
 use moon_task::Task;
 use moon_terminal::{label_checkpoint, Checkpoint};
 use moon_utils::{is_ci, is_test_env, path, time};
 use moon_workspace::Workspace;
 use rustc_hash::FxHashMap;
 use starbase_styles::color;
-use starbase_utils::glob;
+use starbase_utils::{fs, glob};
 use tokio::{
+    sync::mpsc,
     task,
     time::{sleep, Duration},
 };
 
 const LOG_TARGET: &str = "moon:runner";
 
+/// Receives each line of output for a target.
+pub type OutputListener = mpsc::UnboundedSender<(Target, String)>;
+
 pub enum HydrateFrom {
     LocalCache,
     PreviousOutput,
@@ -40,8 +51,17 @@ pub struct Runner<'a> {
 
     emitter: &'a Emitter,
 
+    log_group: Option<LogGroup>,
+
+    /// Receives output (including labels) instead of the terminal,
+    /// for example, when rendering a dashboard.
+    output_listener: Option<OutputListener>,
+
     project: &'a Project,
 
+    /// Names and values of environment variables that must be masked.
+    secret_env: FxHashMap<String, String>,
+
     stderr: Term,
 
     stdout: Term,
@@ -66,10 +86,15 @@ impl<'a> Runner<'a> {
             cache.data.target = task.target.to_string();
         }
 
+        let secret_env = task.get_secret_env(&workspace.config.runner.secret_env_vars)?;
+
         Ok(Runner {
             cache,
             emitter,
+            log_group: LogGroup::detect(),
+            output_listener: None,
             project,
+            secret_env,
             stderr: Term::buffered_stderr(),
             stdout: Term::buffered_stdout(),
             task,
@@ -117,11 +142,21 @@ impl<'a> Runner<'a> {
         Ok(())
     }
 
-    pub async fn hydrate(&self, from: HydrateFrom) -> miette::Result<ActionStatus> {
+    /// Hydrate the outputs and return the cached status. Returns `None` if
+    /// the outputs could not be hydrated, which should be treated as a miss.
+    pub async fn hydrate(&self, from: HydrateFrom) -> miette::Result<Option<ActionStatus>> {
         // Only hydrate when the hash is different from the previous build,
         // as we can assume the outputs from the previous build still exist?
-        if matches!(from, HydrateFrom::LocalCache) || matches!(from, HydrateFrom::RemoteCache) {
-            self.hydrate_outputs().await?;
+        if (matches!(from, HydrateFrom::LocalCache) || matches!(from, HydrateFrom::RemoteCache))
+            && !self.hydrate_outputs().await?
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Failed to hydrate outputs for hash {}, treating as a cache miss",
+                color::hash(&self.cache.data.hash),
+            );
+
+            return Ok(None);
         }
 
         let mut comments = vec![match from {
@@ -138,24 +173,25 @@ impl<'a> Runner<'a> {
         self.print_cache_item()?;
         self.flush_output()?;
 
-        Ok(if matches!(from, HydrateFrom::RemoteCache) {
+        Ok(Some(if matches!(from, HydrateFrom::RemoteCache) {
             ActionStatus::CachedFromRemote
         } else {
             ActionStatus::Cached
-        })
+        }))
     }
 
     /// If we are cached (hash match), hydrate the project with the
-    /// cached task outputs found in the hashed archive.
-    pub async fn hydrate_outputs(&self) -> miette::Result<()> {
+    /// cached task outputs found in the hashed archive. Returns false
+    /// if no archive was hydrated.
+    pub async fn hydrate_outputs(&self) -> miette::Result<bool> {
         let hash = &self.cache.data.hash;
 
         if hash.is_empty() {
-            return Ok(());
+            return Ok(false);
         }
 
         // Hydrate outputs from the cache
-        if let EventFlow::Return(archive_path) = self
+        let EventFlow::Return(archive_path) = self
             .emitter
             .emit(Event::TargetOutputHydrating {
                 hash,
@@ -164,22 +200,24 @@ impl<'a> Runner<'a> {
                 task: self.task,
             })
             .await?
-        {
-            self.emitter
-                .emit(Event::TargetOutputHydrated {
-                    archive_path: archive_path.into(),
-                    hash,
-                    project: self.project,
-                    target: &self.task.target,
-                    task: self.task,
-                })
-                .await?;
-        }
+        else {
+            return Ok(false);
+        };
+
+        self.emitter
+            .emit(Event::TargetOutputHydrated {
+                archive_path: (!archive_path.is_empty()).then(|| archive_path.into()),
+                hash,
+                project: self.project,
+                target: &self.task.target,
+                task: self.task,
+            })
+            .await?;
 
         // Update the run state with the new hash
         self.cache.save()?;
 
-        Ok(())
+        Ok(true)
     }
 
     /// Create a hasher that is shared amongst all platforms.
@@ -202,6 +240,8 @@ impl<'a> Runner<'a> {
             hash.hash_args(&context.passthrough_args);
         }
 
+        hash.hash_secrets(&self.secret_env);
+
         hash.hash_inputs(
             inputs_collector::collect_and_hash_inputs(
                 vcs,
@@ -250,6 +290,44 @@ impl<'a> Runner<'a> {
             // We need to handle non-zero's manually
             .set_error_on_nonzero(false);
 
+        // Timed processes are spawned in their own process group, which can't
+        // read from the terminal, so timeouts don't apply to interactive tasks
+        if let Some(timeout) = task.options.timeout {
+            if context.interactive || task.is_interactive() {
+                warn!(
+                    target: LOG_TARGET,
+                    "Target {} is interactive, ignoring its timeout",
+                    color::label(&task.target),
+                );
+            } else {
+                command.set_timeout(Duration::from_secs(timeout));
+            }
+        }
+
+        // Persistent targets are restarted when watching, so spawn them in their own
+        // process group, that can be terminated along with the target's descendants
+        if context.watch && task.is_persistent() {
+            command.set_process_group(&task.target.id);
+        }
+
+        // Only allow access to the task's inputs and outputs
+        if task.options.sandbox {
+            if Sandbox::is_supported() {
+                command.set_sandbox(create_sandbox(workspace, project, task).await?);
+            } else {
+                warn!(
+                    target: LOG_TARGET,
+                    "Sandboxing requires Linux 5.13+ with Landlock enabled, running target {} without a sandbox",
+                    color::label(&task.target),
+                );
+            }
+        }
+
+        // Mask secrets in output and logs
+        for value in self.secret_env.values() {
+            command.add_secret(value);
+        }
+
         // Wrap in a shell
         if task.platform.is_system() && task.options.shell {
             command.with_shell();
@@ -360,6 +438,30 @@ impl<'a> Runner<'a> {
         Ok(())
     }
 
+    pub fn set_output_listener(&mut self, listener: OutputListener) {
+        self.output_listener = Some(listener);
+    }
+
+    fn write_stderr(&self, line: &str) -> miette::Result<()> {
+        if let Some(listener) = &self.output_listener {
+            let _ = listener.send((self.task.target.clone(), line.to_owned()));
+
+            return Ok(());
+        }
+
+        self.stderr.write_line(line).into_diagnostic()
+    }
+
+    fn write_stdout(&self, line: &str) -> miette::Result<()> {
+        if let Some(listener) = &self.output_listener {
+            let _ = listener.send((self.task.target.clone(), line.to_owned()));
+
+            return Ok(());
+        }
+
+        self.stdout.write_line(line).into_diagnostic()
+    }
+
     pub fn get_short_hash(&self) -> &str {
         if self.cache.data.hash.is_empty() {
             "" // Empty when cache is disabled
@@ -429,14 +531,12 @@ impl<'a> Runner<'a> {
         Ok(false)
     }
 
-    /// Hash the target based on all current parameters and return early
-    /// if this target hash has already been cached. Based on the state
-    /// of the target and project, determine the hydration strategy as well.
-    pub async fn is_cached(
-        &mut self,
-        context: &mut ActionContext,
+    /// Generate a hash for the target based on all current parameters.
+    pub async fn generate_hash(
+        &self,
+        context: &ActionContext,
         runtime: &Runtime,
-    ) -> miette::Result<Option<HydrateFrom>> {
+    ) -> miette::Result<(String, ContentHasher)> {
         let mut hasher = self
             .workspace
             .hash_engine
@@ -463,6 +563,19 @@ impl<'a> Runner<'a> {
             color::id(&self.task.target)
         );
 
+        Ok((hash, hasher))
+    }
+
+    /// Hash the target based on all current parameters and return early
+    /// if this target hash has already been cached. Based on the state
+    /// of the target and project, determine the hydration strategy as well.
+    pub async fn is_cached(
+        &mut self,
+        context: &mut ActionContext,
+        runtime: &Runtime,
+    ) -> miette::Result<Option<HydrateFrom>> {
+        let (hash, mut hasher) = self.generate_hash(context, runtime).await?;
+
         context.target_states.insert(
             self.task.target.clone(),
             TargetState::Completed(hash.clone()),
@@ -470,6 +583,47 @@ impl<'a> Runner<'a> {
 
         // Hash is the same as the previous build, so simply abort!
         // However, ensure the outputs also exist, otherwise we should hydrate
+        if self.is_previous_output(&hash)? {
+            return Ok(Some(HydrateFrom::PreviousOutput));
+        }
+
+        // Load the manifest of the previous run, so that a miss can be explained
+        let previous_manifest_path = self
+            .workspace
+            .hash_engine
+            .get_manifest_path(&self.cache.data.hash);
+        let previous_manifest =
+            if !self.cache.data.hash.is_empty() && previous_manifest_path.exists() {
+                Some(fs::read_file(&previous_manifest_path)?)
+            } else {
+                None
+            };
+        let current_manifest = hasher.serialize()?.to_owned();
+
+        self.cache.data.hash = hash.clone();
+
+        // Refresh the hash manifest
+        self.workspace.hash_engine.save_manifest(hasher)?;
+
+        // Check if that hash exists in the cache
+        if let Some(from) = self.check_output_cache(&hash).await? {
+            return Ok(Some(from));
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Cache miss for hash {}, continuing run",
+            color::hash(&hash),
+        );
+
+        self.explain_cache_miss(context, previous_manifest.as_deref(), &current_manifest)?;
+
+        Ok(None)
+    }
+
+    /// Return true if the hash is the same as the previous build,
+    /// and its outputs still exist.
+    pub fn is_previous_output(&self, hash: &str) -> miette::Result<bool> {
         if self.cache.data.exit_code == 0
             && self.cache.data.hash == hash
             && self.has_outputs(true)?
@@ -477,22 +631,21 @@ impl<'a> Runner<'a> {
             debug!(
                 target: LOG_TARGET,
                 "Cache hit for hash {}, reusing previous build",
-                color::hash(&hash),
+                color::hash(hash),
             );
 
-            return Ok(Some(HydrateFrom::PreviousOutput));
+            return Ok(true);
         }
 
-        self.cache.data.hash = hash.clone();
-
-        // Refresh the hash manifest
-        self.workspace.hash_engine.save_manifest(hasher)?;
+        Ok(false)
+    }
 
-        // Check if that hash exists in the cache
+    /// Check whether the outputs for the hash exist in the local or remote cache.
+    pub async fn check_output_cache(&self, hash: &str) -> miette::Result<Option<HydrateFrom>> {
         if let EventFlow::Return(value) = self
             .emitter
             .emit(Event::TargetOutputCacheCheck {
-                hash: &hash,
+                hash,
                 target: &self.task.target,
             })
             .await?
@@ -502,7 +655,7 @@ impl<'a> Runner<'a> {
                     debug!(
                         target: LOG_TARGET,
                         "Cache hit for hash {}, hydrating from local cache",
-                        color::hash(&hash),
+                        color::hash(hash),
                     );
 
                     return Ok(Some(HydrateFrom::LocalCache));
@@ -511,7 +664,7 @@ impl<'a> Runner<'a> {
                     debug!(
                         target: LOG_TARGET,
                         "Cache hit for hash {}, hydrating from remote cache",
-                        color::hash(&hash),
+                        color::hash(hash),
                     );
 
                     return Ok(Some(HydrateFrom::RemoteCache));
@@ -520,15 +673,89 @@ impl<'a> Runner<'a> {
             }
         }
 
-        debug!(
-            target: LOG_TARGET,
-            "Cache miss for hash {}, continuing run",
-            color::hash(&hash),
-        );
-
         Ok(None)
     }
 
+    /// Diff the previous manifest against the current, and log what has changed.
+    /// When `--explain-miss` is passed, the changes are also printed.
+    pub fn explain_cache_miss(
+        &self,
+        context: &ActionContext,
+        previous_manifest: Option<&str>,
+        current_manifest: &str,
+    ) -> miette::Result<()> {
+        let Some(previous_manifest) = previous_manifest else {
+            debug!(
+                target: LOG_TARGET,
+                "No previous hash manifest for target {}, unable to explain cache miss",
+                color::label(&self.task.target),
+            );
+
+            if context.explain_miss {
+                self.print_checkpoint(Checkpoint::Announcement, &["cache miss, no previous run"])?;
+            }
+
+            return Ok(());
+        };
+
+        let changes = diff_manifests(previous_manifest, current_manifest)?;
+
+        for change in &changes {
+            debug!(
+                target: LOG_TARGET,
+                "Cache miss for target {}, {} changed ({} -> {})",
+                color::label(&self.task.target),
+                color::id(&change.key),
+                change.previous.as_deref().unwrap_or("none"),
+                change.current.as_deref().unwrap_or("none"),
+            );
+        }
+
+        if !context.explain_miss {
+            return Ok(());
+        }
+
+        self.print_checkpoint(
+            Checkpoint::Announcement,
+            &[format!("cache miss, {} changes", changes.len())],
+        )?;
+
+        for change in &changes {
+            let kind = match change.kind {
+                ManifestChangeKind::Dependency => "dependency",
+                ManifestChangeKind::EnvVar => "env var",
+                ManifestChangeKind::Input => "input",
+                ManifestChangeKind::Task => "task",
+                ManifestChangeKind::Toolchain => "toolchain",
+            };
+
+            // Inputs and dependencies are hashes, so the values aren't useful
+            let detail = match (&change.previous, &change.current) {
+                (None, _) => "added".to_owned(),
+                (_, None) => "removed".to_owned(),
+                (Some(previous), Some(current)) => match change.kind {
+                    ManifestChangeKind::Dependency | ManifestChangeKind::Input => {
+                        "changed".to_owned()
+                    }
+                    _ => format!("{previous} → {current}"),
+                },
+            };
+
+            self.write_stdout(&format!(
+                "  {} {} {}",
+                color::muted_light(format!("{kind:<10}")),
+                if matches!(change.kind, ManifestChangeKind::Input) {
+                    color::file(change.get_name())
+                } else {
+                    color::id(change.get_name())
+                },
+                color::muted(format!("({detail})")),
+            ))?;
+        }
+
+        Ok(())
+    }
+
     /// Run the command as a child process and capture its output. If the process fails
     /// and `retry_count` is greater than 0, attempt the process again in case it passes.
     pub async fn run_command(
@@ -546,22 +773,42 @@ impl<'a> Runner<'a> {
         let output;
         let error;
 
+        // Persistent tasks never complete, so their dependents are released once
+        // the ready check passes. Matching output requires it to be streamed.
+        let task = self.task;
+        let ready_check = task.options.ready_check.as_ref().filter(|_| is_persistent);
+        let listen_to_output = ready_check.is_some_and(|check| check.output.is_some());
+        let mut is_ready = false;
+
+        // When a listener is set, output is streamed to it instead of the terminal.
+        let is_listening = self.output_listener.is_some();
+
         // When a task is configured as local (no caching), or the interactive flag is passed,
         // we don't "capture" stdout/stderr (which breaks stdin) and let it stream natively.
-        let is_interactive = (!self.task.options.cache && context.primary_targets.len() == 1)
-            || context.interactive
-            || self.task.is_interactive();
+        let is_interactive = !listen_to_output
+            && !is_listening
+            && ((!self.task.options.cache && context.primary_targets.len() == 1)
+                || context.interactive
+                || self.task.is_interactive());
 
         // When the primary target, always stream the output for a better developer experience.
         // However, transitive targets can opt into streaming as well.
-        let should_stream_output = if let Some(output_style) = &self.task.options.output_style {
+        let should_stream_output = if listen_to_output || is_listening {
+            true
+        } else if let Some(output_style) = &self.task.options.output_style {
             matches!(output_style, TaskOutputStyle::Stream)
+        } else if self.log_group.is_some() && !is_persistent && !is_interactive {
+            // Streamed output of concurrent targets is interleaved, and can't be
+            // grouped, so buffer it instead, to wrap it in a collapsible section
+            false
         } else {
             is_primary || is_real_ci
         };
 
         // Transitive targets may run concurrently, so differentiate them with a prefix.
-        let stream_prefix = if is_real_ci || !is_primary || context.primary_targets.len() > 1 {
+        let stream_prefix = if is_listening {
+            None
+        } else if is_real_ci || !is_primary || context.primary_targets.len() > 1 {
             Some(&self.task.target.id)
         } else {
             None
@@ -570,7 +817,7 @@ impl<'a> Runner<'a> {
         // For long-running process, log a message every 30 seconds to indicate it's still running
         let interval_target = self.task.target.clone();
         let interval_handle = task::spawn(async move {
-            if is_persistent {
+            if is_persistent || is_listening {
                 return;
             }
 
@@ -595,23 +842,96 @@ impl<'a> Runner<'a> {
             self.print_target_command(context, command)?;
             self.flush_output()?;
 
-            let possible_output = if should_stream_output {
+            if should_stream_output {
                 if let Some(prefix) = stream_prefix {
                     command.set_prefix(prefix, primary_longest_width);
                 }
+            }
 
-                if is_interactive {
-                    command.create_async().exec_stream_output().await
-                } else {
-                    command
-                        .create_async()
-                        .exec_stream_and_capture_output()
-                        .await
+            let mut output_receiver = None;
+            let mut output_forwarder = None;
+
+            if listen_to_output && !is_ready {
+                let (sender, receiver) = mpsc::unbounded_channel();
+
+                command.add_output_listener(sender);
+                output_receiver = Some(receiver);
+            }
+
+            if let Some(listener) = &self.output_listener {
+                let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+                let listener = listener.clone();
+                let target = task.target.clone();
+
+                command.add_output_listener(sender).set_print_output(false);
+
+                output_forwarder = Some(task::spawn(async move {
+                    while let Some(line) = receiver.recv().await {
+                        let _ = listener.send((target.clone(), line));
+                    }
+                }));
+            }
+
+            let possible_output = {
+                let mut async_command = command.create_async();
+                let exec = async {
+                    if should_stream_output {
+                        if is_interactive {
+                            async_command.exec_stream_output().await
+                        } else {
+                            async_command.exec_stream_and_capture_output().await
+                        }
+                    } else {
+                        async_command.exec_capture_output().await
+                    }
+                };
+
+                tokio::pin!(exec);
+
+                match ready_check {
+                    Some(check) if !is_ready => {
+                        tokio::select! {
+                            // process exited before it became ready
+                            result = &mut exec => result,
+                            ready = wait_until_ready(
+                                &task.target.id,
+                                check,
+                                &self.project.root,
+                                output_receiver,
+                            ) => {
+                                if let Err(error) = ready {
+                                    // dropping the process will kill it
+                                    attempt.done(ActionStatus::Failed);
+                                    attempts.push(attempt);
+
+                                    interval_handle.abort();
+
+                                    return Err(error.into());
+                                }
+
+                                is_ready = true;
+
+                                self.emitter
+                                    .emit(Event::TargetReady {
+                                        target: &task.target,
+                                    })
+                                    .await?;
+
+                                exec.await
+                            }
+                        }
+                    }
+                    _ => exec.await,
                 }
-            } else {
-                command.create_async().exec_capture_output().await
             };
 
+            // Close the channels, and wait for all output to be forwarded
+            command.output_listeners.clear();
+
+            if let Some(forwarder) = output_forwarder {
+                let _ = forwarder.await;
+            }
+
             match possible_output {
                 // zero and non-zero exit codes
                 Ok(out) => {
@@ -635,17 +955,26 @@ impl<'a> Runner<'a> {
 
                         break;
                     } else if attempt_index >= attempt_total {
-                        error = Some(RunnerError::RunFailed {
-                            target: self.task.target.id.clone(),
-                            query: format!(
-                                "moon query hash {}",
-                                if is_test_env() {
-                                    "hash1234"
-                                } else {
-                                    self.get_short_hash()
-                                }
-                            ),
-                            error: output_to_error(self.task.command.clone(), &out, false),
+                        let violations = self.find_sandbox_violations(command, &out);
+
+                        error = Some(if !violations.is_empty() {
+                            RunnerError::SandboxViolation {
+                                target: self.task.target.id.clone(),
+                                paths: violations,
+                            }
+                        } else {
+                            RunnerError::RunFailed {
+                                target: self.task.target.id.clone(),
+                                query: format!(
+                                    "moon query hash {}",
+                                    if is_test_env() {
+                                        "hash1234"
+                                    } else {
+                                        self.get_short_hash()
+                                    }
+                                ),
+                                error: output_to_error(self.task.command.clone(), &out, false),
+                            }
                         });
                         output = out;
 
@@ -661,6 +990,35 @@ impl<'a> Runner<'a> {
                         );
                     }
                 }
+                // process timed out, which counts as a failed attempt
+                Err(error)
+                    if matches!(
+                        error.downcast_ref::<ProcessError>(),
+                        Some(ProcessError::Timeout { .. })
+                    ) =>
+                {
+                    attempt.done(ActionStatus::Failed);
+                    attempts.push(attempt);
+
+                    if attempt_index >= attempt_total {
+                        interval_handle.abort();
+
+                        return Err(RunnerError::TimedOut {
+                            target: self.task.target.id.clone(),
+                            timeout: self.task.options.timeout.unwrap_or_default(),
+                        }
+                        .into());
+                    }
+
+                    attempt_index += 1;
+
+                    warn!(
+                        target: LOG_TARGET,
+                        "Target {} timed out, running again with attempt {}",
+                        color::label(&self.task.target),
+                        attempt_index
+                    );
+                }
                 // process itself failed
                 Err(error) => {
                     attempt.done(ActionStatus::Failed);
@@ -691,6 +1049,130 @@ impl<'a> Runner<'a> {
         Ok(attempts)
     }
 
+    /// Format the paths that a failed sandboxed process was denied access to,
+    /// if any, as a list.
+    fn find_sandbox_violations(&self, command: &Command, output: &Output) -> String {
+        let Some(sandbox) = &command.sandbox else {
+            return String::new();
+        };
+
+        let violations = sandbox.find_violations(
+            &format!(
+                "{}\n{}",
+                output_to_string(&output.stdout),
+                output_to_string(&output.stderr)
+            ),
+            command.cwd.as_deref().unwrap_or(&self.workspace.root),
+        );
+
+        violations
+            .iter()
+            .map(|path| {
+                format!(
+                    "  - {}",
+                    color::path(path.strip_prefix(&self.workspace.root).unwrap_or(path))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Snapshot the files in the project before running, so that files written
+    /// by the task, but not declared as outputs, can be detected afterwards.
+    /// This requires listing the project's files through VCS before and after.
+    async fn snapshot_project(&self, run: &ProjectRun) -> miette::Result<Option<FileSnapshot>> {
+        let workspace = &self.workspace;
+        let task = &self.task;
+
+        if matches!(
+            workspace.config.runner.undeclared_outputs,
+            UndeclaredOutputsMode::Off
+        ) || !task.options.cache
+            || !workspace.vcs.is_enabled()
+            || task.is_persistent()
+        {
+            return Ok(None);
+        }
+
+        if !run.is_exclusive() {
+            debug!(
+                target: LOG_TARGET,
+                "Another task in project {} is running, skipping undeclared outputs detection for {}",
+                color::id(&self.project.id),
+                color::label(&task.target),
+            );
+
+            return Ok(None);
+        }
+
+        Ok(Some(
+            FileSnapshot::capture(
+                &workspace.vcs,
+                self.project.source.as_str(),
+                &workspace.root,
+            )
+            .await?,
+        ))
+    }
+
+    async fn check_undeclared_outputs(
+        &self,
+        run: &ProjectRun,
+        previous: FileSnapshot,
+    ) -> miette::Result<()> {
+        let workspace = &self.workspace;
+        let current = FileSnapshot::capture(
+            &workspace.vcs,
+            self.project.source.as_str(),
+            &workspace.root,
+        )
+        .await?;
+
+        // Changes can't be attributed to this task if another task in the
+        // project started while it was running
+        if !run.is_exclusive() {
+            debug!(
+                target: LOG_TARGET,
+                "Another task in project {} ran alongside {}, skipping undeclared outputs detection",
+                color::id(&self.project.id),
+                color::label(&self.task.target),
+            );
+
+            return Ok(());
+        }
+        let undeclared = find_undeclared_outputs(self.task, current.get_changed_files(&previous))?;
+
+        if undeclared.is_empty() {
+            return Ok(());
+        }
+
+        let paths = undeclared
+            .iter()
+            .map(|file| format!("  - {}", color::path(file.as_str())))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if matches!(
+            workspace.config.runner.undeclared_outputs,
+            UndeclaredOutputsMode::Error
+        ) {
+            return Err(RunnerError::UndeclaredOutputs {
+                target: self.task.target.id.clone(),
+                paths,
+            }
+            .into());
+        }
+
+        warn!(
+            target: LOG_TARGET,
+            "Target {} created or modified files that are not declared as outputs, and will be missing from the cache:\n{}",
+            color::label(&self.task.target),
+            paths
+        );
+
+        Ok(())
+    }
+
     pub async fn create_and_run_command(
         &mut self,
         context: &ActionContext,
@@ -710,7 +1192,36 @@ impl<'a> Runner<'a> {
         } else {
             let mut command = self.create_command(context, runtime).await?;
 
-            self.run_command(context, &mut command).await
+            // Outputs hydrated from the store may be hardlinks to read-only objects,
+            // so replace them with copies that the task can write to
+            if matches!(
+                self.workspace.config.runner.hydration_mode,
+                HydrationMode::Link
+            ) {
+                let output_paths = self
+                    .task
+                    .outputs
+                    .iter()
+                    .filter_map(|o| o.to_workspace_relative(&self.project.source))
+                    .collect::<Vec<_>>();
+
+                break_output_links(&self.workspace.root, &output_paths)?;
+            }
+
+            let run = ProjectRun::start(self.project.id.as_str());
+            let snapshot = self.snapshot_project(&run).await?;
+            let mut result = self.run_command(context, &mut command).await;
+
+            if let (Ok(_), Some(snapshot)) = (&result, snapshot) {
+                if let Err(error) = self.check_undeclared_outputs(&run, snapshot).await {
+                    // Don't reuse the outputs of this run on the next run
+                    self.cache.data.exit_code = 1;
+
+                    result = Err(error);
+                }
+            }
+
+            result
         };
 
         self.cache.data.last_run_time = time::now_millis();
@@ -736,22 +1247,20 @@ impl<'a> Runner<'a> {
         let label = label_checkpoint(&self.task.target, checkpoint);
 
         if comments.is_empty() {
-            self.stdout.write_line(&label).into_diagnostic()?;
+            self.write_stdout(&label)?;
         } else {
-            self.stdout
-                .write_line(&format!(
-                    "{} {}",
-                    label,
-                    color::muted(format!(
-                        "({})",
-                        comments
-                            .iter()
-                            .map(|c| c.as_ref())
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    ))
+            self.write_stdout(&format!(
+                "{} {}",
+                label,
+                color::muted(format!(
+                    "({})",
+                    comments
+                        .iter()
+                        .map(|c| c.as_ref())
+                        .collect::<Vec<_>>()
+                        .join(", ")
                 ))
-                .into_diagnostic()?;
+            ))?;
         }
 
         Ok(())
@@ -763,17 +1272,42 @@ impl<'a> Runner<'a> {
         stderr: &str,
         failed: bool,
     ) -> miette::Result<()> {
-        let print_stdout = || -> miette::Result<()> {
-            if !stdout.is_empty() {
-                self.stdout.write_line(stdout).into_diagnostic()?;
+        let print_output = || -> miette::Result<()> {
+            if stdout.is_empty() && stderr.is_empty() {
+                return Ok(());
             }
 
-            Ok(())
-        };
+            // Wrap the output in a collapsible section, which is expanded on failure
+            let name = &self.task.target.id;
+            let group = self.log_group.and_then(|group| {
+                group
+                    .open(name, name, failed)
+                    .map(|open| (open, group.close(name)))
+            });
+
+            if let Some((open, _)) = &group {
+                self.write_stdout(open)?;
+            }
+
+            if !stdout.is_empty() {
+                self.write_stdout(stdout)?;
+            }
 
-        let print_stderr = || -> miette::Result<()> {
             if !stderr.is_empty() {
-                self.stderr.write_line(stderr).into_diagnostic()?;
+                // Both streams are buffered, so flush stdout first,
+                // otherwise stderr would be printed before the section opens
+                if group.is_some() {
+                    self.flush_output()?;
+                }
+
+                self.write_stderr(stderr)?;
+            }
+
+            // Providers only pair markers written to the same stream, so close
+            // the section on stdout, but only after stderr has been flushed
+            if let Some((_, close)) = &group {
+                self.flush_output()?;
+                self.write_stdout(close)?;
             }
 
             Ok(())
@@ -783,8 +1317,7 @@ impl<'a> Runner<'a> {
             // Only show output on failure
             Some(TaskOutputStyle::BufferOnlyFailure) => {
                 if failed {
-                    print_stdout()?;
-                    print_stderr()?;
+                    print_output()?;
                 }
             }
             // Only show the hash
@@ -793,15 +1326,14 @@ impl<'a> Runner<'a> {
 
                 if !hash.is_empty() {
                     // Print to stderr so it can be captured
-                    self.stderr.write_line(hash).into_diagnostic()?;
+                    self.write_stderr(hash)?;
                 }
             }
             // Show nothing
             Some(TaskOutputStyle::None) => {}
             // Show output on both success and failure
             _ => {
-                print_stdout()?;
-                print_stderr()?;
+                print_output()?;
             }
         };
 
@@ -828,7 +1360,7 @@ impl<'a> Runner<'a> {
         let command_line = args::join_args(args);
 
         let message = color::muted_light(command.inspect().format_command(
-            &command_line,
+            &command.mask_secrets(&command_line),
             &self.workspace.root,
             Some(if task.options.run_from_workspace_root {
                 &self.workspace.root
@@ -837,7 +1369,7 @@ impl<'a> Runner<'a> {
             }),
         ));
 
-        self.stdout.write_line(&message).into_diagnostic()?;
+        self.write_stdout(&message)?;
 
         Ok(())
     }
@@ -0,0 +1,161 @@
+use crate::errors::RunnerError;
+use crate::inputs_collector;
+use moon_logger::trace;
+use moon_process::Sandbox;
+use moon_project::Project;
+use moon_task::Task;
+use moon_workspace::Workspace;
+use starbase_styles::color;
+use std::env;
+use std::path::{Path, PathBuf};
+
+const LOG_TARGET: &str = "moon:runner:sandbox";
+
+// Shared libraries, certificates, devices, etc, that most binaries require
+const SYSTEM_DIRS: [&str; 11] = [
+    "/bin", "/etc", "/lib", "/lib32", "/lib64", "/nix", "/opt", "/proc", "/sbin", "/sys", "/usr",
+];
+
+fn get_toolchain_dirs(workspace: &Workspace) -> Vec<PathBuf> {
+    let mut dirs = vec![workspace.proto_env.root.clone()];
+
+    if let Some(home_dir) = starbase_utils::dirs::home_dir() {
+        dirs.push(
+            env::var("CARGO_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| home_dir.join(".cargo")),
+        );
+        dirs.push(
+            env::var("RUSTUP_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| home_dir.join(".rustup")),
+        );
+    }
+
+    dirs
+}
+
+// Everything up to the first glob character
+fn get_glob_root(glob: &str, workspace_root: &Path) -> PathBuf {
+    let mut root = workspace_root.to_path_buf();
+
+    for part in glob.split('/') {
+        if part.contains(['*', '?', '[', '{', '!']) {
+            break;
+        }
+
+        root.push(part);
+    }
+
+    root
+}
+
+/// Create a sandbox that only allows the task to read its resolved inputs,
+/// toolchains, and installed dependencies, and to write to its outputs.
+pub async fn create_sandbox(
+    workspace: &Workspace,
+    project: &Project,
+    task: &Task,
+) -> miette::Result<Sandbox> {
+    let mut sandbox = Sandbox::default();
+
+    // Directories can be listed, but only inputs can be read
+    sandbox.allow_list(&workspace.root);
+
+    let inputs = inputs_collector::collect_inputs(
+        &workspace.vcs,
+        task,
+        &project.root,
+        &workspace.root,
+        &workspace.config.hasher,
+    )
+    .await?
+    .into_iter()
+    .map(|input| workspace.root.join(input))
+    .collect::<Vec<_>>();
+
+    for input in &inputs {
+        sandbox.allow_read(input);
+    }
+
+    // Dependencies are hashed through their lockfile instead
+    sandbox.allow_read(workspace.root.join("node_modules"));
+    sandbox.allow_read(project.root.join("node_modules"));
+
+    for dir in SYSTEM_DIRS {
+        sandbox.allow_read(dir);
+    }
+
+    for dir in get_toolchain_dirs(workspace) {
+        sandbox.allow_read(dir);
+    }
+
+    // Write rights are recursive, so they're only granted to directories that
+    // are dedicated to outputs, otherwise the task could modify its own inputs,
+    // or any other file in the project or workspace
+    let is_dedicated_dir = |dir: &Path| {
+        dir != workspace.root
+            && dir != project.root
+            && !inputs.iter().any(|input| input.starts_with(dir))
+    };
+
+    let fail = |output: &str| RunnerError::SandboxUndedicatedOutput {
+        target: task.target.id.clone(),
+        output: output.to_owned(),
+    };
+
+    // Outputs must exist for the sandbox to allow them, so require their
+    // directories, which are only created when the sandbox is enforced
+    for output in &task.output_files {
+        let path = output.to_path(&workspace.root);
+
+        if path.exists() {
+            sandbox.allow_write(&path);
+            continue;
+        }
+
+        // Whether a missing output will be a file or a directory is unknown,
+        // so allow creating files within its parent, when possible
+        match path.parent() {
+            Some(parent) if is_dedicated_dir(parent) => {
+                sandbox
+                    .require_dir(parent)
+                    .allow_create(parent)
+                    .allow_write(&path);
+            }
+            // Otherwise, assume that outputs without an extension are directories
+            _ if path.extension().is_none() => {
+                sandbox.require_dir(&path).allow_write(&path);
+            }
+            _ => {
+                return Err(fail(output.as_str()).into());
+            }
+        };
+    }
+
+    for output in &task.output_globs {
+        if output.as_str().starts_with('!') {
+            continue;
+        }
+
+        let root = get_glob_root(output.as_str(), &workspace.root);
+
+        if !is_dedicated_dir(&root) {
+            return Err(fail(output.as_str()).into());
+        }
+
+        sandbox.require_dir(&root).allow_write(root);
+    }
+
+    sandbox.allow_write("/dev").allow_write(env::temp_dir());
+
+    trace!(
+        target: LOG_TARGET,
+        "Sandboxing {} with {} readable and {} writable paths",
+        color::label(&task.target),
+        sandbox.read_paths.len(),
+        sandbox.write_paths.len() + sandbox.create_dirs.len(),
+    );
+
+    Ok(sandbox)
+}
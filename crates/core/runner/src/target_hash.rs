@@ -7,6 +7,8 @@ use moon_hash::hash_content;
 use moon_target::Target;
 use moon_task::Task;
 use rustc_hash::FxHashMap;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 hash_content!(
@@ -15,13 +17,13 @@ hash_content!(
         command: &'task str,
 
         // Task `args`
-        args: Vec<&'task str>,
+        args: Vec<Cow<'task, str>>,
 
         // Task `deps` mapped to their hash
         deps: BTreeMap<&'task Target, &'task str>,
 
         // Environment variables
-        env_vars: BTreeMap<&'task str, &'task str>,
+        env_vars: BTreeMap<&'task str, Cow<'task, str>>,
 
         // Input files and globs mapped to a unique hash
         inputs: BTreeMap<WorkspaceRelativePathBuf, String>,
@@ -44,12 +46,16 @@ impl<'task> TargetHasher<'task> {
     pub fn new(task: &'task Task) -> Self {
         TargetHasher {
             command: &task.command,
-            args: task.args.iter().map(|a| a.as_str()).collect(),
+            args: task
+                .args
+                .iter()
+                .map(|a| Cow::Borrowed(a.as_str()))
+                .collect(),
             deps: BTreeMap::new(),
             env_vars: task
                 .env
                 .iter()
-                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .map(|(k, v)| (k.as_str(), Cow::Borrowed(v.as_str())))
                 .collect(),
             inputs: BTreeMap::new(),
             outputs: task.outputs.iter().collect(),
@@ -63,7 +69,7 @@ impl<'task> TargetHasher<'task> {
     pub fn hash_args(&mut self, passthrough_args: &'task [String]) {
         if !passthrough_args.is_empty() {
             for arg in passthrough_args {
-                self.args.push(arg);
+                self.args.push(Cow::Borrowed(arg));
             }
         }
     }
@@ -80,6 +86,26 @@ impl<'task> TargetHasher<'task> {
         self.project_deps.sort();
     }
 
+    /// Replace secret values in args and environment variables with their digest,
+    /// so that a changed secret still invalidates the hash, but the value itself
+    /// is never written to the manifest.
+    pub fn hash_secrets(&mut self, secrets: &FxHashMap<String, String>) {
+        let mut secrets = secrets.values().collect::<Vec<_>>();
+
+        // Longest first, so that overlapping secrets are fully replaced
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()));
+
+        for secret in secrets {
+            let digest = format!("sha256:{:x}", Sha256::digest(secret.as_bytes()));
+
+            for value in self.args.iter_mut().chain(self.env_vars.values_mut()) {
+                if value.contains(secret.as_str()) {
+                    *value = Cow::Owned(value.replace(secret.as_str(), &digest));
+                }
+            }
+        }
+    }
+
     /// Hash `deps` from a task and associate it with their current hash.
     pub fn hash_task_deps(
         &mut self,
@@ -0,0 +1,47 @@
+use moon_runner::ProjectRun;
+
+mod project_run {
+    use super::*;
+
+    #[test]
+    fn is_exclusive_when_alone() {
+        let run = ProjectRun::start("alone");
+
+        assert!(run.is_exclusive());
+    }
+
+    #[test]
+    fn is_exclusive_after_previous_finished() {
+        drop(ProjectRun::start("sequential"));
+
+        let run = ProjectRun::start("sequential");
+
+        assert!(run.is_exclusive());
+    }
+
+    #[test]
+    fn isnt_exclusive_when_started_alongside_another() {
+        let first = ProjectRun::start("concurrent");
+        let second = ProjectRun::start("concurrent");
+
+        assert!(!first.is_exclusive());
+        assert!(!second.is_exclusive());
+    }
+
+    #[test]
+    fn isnt_exclusive_when_another_started_and_finished() {
+        let run = ProjectRun::start("overlapping");
+
+        drop(ProjectRun::start("overlapping"));
+
+        assert!(!run.is_exclusive());
+    }
+
+    #[test]
+    fn ignores_other_projects() {
+        let run = ProjectRun::start("project-a");
+        let _other = ProjectRun::start("project-b");
+
+        assert!(run.is_exclusive());
+    }
+}
@@ -0,0 +1,59 @@
+use moon_runner::LogGroup;
+
+mod log_group {
+    use super::*;
+
+    #[test]
+    fn wraps_github_actions_output() {
+        let group = LogGroup::GithubActions;
+
+        assert_eq!(
+            group.open("app:build", "app:build", false).unwrap(),
+            "::group::app:build"
+        );
+        assert_eq!(group.close("app:build"), "::endgroup::");
+    }
+
+    #[test]
+    fn wraps_azure_output() {
+        let group = LogGroup::Azure;
+
+        assert_eq!(
+            group.open("app:build", "app:build", false).unwrap(),
+            "##[group]app:build"
+        );
+        assert_eq!(group.close("app:build"), "##[endgroup]");
+    }
+
+    #[test]
+    fn doesnt_collapse_failures_when_unsupported() {
+        assert_eq!(
+            LogGroup::GithubActions.open("app:build", "app:build", true),
+            None
+        );
+        assert_eq!(LogGroup::Azure.open("app:build", "app:build", true), None);
+    }
+
+    #[test]
+    fn wraps_gitlab_output_in_sections() {
+        let group = LogGroup::Gitlab;
+        let open = group.open("app:build", "app:build", false).unwrap();
+
+        assert!(open.starts_with("\x1b[0Ksection_start:"));
+        assert!(open.ends_with(":app_build[collapsed=true]\r\x1b[0Kapp:build"));
+
+        let close = group.close("app:build");
+
+        assert!(close.starts_with("\x1b[0Ksection_end:"));
+        assert!(close.ends_with(":app_build\r\x1b[0K"));
+    }
+
+    #[test]
+    fn expands_gitlab_failures() {
+        let open = LogGroup::Gitlab
+            .open("app:build", "app:build", true)
+            .unwrap();
+
+        assert!(open.contains("[collapsed=false]"));
+    }
+}
@@ -0,0 +1,167 @@
+use moon_cache_item::StoreManifest;
+use moon_runner::{break_output_links, link_outputs, store_outputs};
+use moon_test_utils::{create_sandbox, Sandbox};
+use starbase_utils::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn store(sandbox: &Sandbox) -> (PathBuf, PathBuf) {
+    sandbox.create_file("out/a.txt", "a");
+    sandbox.create_file("out/nested/b.txt", "b");
+    sandbox.create_file("out/nested/c.txt", "a");
+
+    let manifest_file = sandbox.path().join(".moon/cache/store/abc.json");
+    let objects_dir = sandbox.path().join(".moon/cache/store/objects");
+
+    store_outputs(
+        &sandbox.path().join(".moon/cache/states/project/build"),
+        &manifest_file,
+        &objects_dir,
+        sandbox.path(),
+        &["out".into()],
+    )
+    .unwrap();
+
+    (manifest_file, objects_dir)
+}
+
+fn link(sandbox: &Sandbox, manifest_file: &Path, objects_dir: &Path) -> bool {
+    link_outputs(
+        &sandbox.path().join(".moon/cache/states/project/build"),
+        manifest_file,
+        objects_dir,
+        sandbox.path(),
+        &["out".into()],
+    )
+    .unwrap()
+}
+
+mod output_store {
+    use super::*;
+
+    #[test]
+    fn stores_files_by_content() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, _) = store(&sandbox);
+
+        let manifest: StoreManifest = json::read_file(manifest_file).unwrap();
+
+        assert_eq!(manifest.files.len(), 3);
+        assert_eq!(
+            manifest.files.get("out/a.txt"),
+            manifest.files.get("out/nested/c.txt")
+        );
+        assert_ne!(
+            manifest.files.get("out/a.txt"),
+            manifest.files.get("out/nested/b.txt")
+        );
+    }
+
+    #[test]
+    fn links_files_into_place() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, objects_dir) = store(&sandbox);
+
+        fs::remove_dir_all(sandbox.path().join("out")).unwrap();
+
+        assert!(link_outputs(
+            &sandbox.path().join(".moon/cache/states/project/build"),
+            &manifest_file,
+            &objects_dir,
+            sandbox.path(),
+            &["out".into()],
+        )
+        .unwrap());
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("out/nested/b.txt")).unwrap(),
+            "b"
+        );
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("out/nested/c.txt")).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn evicts_when_objects_are_missing() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, objects_dir) = store(&sandbox);
+
+        fs::remove_dir_all(&objects_dir).unwrap();
+
+        assert!(!link_outputs(
+            &sandbox.path().join(".moon/cache/states/project/build"),
+            &manifest_file,
+            &objects_dir,
+            sandbox.path(),
+            &["out".into()],
+        )
+        .unwrap());
+
+        assert!(!manifest_file.exists());
+    }
+
+    #[test]
+    fn removes_stale_outputs() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, objects_dir) = store(&sandbox);
+
+        sandbox.create_file("out/stale.txt", "stale");
+
+        link(&sandbox, &manifest_file, &objects_dir);
+
+        assert!(!sandbox.path().join("out/stale.txt").exists());
+        assert!(sandbox.path().join("out/a.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stores_read_only_objects() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, objects_dir) = store(&sandbox);
+        let manifest: StoreManifest = json::read_file(manifest_file).unwrap();
+
+        for digest in manifest.files.values() {
+            let object_file = objects_dir.join(&digest[0..2]).join(digest);
+
+            assert!(fs::metadata(object_file).unwrap().permissions().readonly());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn evicts_when_objects_are_modified() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, objects_dir) = store(&sandbox);
+        let manifest: StoreManifest = json::read_file(&manifest_file).unwrap();
+        let digest = manifest.files.get("out/a.txt").unwrap();
+        let object_file = objects_dir.join(&digest[0..2]).join(digest);
+
+        let mut perms = fs::metadata(&object_file).unwrap().permissions();
+        perms.set_readonly(false);
+        fs::set_permissions(&object_file, perms).unwrap();
+        fs::write(&object_file, "changed").unwrap();
+
+        assert!(!link(&sandbox, &manifest_file, &objects_dir));
+        assert!(!manifest_file.exists());
+        assert!(!object_file.exists());
+    }
+
+    #[test]
+    fn linked_outputs_dont_modify_store() {
+        let sandbox = create_sandbox("cases");
+        let (manifest_file, objects_dir) = store(&sandbox);
+
+        link(&sandbox, &manifest_file, &objects_dir);
+        break_output_links(sandbox.path(), &["out".into()]).unwrap();
+
+        fs::write(sandbox.path().join("out/a.txt"), "changed").unwrap();
+
+        assert!(link(&sandbox, &manifest_file, &objects_dir));
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("out/a.txt")).unwrap(),
+            "a"
+        );
+    }
+}
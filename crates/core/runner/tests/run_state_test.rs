@@ -0,0 +1,92 @@
+use moon_runner::{archive_outputs, hydrate_outputs, verify_archive_digest, ArchiveDigest};
+use moon_test_utils::{create_sandbox, Sandbox};
+use starbase_utils::json;
+use std::fs;
+use std::path::PathBuf;
+
+fn create_archive(sandbox: &Sandbox) -> (PathBuf, PathBuf) {
+    sandbox.create_file("out/a.txt", "a");
+    sandbox.create_file("out/b.txt", "b");
+
+    let archive_file = sandbox.path().join(".moon/cache/outputs/abc.tar.gz");
+    let digest_file = sandbox.path().join(".moon/cache/outputs/abc.digest.json");
+
+    archive_outputs(
+        &sandbox.path().join(".moon/cache/states/project/build"),
+        &archive_file,
+        &digest_file,
+        sandbox.path(),
+        &["out".into()],
+    )
+    .unwrap();
+
+    (archive_file, digest_file)
+}
+
+mod archive_digest {
+    use super::*;
+
+    #[test]
+    fn creates_digest_for_archive_and_files() {
+        let sandbox = create_sandbox("cases");
+        let (_, digest_file) = create_archive(&sandbox);
+
+        let digest: ArchiveDigest = json::read_file(digest_file).unwrap();
+
+        assert!(!digest.archive.is_empty());
+        assert!(digest.files.contains_key("out/a.txt"));
+        assert!(digest.files.contains_key("out/b.txt"));
+    }
+
+    #[test]
+    fn passes_when_unchanged() {
+        let sandbox = create_sandbox("cases");
+        let (archive_file, digest_file) = create_archive(&sandbox);
+
+        assert!(verify_archive_digest(&archive_file, &digest_file).unwrap());
+    }
+
+    #[test]
+    fn passes_when_no_digest() {
+        let sandbox = create_sandbox("cases");
+        let (archive_file, digest_file) = create_archive(&sandbox);
+
+        fs::remove_file(&digest_file).unwrap();
+
+        assert!(verify_archive_digest(&archive_file, &digest_file).unwrap());
+    }
+
+    #[test]
+    fn fails_when_truncated() {
+        let sandbox = create_sandbox("cases");
+        let (archive_file, digest_file) = create_archive(&sandbox);
+
+        let bytes = fs::read(&archive_file).unwrap();
+        fs::write(&archive_file, &bytes[0..bytes.len() / 2]).unwrap();
+
+        assert!(!verify_archive_digest(&archive_file, &digest_file).unwrap());
+    }
+
+    #[test]
+    fn evicts_and_misses_when_hydrating_mismatch() {
+        let sandbox = create_sandbox("cases");
+        let (archive_file, digest_file) = create_archive(&sandbox);
+
+        fs::write(&archive_file, "tampered").unwrap();
+        fs::remove_dir_all(sandbox.path().join("out")).unwrap();
+
+        let hydrated = hydrate_outputs(
+            &sandbox.path().join(".moon/cache/states/project/build"),
+            &archive_file,
+            &digest_file,
+            sandbox.path(),
+            &["out".into()],
+        )
+        .unwrap();
+
+        assert!(!hydrated);
+        assert!(!archive_file.exists());
+        assert!(!digest_file.exists());
+        assert!(!sandbox.path().join("out/a.txt").exists());
+    }
+}
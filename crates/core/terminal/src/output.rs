@@ -12,6 +12,7 @@ pub enum Checkpoint {
     Announcement,
     RunFailed,
     RunPassed,
+    RunSkipped,
     RunStart,
     Setup,
 }
@@ -50,6 +51,7 @@ pub fn get_checkpoint_prefix(checkpoint: Checkpoint) -> String {
         Checkpoint::Announcement => ANNOUNCEMENT_COLORS,
         Checkpoint::RunFailed => FAIL_COLORS,
         Checkpoint::RunPassed => PASS_COLORS,
+        Checkpoint::RunSkipped => MUTED_COLORS,
         Checkpoint::RunStart => MUTED_COLORS,
         Checkpoint::Setup => SETUP_COLORS,
     };
@@ -59,6 +59,13 @@ impl<T: Tool> ToolManager<T> {
         }
     }
 
+    pub async fn is_setup(&mut self, version: &Version) -> miette::Result<bool> {
+        match self.cache.get_mut(&version.number) {
+            Some(cache) => Ok(cache.is_setup().await?),
+            None => Err(ToolError::UnknownTool(self.runtime.to_string()).into()),
+        }
+    }
+
     pub async fn teardown(&mut self, version: &Version) -> miette::Result<()> {
         if let Some(mut tool) = self.cache.remove(&version.number) {
             tool.teardown().await?;
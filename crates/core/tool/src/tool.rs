@@ -26,6 +26,12 @@ pub trait Tool: Any + Send + Sync {
         Ok(0)
     }
 
+    /// Return true if the tool (and its sub-tools) has been installed, and locate
+    /// its binaries. Unlike `setup`, nothing is downloaded or installed.
+    async fn is_setup(&mut self) -> miette::Result<bool> {
+        Ok(true)
+    }
+
     /// Teardown the tool by uninstalling and deleting files.
     async fn teardown(&mut self) -> miette::Result<()> {
         Ok(())
@@ -164,6 +164,19 @@ impl Platform for DenoPlatform {
         Ok(self.toolchain.setup(&version, last_versions).await?)
     }
 
+    async fn is_tool_setup(&mut self, runtime: &Runtime) -> miette::Result<bool> {
+        let version = runtime.version();
+
+        if !self.toolchain.has(&version) {
+            self.toolchain.register(
+                &version,
+                DenoTool::new(&self.proto_env, &self.config, &version)?,
+            );
+        }
+
+        self.toolchain.is_setup(&version).await
+    }
+
     async fn install_deps(
         &self,
         _context: &ActionContext,
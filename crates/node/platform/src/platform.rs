@@ -321,6 +321,19 @@ impl Platform for NodePlatform {
         Ok(installed)
     }
 
+    async fn is_tool_setup(&mut self, runtime: &Runtime) -> miette::Result<bool> {
+        let version = runtime.version();
+
+        if !self.toolchain.has(&version) {
+            self.toolchain.register(
+                &version,
+                NodeTool::new(&self.proto_env, &self.config, &version).await?,
+            );
+        }
+
+        self.toolchain.is_setup(&version).await
+    }
+
     async fn install_deps(
         &self,
         _context: &ActionContext,
@@ -214,6 +214,40 @@ impl Tool for NodeTool {
         Ok(installed)
     }
 
+    async fn is_setup(&mut self) -> miette::Result<bool> {
+        if let Some(version) = &self.config.version {
+            if !self
+                .tool
+                .is_setup(&UnresolvedVersionSpec::parse(version)?)
+                .await?
+            {
+                return Ok(false);
+            }
+
+            self.tool.locate_globals_dir().await?;
+        }
+
+        if let Some(npm) = &mut self.npm {
+            if !npm.is_setup().await? {
+                return Ok(false);
+            }
+        }
+
+        if let Some(pnpm) = &mut self.pnpm {
+            if !pnpm.is_setup().await? {
+                return Ok(false);
+            }
+        }
+
+        if let Some(yarn) = &mut self.yarn {
+            if !yarn.is_setup().await? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
     async fn teardown(&mut self) -> miette::Result<()> {
         self.tool.teardown().await?;
 
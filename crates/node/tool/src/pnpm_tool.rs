@@ -101,6 +101,24 @@ impl Tool for PnpmTool {
         Ok(count)
     }
 
+    async fn is_setup(&mut self) -> miette::Result<bool> {
+        let Some(version) = &self.config.version else {
+            return Ok(true);
+        };
+
+        if self
+            .tool
+            .is_setup(&UnresolvedVersionSpec::parse(version)?)
+            .await?
+        {
+            self.tool.locate_globals_dir().await?;
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
     async fn teardown(&mut self) -> miette::Result<()> {
         self.tool.teardown().await?;
 
@@ -211,6 +211,19 @@ impl Platform for RustPlatform {
         Ok(self.toolchain.setup(&version, last_versions).await?)
     }
 
+    async fn is_tool_setup(&mut self, runtime: &Runtime) -> miette::Result<bool> {
+        let version = runtime.version();
+
+        if !self.toolchain.has(&version) {
+            self.toolchain.register(
+                &version,
+                RustTool::new(&self.proto_env, &self.config, &version).await?,
+            );
+        }
+
+        self.toolchain.is_setup(&version).await
+    }
+
     async fn install_deps(
         &self,
         _context: &ActionContext,
@@ -18,3 +18,12 @@ cache_item!(
         pub last_hash: String,
     }
 );
+
+cache_item!(
+    pub struct StoreManifest {
+        // Workspace relative file path -> object digest
+        pub files: std::collections::BTreeMap<String, String>,
+        pub stderr: Option<String>,
+        pub stdout: Option<String>,
+    }
+);
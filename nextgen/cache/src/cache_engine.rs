@@ -1,13 +1,44 @@
 use moon_cache_item::*;
-use moon_common::consts;
-use moon_time::parse_duration;
+use moon_common::{consts, parse_size};
+use moon_time::{now_millis, parse_duration, to_millis};
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use starbase_utils::{fs, json};
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::debug;
 
+cache_item!(
+    pub struct OutputsState {
+        /// Timestamp (in millis) of when an archive was last hydrated, keyed by hash.
+        pub last_hydrated: FxHashMap<String, u128>,
+    }
+);
+
+cache_item!(
+    pub struct DurationsState {
+        /// Average duration (in millis) of previous runs, keyed by target.
+        pub targets: FxHashMap<String, u64>,
+    }
+);
+
+struct OutputEntry {
+    last_used: u128,
+    size: u64,
+    hash: String,
+    paths: Vec<PathBuf>,
+    objects: Vec<String>,
+}
+
+fn get_file_stem(path: &Path, suffix: &str) -> Option<String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(suffix))
+        .map(|name| name.to_owned())
+}
+
 pub struct CacheEngine {
     /// The `.moon/cache` directory relative to workspace root.
     /// Contains cached items pertaining to runs and processes.
@@ -80,10 +111,251 @@ impl CacheEngine {
         Ok((deleted, bytes))
     }
 
+    /// Delete the least recently used outputs until the total size of the archives
+    /// (and their digests) in the outputs directory, and of the objects in the
+    /// store directory, is within the provided max size. An output is used when
+    /// it's created or hydrated, whichever is latest. Objects are shared between
+    /// store manifests, so they're only deleted once no longer referenced.
+    pub fn clean_outputs_over_size(
+        &self,
+        outputs_dir: &Path,
+        store_dir: &Path,
+        max_size: &str,
+    ) -> miette::Result<(usize, u64)> {
+        let max_bytes =
+            parse_size(max_size).map_err(|e| miette::miette!("Invalid max size: {e}"))?;
+
+        let mut state = self.cache_state::<OutputsState>("outputs.json")?;
+        let mut entries = vec![];
+        let mut total_bytes = 0;
+
+        let get_last_used = |hash: &str, meta: &std::fs::Metadata| {
+            let created = meta.modified().map(to_millis).unwrap_or_default();
+
+            state
+                .data
+                .last_hydrated
+                .get(hash)
+                .map_or(created, |hydrated| created.max(*hydrated))
+        };
+
+        // Archives, and the digests created alongside them
+        if outputs_dir.exists() {
+            for entry in fs::read_dir(outputs_dir)? {
+                let path = entry.path();
+
+                let Some(hash) = get_file_stem(&path, ".tar.gz") else {
+                    continue;
+                };
+
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+
+                let mut output = OutputEntry {
+                    last_used: get_last_used(&hash, &meta),
+                    size: meta.len(),
+                    hash,
+                    paths: vec![path],
+                    objects: vec![],
+                };
+
+                let digest_path = outputs_dir.join(format!("{}.digest.json", output.hash));
+
+                if let Ok(digest_meta) = std::fs::metadata(&digest_path) {
+                    output.size += digest_meta.len();
+                    output.paths.push(digest_path);
+                }
+
+                total_bytes += output.size;
+                entries.push(output);
+            }
+        }
+
+        // Store manifests, and the objects they reference
+        let mut object_refs = FxHashMap::<String, usize>::default();
+        let mut objects = FxHashMap::<String, (u64, PathBuf)>::default();
+
+        if store_dir.exists() {
+            for entry in fs::read_dir(store_dir)? {
+                let path = entry.path();
+
+                let Some(hash) = get_file_stem(&path, ".json") else {
+                    continue;
+                };
+
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+
+                // An unreadable manifest can't be hydrated, so treat it as empty
+                let manifest: StoreManifest = json::read_file(&path).unwrap_or_default();
+
+                let digests = manifest
+                    .files
+                    .into_values()
+                    .chain(manifest.stdout)
+                    .chain(manifest.stderr)
+                    .collect::<FxHashSet<_>>();
+
+                for digest in &digests {
+                    *object_refs.entry(digest.to_owned()).or_default() += 1;
+                }
+
+                total_bytes += meta.len();
+
+                entries.push(OutputEntry {
+                    last_used: get_last_used(&hash, &meta),
+                    size: meta.len(),
+                    hash,
+                    paths: vec![path],
+                    objects: digests.into_iter().collect(),
+                });
+            }
+
+            let objects_dir = store_dir.join("objects");
+
+            if objects_dir.exists() {
+                for entry in fs::read_dir_all(&objects_dir)? {
+                    let path = entry.path();
+                    let name = fs::file_name(&path);
+
+                    // Objects that are still being written
+                    if name.ends_with(".tmp") {
+                        continue;
+                    }
+
+                    let Ok(meta) = entry.metadata() else {
+                        continue;
+                    };
+
+                    if meta.is_file() {
+                        total_bytes += meta.len();
+                        objects.insert(name, (meta.len(), path));
+                    }
+                }
+            }
+        }
+
+        if total_bytes <= max_bytes {
+            return Ok((0, 0));
+        }
+
+        debug!(
+            "Outputs cache is {} bytes, deleting least recently used outputs until under {} bytes",
+            total_bytes, max_bytes
+        );
+
+        let mut deleted = 0;
+        let mut bytes = 0;
+
+        // Objects that are no longer referenced are always deleted first
+        for (digest, (size, path)) in &objects {
+            if !object_refs.contains_key(digest) {
+                fs::remove(path)?;
+                bytes += size;
+            }
+        }
+
+        entries.sort_by(|a, b| a.last_used.cmp(&b.last_used));
+
+        for entry in entries {
+            if total_bytes - bytes <= max_bytes {
+                break;
+            }
+
+            for path in &entry.paths {
+                fs::remove(path)?;
+            }
+
+            state.data.last_hydrated.remove(&entry.hash);
+
+            deleted += 1;
+            bytes += entry.size;
+
+            for digest in entry.objects {
+                let Some(count) = object_refs.get_mut(&digest) else {
+                    continue;
+                };
+
+                *count -= 1;
+
+                if *count == 0 {
+                    if let Some((size, path)) = objects.remove(&digest) {
+                        fs::remove(path)?;
+                        bytes += size;
+                    }
+                }
+            }
+        }
+
+        state.save()?;
+
+        debug!("Deleted {} outputs and saved {} bytes", deleted, bytes);
+
+        Ok((deleted, bytes))
+    }
+
     pub fn get_mode(&self) -> CacheMode {
         get_cache_mode()
     }
 
+    /// Load the historical duration of every target that has previously ran.
+    pub fn get_target_durations(&self) -> miette::Result<FxHashMap<String, Duration>> {
+        let state = self.cache_state::<DurationsState>("durations.json")?;
+
+        Ok(state
+            .data
+            .targets
+            .into_iter()
+            .map(|(target, millis)| (target, Duration::from_millis(millis)))
+            .collect())
+    }
+
+    /// Record the durations of the provided targets. Durations are averaged with
+    /// the previously recorded duration, weighted towards the most recent run.
+    pub fn track_target_durations(&self, durations: &[(String, Duration)]) -> miette::Result<()> {
+        if durations.is_empty() {
+            return Ok(());
+        }
+
+        let mut state = self.cache_state::<DurationsState>("durations.json")?;
+
+        for (target, duration) in durations {
+            let current = duration.as_millis() as u64;
+
+            state
+                .data
+                .targets
+                .entry(target.to_owned())
+                .and_modify(|previous| *previous = (*previous + current * 3) / 4)
+                .or_insert(current);
+        }
+
+        state.save()?;
+
+        Ok(())
+    }
+
+    /// Record the outputs for the provided hashes as hydrated, so that they
+    /// are considered recently used when evicting by size.
+    pub fn track_hydrated_outputs(&self, hashes: &[String]) -> miette::Result<()> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+
+        let mut state = self.cache_state::<OutputsState>("outputs.json")?;
+        let now = now_millis();
+
+        for hash in hashes {
+            state.data.last_hydrated.insert(hash.to_owned(), now);
+        }
+
+        state.save()?;
+
+        Ok(())
+    }
+
     pub fn write<T>(&self, path: impl AsRef<OsStr>, data: &T) -> miette::Result<()>
     where
         T: ?Sized + Serialize,
@@ -2,6 +2,7 @@ mod cache_engine;
 
 pub use cache_engine::*;
 pub use moon_cache_item::*;
+pub use moon_common::{format_size, parse_size};
 
 use moon_common::consts::CONFIG_DIRNAME;
 use starbase_utils::dirs;
@@ -83,4 +83,257 @@ mod cache_engine {
 
         env::remove_var("MOON_CACHE");
     }
+
+    mod clean_outputs_over_size {
+        use super::*;
+        use std::thread;
+        use std::time::Duration;
+
+        fn create_archive(sandbox: &starbase_sandbox::Sandbox, hash: &str) {
+            sandbox.create_file(
+                format!(".moon/cache/outputs/{hash}.tar.gz"),
+                "x".repeat(100),
+            );
+
+            // Ensure modified times are distinct
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        fn create_store_manifest(
+            sandbox: &starbase_sandbox::Sandbox,
+            hash: &str,
+            objects: &[&str],
+        ) {
+            for object in objects {
+                sandbox.create_file(
+                    format!(".moon/cache/store/objects/{}/{object}", &object[0..2]),
+                    "x".repeat(100),
+                );
+            }
+
+            sandbox.create_file(
+                format!(".moon/cache/store/{hash}.json"),
+                format!(
+                    r#"{{ "files": {{ {} }} }}"#,
+                    objects
+                        .iter()
+                        .map(|object| format!(r#""file-{object}": "{object}""#))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        fn clean(engine: &CacheEngine, max_size: &str) -> (usize, u64) {
+            engine
+                .clean_outputs_over_size(
+                    &engine.cache_dir.join("outputs"),
+                    &engine.cache_dir.join("store"),
+                    max_size,
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn does_nothing_if_under_size() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            create_archive(&sandbox, "a");
+            create_archive(&sandbox, "b");
+
+            let (deleted, _) = clean(&engine, "1 KB");
+
+            assert_eq!(deleted, 0);
+            assert!(sandbox.path().join(".moon/cache/outputs/a.tar.gz").exists());
+            assert!(sandbox.path().join(".moon/cache/outputs/b.tar.gz").exists());
+        }
+
+        #[test]
+        fn deletes_oldest_archives_first() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            create_archive(&sandbox, "a");
+            create_archive(&sandbox, "b");
+            create_archive(&sandbox, "c");
+
+            let (deleted, bytes) = clean(&engine, "200");
+
+            assert_eq!(deleted, 1);
+            assert_eq!(bytes, 100);
+            assert!(!sandbox.path().join(".moon/cache/outputs/a.tar.gz").exists());
+            assert!(sandbox.path().join(".moon/cache/outputs/b.tar.gz").exists());
+            assert!(sandbox.path().join(".moon/cache/outputs/c.tar.gz").exists());
+        }
+
+        #[test]
+        fn keeps_recently_hydrated_archives() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            create_archive(&sandbox, "a");
+            create_archive(&sandbox, "b");
+            create_archive(&sandbox, "c");
+
+            engine.track_hydrated_outputs(&["a".into()]).unwrap();
+
+            clean(&engine, "200");
+
+            assert!(sandbox.path().join(".moon/cache/outputs/a.tar.gz").exists());
+            assert!(!sandbox.path().join(".moon/cache/outputs/b.tar.gz").exists());
+            assert!(sandbox.path().join(".moon/cache/outputs/c.tar.gz").exists());
+        }
+
+        #[test]
+        fn deletes_digests_with_archives() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            create_archive(&sandbox, "a");
+            sandbox.create_file(".moon/cache/outputs/a.digest.json", "{}");
+            create_archive(&sandbox, "b");
+            create_archive(&sandbox, "c");
+
+            clean(&engine, "200");
+
+            assert!(!sandbox.path().join(".moon/cache/outputs/a.tar.gz").exists());
+            assert!(!sandbox
+                .path()
+                .join(".moon/cache/outputs/a.digest.json")
+                .exists());
+        }
+
+        #[test]
+        fn deletes_store_objects_once_unreferenced() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            create_store_manifest(&sandbox, "a", &["aa11", "cc33"]);
+            create_store_manifest(&sandbox, "b", &["bb22", "cc33"]);
+
+            let (deleted, _) = clean(&engine, "300");
+
+            assert_eq!(deleted, 1);
+            assert!(!sandbox.path().join(".moon/cache/store/a.json").exists());
+            assert!(!sandbox
+                .path()
+                .join(".moon/cache/store/objects/aa/aa11")
+                .exists());
+            assert!(sandbox.path().join(".moon/cache/store/b.json").exists());
+            assert!(sandbox
+                .path()
+                .join(".moon/cache/store/objects/bb/bb22")
+                .exists());
+            assert!(sandbox
+                .path()
+                .join(".moon/cache/store/objects/cc/cc33")
+                .exists());
+        }
+
+        #[test]
+        fn deletes_unreferenced_store_objects_first() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            create_store_manifest(&sandbox, "a", &["aa11"]);
+            sandbox.create_file(".moon/cache/store/objects/dd/dd44", "x".repeat(100));
+
+            let (deleted, _) = clean(&engine, "200");
+
+            assert_eq!(deleted, 0);
+            assert!(sandbox.path().join(".moon/cache/store/a.json").exists());
+            assert!(!sandbox
+                .path()
+                .join(".moon/cache/store/objects/dd/dd44")
+                .exists());
+        }
+
+        #[test]
+        #[should_panic(expected = "Invalid max size")]
+        fn errors_on_invalid_size() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            clean(&engine, "10 XB");
+        }
+    }
+
+    mod target_durations {
+        use super::*;
+        use std::time::Duration;
+
+        #[test]
+        fn returns_empty_if_no_history() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            assert!(engine.get_target_durations().unwrap().is_empty());
+        }
+
+        #[test]
+        fn records_first_duration_as_is() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            engine
+                .track_target_durations(&[("app:build".into(), Duration::from_millis(1000))])
+                .unwrap();
+
+            assert_eq!(
+                engine.get_target_durations().unwrap().get("app:build"),
+                Some(&Duration::from_millis(1000))
+            );
+        }
+
+        #[test]
+        fn weights_towards_recent_runs() {
+            let sandbox = create_empty_sandbox();
+            let engine = CacheEngine::new(sandbox.path()).unwrap();
+
+            engine
+                .track_target_durations(&[("app:build".into(), Duration::from_millis(1000))])
+                .unwrap();
+            engine
+                .track_target_durations(&[("app:build".into(), Duration::from_millis(2000))])
+                .unwrap();
+
+            assert_eq!(
+                engine.get_target_durations().unwrap().get("app:build"),
+                Some(&Duration::from_millis(1750))
+            );
+        }
+    }
+}
+
+mod parse_size {
+    use super::*;
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_size("100").unwrap(), 100);
+        assert_eq!(parse_size("2 KB").unwrap(), 2048);
+        assert_eq!(parse_size("1.5mb").unwrap(), 1024 * 1024 * 3 / 2);
+        assert_eq!(parse_size("10GB").unwrap(), 10 * 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn errors_on_invalid() {
+        assert!(parse_size("GB").is_err());
+        assert!(parse_size("10 parsecs").is_err());
+    }
+}
+
+mod format_size {
+    use super::*;
+
+    #[test]
+    fn formats_units() {
+        assert_eq!(format_size(100), "100 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(1024 * 1024 * 3 / 2), "1.5 MB");
+        assert_eq!(format_size(10 * 1024 * 1024 * 1024), "10.0 GB");
+    }
 }
@@ -3,8 +3,10 @@ mod env;
 mod id;
 mod macros;
 pub mod path;
+mod size;
 
 pub use env::*;
 pub use id::*;
 pub use macros::*;
+pub use size::*;
 pub use starbase_styles::*;
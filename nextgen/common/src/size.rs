@@ -0,0 +1,42 @@
+/// Parse a human readable size, like "500 MB" or "10GB", into bytes.
+/// Units are base 1024, and a value without a unit is in bytes.
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let index = value
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(index);
+
+    let number = number
+        .parse::<f64>()
+        .map_err(|_| format!("expected a number, received \"{value}\""))?;
+
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024_u64.pow(2),
+        "g" | "gb" | "gib" => 1024_u64.pow(3),
+        "t" | "tb" | "tib" => 1024_u64.pow(4),
+        unit => return Err(format!("unknown unit \"{unit}\"")),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// Format bytes into a human readable size, like "1.5 MB".
+pub fn format_size(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut index = 0;
+
+    while size >= 1024.0 && index < units.len() - 1 {
+        size /= 1024.0;
+        index += 1;
+    }
+
+    if index == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", units[index])
+    }
+}
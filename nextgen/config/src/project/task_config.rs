@@ -67,6 +67,26 @@ cacheable!(
     }
 );
 
+cacheable!(
+    #[derive(Clone, Config, Debug, Eq, PartialEq)]
+    pub struct TaskConditionConfig {
+        // Any of, compared against `std::env::consts::ARCH`
+        pub arch: Vec<String>,
+
+        // All of, either `NAME` (is set) or `NAME=value` (equals)
+        pub env: Vec<String>,
+
+        // All of, project relative, or workspace relative with a leading `/`
+        pub files: Vec<String>,
+
+        // Any of, compared against `std::env::consts::OS`
+        pub os: Vec<String>,
+
+        // Matched against the task's project
+        pub query: Option<String>,
+    }
+);
+
 cacheable!(
     #[derive(Clone, Config, Debug, Eq, PartialEq)]
     pub struct TaskConfig {
@@ -78,6 +98,9 @@ cacheable!(
         #[setting(nested)]
         pub args: TaskCommandArgs,
 
+        #[setting(nested)]
+        pub condition: Option<TaskConditionConfig>,
+
         #[setting(validate = validate_deps)]
         pub deps: Vec<Target>,
 
@@ -3,8 +3,10 @@ use std::str::FromStr;
 use crate::portable_path::FilePath;
 use crate::shapes::InputPath;
 use moon_common::cacheable;
+use moon_target::Target;
+use regex::Regex;
 use schematic::schema::StringType;
-use schematic::{derive_enum, Config, ConfigEnum, SchemaType, Schematic, ValidateError};
+use schematic::{derive_enum, validate, Config, ConfigEnum, SchemaType, Schematic, ValidateError};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_yaml::Value;
 
@@ -87,6 +89,104 @@ impl Schematic for TaskOptionEnvFile {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TaskOptionResource {
+    pub name: String,
+    pub capacity: u16,
+}
+
+impl TryFrom<String> for TaskOptionResource {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let (name, capacity) = match value.split_once(':') {
+            Some((name, capacity)) => match capacity.parse::<u16>() {
+                Ok(capacity) if capacity > 0 => (name, capacity),
+                _ => {
+                    return Err(format!(
+                        "invalid capacity for resource `{name}`, expected a positive number"
+                    ));
+                }
+            },
+            None => (value.as_str(), 1),
+        };
+
+        if name.is_empty() {
+            return Err("resource name must not be empty".into());
+        }
+
+        Ok(TaskOptionResource {
+            name: name.to_owned(),
+            capacity,
+        })
+    }
+}
+
+impl From<TaskOptionResource> for String {
+    fn from(value: TaskOptionResource) -> Self {
+        if value.capacity == 1 {
+            value.name
+        } else {
+            format!("{}:{}", value.name, value.capacity)
+        }
+    }
+}
+
+impl Schematic for TaskOptionResource {
+    fn generate_schema() -> SchemaType {
+        SchemaType::string()
+    }
+}
+
+fn validate_timeout<D, C>(timeout: &u64, _data: &D, _ctx: &C) -> Result<(), ValidateError> {
+    if *timeout == 0 {
+        return Err(ValidateError::new("timeout must be greater than 0"));
+    }
+
+    Ok(())
+}
+
+fn validate_ready_check<D, C>(
+    check: &PartialTaskReadyCheckConfig,
+    _data: &D,
+    _ctx: &C,
+) -> Result<(), ValidateError> {
+    if check.file.is_none() && check.output.is_none() && check.port.is_none() && check.url.is_none()
+    {
+        return Err(ValidateError::new(
+            "at least one of `file`, `output`, `port`, or `url` is required",
+        ));
+    }
+
+    if let Some(output) = &check.output {
+        if let Err(error) = Regex::new(output) {
+            return Err(ValidateError::new(format!(
+                "invalid output pattern: {error}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+cacheable!(
+    #[derive(Clone, Config, Debug, Eq, PartialEq)]
+    pub struct TaskReadyCheckConfig {
+        pub file: Option<String>,
+
+        pub output: Option<String>,
+
+        pub port: Option<u16>,
+
+        #[setting(default = 60, validate = validate_timeout)]
+        pub timeout: u64,
+
+        #[setting(validate = validate::url)]
+        pub url: Option<String>,
+    }
+);
+
 derive_enum!(
     #[derive(ConfigEnum, Copy, Default)]
     pub enum TaskMergeStrategy {
@@ -120,6 +220,8 @@ cacheable!(
 
         pub env_file: Option<TaskOptionEnvFile>,
 
+        pub finally: Option<Vec<Target>>,
+
         #[setting(validate = validate_interactive)]
         pub interactive: Option<bool>,
 
@@ -138,6 +240,11 @@ cacheable!(
 
         pub persistent: Option<bool>,
 
+        #[setting(nested, validate = validate_ready_check)]
+        pub ready_check: Option<TaskReadyCheckConfig>,
+
+        pub resources: Option<Vec<TaskOptionResource>>,
+
         #[setting(env = "MOON_RETRY_COUNT")]
         pub retry_count: Option<u8>,
 
@@ -148,6 +255,13 @@ cacheable!(
 
         pub run_from_workspace_root: Option<bool>,
 
+        pub sandbox: Option<bool>,
+
+        pub secret_env_vars: Option<Vec<String>>,
+
         pub shell: Option<bool>,
+
+        #[setting(validate = validate_timeout)]
+        pub timeout: Option<u64>,
     }
 );
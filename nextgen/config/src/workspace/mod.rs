@@ -4,6 +4,8 @@ mod experiments_config;
 mod generator_config;
 mod hasher_config;
 mod notifier_config;
+mod open_telemetry_config;
+mod remote_cache_config;
 mod runner_config;
 mod vcs_config;
 
@@ -13,5 +15,7 @@ pub use experiments_config::*;
 pub use generator_config::*;
 pub use hasher_config::*;
 pub use notifier_config::*;
+pub use open_telemetry_config::*;
+pub use remote_cache_config::*;
 pub use runner_config::*;
 pub use vcs_config::*;
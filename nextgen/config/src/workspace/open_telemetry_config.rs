@@ -0,0 +1,21 @@
+use rustc_hash::FxHashMap;
+use schematic::{validate, Config};
+
+#[derive(Clone, Config, Debug)]
+pub struct OpenTelemetryConfig {
+    #[setting(validate = validate::url)]
+    pub endpoint: Option<String>,
+
+    pub file: Option<String>,
+
+    pub headers: FxHashMap<String, String>,
+
+    #[setting(default = "moon")]
+    pub service_name: String,
+}
+
+impl OpenTelemetryConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.endpoint.is_some() || self.file.is_some()
+    }
+}
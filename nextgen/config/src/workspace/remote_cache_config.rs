@@ -0,0 +1,112 @@
+use rustc_hash::FxHashMap;
+use schematic::{validate, Config, ValidateError};
+
+fn validate_single_backend<T: ?Sized, C>(
+    _value: &T,
+    data: &PartialRemoteCacheConfig,
+    _ctx: &C,
+) -> Result<(), ValidateError> {
+    let count = [
+        data.directory.is_some(),
+        data.http.is_some(),
+        data.reapi.is_some(),
+        data.s3.is_some(),
+    ]
+    .into_iter()
+    .filter(|enabled| *enabled)
+    .count();
+
+    if count > 1 {
+        return Err(ValidateError::new(
+            "only 1 remote cache backend (directory, http, reapi, or s3) may be configured",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_s3<T: ?Sized, C>(
+    value: &T,
+    data: &PartialRemoteCacheConfig,
+    ctx: &C,
+) -> Result<(), ValidateError> {
+    validate_single_backend(value, data, ctx)?;
+
+    let has_bucket = data
+        .s3
+        .as_ref()
+        .and_then(|s3| s3.bucket.as_ref())
+        .is_some_and(|bucket| !bucket.is_empty());
+
+    if !has_bucket {
+        return Err(ValidateError::new("a bucket is required"));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Config, Debug)]
+pub struct RemoteCacheHttpConfig {
+    pub headers: FxHashMap<String, String>,
+
+    pub token_env_var: Option<String>,
+
+    #[setting(validate = validate::url)]
+    pub url: String,
+}
+
+#[derive(Clone, Config, Debug)]
+pub struct RemoteCacheReapiConfig {
+    pub headers: FxHashMap<String, String>,
+
+    #[setting(validate = validate::url)]
+    pub host: String,
+
+    pub instance_name: String,
+
+    pub token_env_var: Option<String>,
+}
+
+#[derive(Clone, Config, Debug)]
+pub struct RemoteCacheS3Config {
+    #[setting(default = "AWS_ACCESS_KEY_ID")]
+    pub access_key_id_env_var: String,
+
+    #[setting(validate = validate::not_empty)]
+    pub bucket: String,
+
+    #[setting(validate = validate::url)]
+    pub endpoint: Option<String>,
+
+    pub prefix: String,
+
+    #[setting(default = "us-east-1")]
+    pub region: String,
+
+    #[setting(default = "AWS_SECRET_ACCESS_KEY")]
+    pub secret_access_key_env_var: String,
+
+    #[setting(default = "AWS_SESSION_TOKEN")]
+    pub session_token_env_var: String,
+}
+
+#[derive(Clone, Config, Debug)]
+pub struct RemoteCacheConfig {
+    #[setting(validate = validate_single_backend)]
+    pub directory: Option<String>,
+
+    #[setting(nested, validate = validate_single_backend)]
+    pub http: Option<RemoteCacheHttpConfig>,
+
+    #[setting(nested, validate = validate_single_backend)]
+    pub reapi: Option<RemoteCacheReapiConfig>,
+
+    #[setting(nested, validate = validate_s3)]
+    pub s3: Option<RemoteCacheS3Config>,
+}
+
+impl RemoteCacheConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.directory.is_some() || self.http.is_some() || self.reapi.is_some() || self.s3.is_some()
+    }
+}
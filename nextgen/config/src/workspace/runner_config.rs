@@ -1,15 +1,53 @@
+use moon_common::parse_size;
 use moon_target::Target;
-use schematic::Config;
+use schematic::{derive_enum, Config, ConfigEnum, ValidateError};
+
+fn validate_size<D, C>(value: &str, _data: &D, _ctx: &C) -> Result<(), ValidateError> {
+    parse_size(value).map_err(ValidateError::new)?;
+
+    Ok(())
+}
+
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default)]
+    pub enum HydrationMode {
+        #[default]
+        Archive,
+        Link,
+    }
+);
+
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default)]
+    pub enum UndeclaredOutputsMode {
+        Error,
+        Off,
+        #[default]
+        Warn,
+    }
+);
 
 #[derive(Clone, Config, Debug)]
 pub struct RunnerConfig {
     pub archivable_targets: Vec<Target>,
 
+    #[setting(default = true)]
+    pub bail: bool,
+
     #[setting(default = "7 days")]
     pub cache_lifetime: String,
 
+    #[setting(validate = validate_size)]
+    pub cache_max_size: Option<String>,
+
+    pub hydration_mode: HydrationMode,
+
     #[setting(default = true)]
     pub inherit_colors_for_piped_tasks: bool,
 
     pub log_running_command: bool,
+
+    pub secret_env_vars: Vec<String>,
+
+    pub undeclared_outputs: UndeclaredOutputsMode,
 }
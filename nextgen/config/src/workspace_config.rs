@@ -113,9 +113,15 @@ pub struct WorkspaceConfig {
     #[setting(nested)]
     pub notifier: NotifierConfig,
 
+    #[setting(nested)]
+    pub open_telemetry: OpenTelemetryConfig,
+
     #[setting(nested, validate = validate_projects)]
     pub projects: WorkspaceProjects,
 
+    #[setting(nested)]
+    pub remote_cache: RemoteCacheConfig,
+
     #[setting(nested)]
     pub runner: RunnerConfig,
 
@@ -2,8 +2,8 @@ mod utils;
 
 use moon_common::Id;
 use moon_config::{
-    FilePath, InputPath, OutputPath, PlatformType, TaskCommandArgs, TaskConfig, TaskMergeStrategy,
-    TaskOutputStyle, TaskType,
+    FilePath, InputPath, OutputPath, PlatformType, TaskCommandArgs, TaskConditionConfig,
+    TaskConfig, TaskMergeStrategy, TaskOutputStyle, TaskType,
 };
 use moon_target::Target;
 use utils::*;
@@ -13,7 +13,7 @@ mod task_config {
 
     #[test]
     #[should_panic(
-        expected = "unknown field `unknown`, expected one of `extends`, `command`, `args`, `deps`, `env`, `inputs`, `local`, `outputs`, `options`, `platform`, `type`"
+        expected = "unknown field `unknown`, expected one of `extends`, `command`, `args`, `condition`, `deps`, `env`, `inputs`, `local`, `outputs`, `options`, `platform`, `type`"
     )]
     fn error_unknown_field() {
         test_parse_config("unknown: 123", |code| TaskConfig::parse(code));
@@ -123,6 +123,49 @@ args:
         }
     }
 
+    mod condition {
+        use super::*;
+
+        #[test]
+        fn defaults_to_none() {
+            let config = test_parse_config("{}", |code| TaskConfig::parse(code));
+
+            assert_eq!(config.condition, None);
+        }
+
+        #[test]
+        fn can_set_all_fields() {
+            let config = test_parse_config(
+                r"
+condition:
+  arch: [x86_64, aarch64]
+  env: [CI, NODE_ENV=production]
+  files: ['package.json', '/.github/**/*.yml']
+  os: [linux, macos]
+  query: 'language=typescript'
+",
+                |code| TaskConfig::parse(code),
+            );
+
+            assert_eq!(
+                config.condition,
+                Some(TaskConditionConfig {
+                    arch: vec!["x86_64".into(), "aarch64".into()],
+                    env: vec!["CI".into(), "NODE_ENV=production".into()],
+                    files: vec!["package.json".into(), "/.github/**/*.yml".into()],
+                    os: vec!["linux".into(), "macos".into()],
+                    query: Some("language=typescript".into()),
+                })
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "unknown field `unknown`")]
+        fn errors_on_unknown_field() {
+            test_parse_config("condition:\n  unknown: 123", |code| TaskConfig::parse(code));
+        }
+    }
+
     mod deps {
         use super::*;
 
@@ -504,6 +547,169 @@ options:
             //             }
         }
 
+        mod ready_check {
+            use super::*;
+            use moon_config::TaskReadyCheckConfig;
+
+            #[test]
+            fn can_set_checks() {
+                let config = test_parse_config(
+                    r"
+options:
+  persistent: true
+  readyCheck:
+    port: 3000
+    output: 'listening on \d+'
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(
+                    config.options.ready_check,
+                    Some(TaskReadyCheckConfig {
+                        file: None,
+                        output: Some("listening on \\d+".into()),
+                        port: Some(3000),
+                        timeout: 60,
+                        url: None,
+                    })
+                );
+            }
+
+            #[test]
+            fn can_set_timeout() {
+                let config = test_parse_config(
+                    r"
+options:
+  readyCheck:
+    url: 'http://localhost:3000/health'
+    timeout: 10
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                let check = config.options.ready_check.unwrap();
+
+                assert_eq!(check.url, Some("http://localhost:3000/health".into()));
+                assert_eq!(check.timeout, 10);
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "at least one of `file`, `output`, `port`, or `url` is required"
+            )]
+            fn errors_without_checks() {
+                test_parse_config(
+                    r"
+options:
+  readyCheck:
+    timeout: 10
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "timeout must be greater than 0")]
+            fn errors_on_zero_timeout() {
+                test_parse_config(
+                    r"
+options:
+  readyCheck:
+    port: 3000
+    timeout: 0
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "invalid output pattern")]
+            fn errors_on_invalid_pattern() {
+                test_parse_config(
+                    r"
+options:
+  readyCheck:
+    output: 'listening on ('
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+        }
+
+        mod resources {
+            use super::*;
+            use moon_config::TaskOptionResource;
+
+            #[test]
+            fn defaults_capacity_to_one() {
+                let config = test_parse_config(
+                    r"
+options:
+  resources: [db]
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(
+                    config.options.resources,
+                    Some(vec![TaskOptionResource {
+                        name: "db".into(),
+                        capacity: 1,
+                    }])
+                );
+            }
+
+            #[test]
+            fn can_set_capacity() {
+                let config = test_parse_config(
+                    r"
+options:
+  resources: ['db', 'gpu:2']
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(
+                    config.options.resources,
+                    Some(vec![
+                        TaskOptionResource {
+                            name: "db".into(),
+                            capacity: 1,
+                        },
+                        TaskOptionResource {
+                            name: "gpu".into(),
+                            capacity: 2,
+                        }
+                    ])
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "invalid capacity for resource `gpu`")]
+            fn errors_on_zero_capacity() {
+                test_parse_config(
+                    r"
+options:
+  resources: ['gpu:0']
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+
+            #[test]
+            #[should_panic(expected = "resource name must not be empty")]
+            fn errors_on_empty_name() {
+                test_parse_config(
+                    r"
+options:
+  resources: [':2']
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+        }
+
         mod interactive {
             use super::*;
 
@@ -515,6 +721,35 @@ options:
 options:
   interactive: true
   persistent: true
+",
+                    |code| TaskConfig::parse(code),
+                );
+            }
+        }
+
+        mod timeout {
+            use super::*;
+
+            #[test]
+            fn can_set_timeout() {
+                let config = test_parse_config(
+                    r"
+options:
+  timeout: 600
+",
+                    |code| TaskConfig::parse(code),
+                );
+
+                assert_eq!(config.options.timeout, Some(600));
+            }
+
+            #[test]
+            #[should_panic(expected = "timeout must be greater than 0")]
+            fn errors_on_zero() {
+                test_parse_config(
+                    r"
+options:
+  timeout: 0
 ",
                     |code| TaskConfig::parse(code),
                 );
@@ -1,6 +1,6 @@
 mod utils;
 
-use moon_config::{FilePath, VcsProvider, WorkspaceConfig, WorkspaceProjects};
+use moon_config::{FilePath, HydrationMode, VcsProvider, WorkspaceConfig, WorkspaceProjects};
 use rustc_hash::FxHashMap;
 use starbase_sandbox::create_sandbox;
 use utils::*;
@@ -459,6 +459,232 @@ notifier:
         }
     }
 
+    mod open_telemetry {
+        use super::*;
+
+        #[test]
+        fn loads_defaults() {
+            let config = test_load_config(FILENAME, "openTelemetry: {}", |path| {
+                WorkspaceConfig::load_from(path)
+            });
+
+            assert!(!config.open_telemetry.is_enabled());
+            assert_eq!(config.open_telemetry.service_name, "moon");
+        }
+
+        #[test]
+        fn can_set_settings() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+openTelemetry:
+  endpoint: 'http://localhost:4318/v1/traces'
+  file: '.moon/cache/traces.json'
+  headers:
+    x-api-key: abc123
+  serviceName: monorepo
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            assert!(config.open_telemetry.is_enabled());
+            assert_eq!(
+                config.open_telemetry.endpoint,
+                Some("http://localhost:4318/v1/traces".into())
+            );
+            assert_eq!(
+                config.open_telemetry.file,
+                Some(".moon/cache/traces.json".into())
+            );
+            assert_eq!(
+                config.open_telemetry.headers.get("x-api-key"),
+                Some(&"abc123".to_owned())
+            );
+            assert_eq!(config.open_telemetry.service_name, "monorepo");
+        }
+
+        #[test]
+        #[should_panic(expected = "not a valid url")]
+        fn errors_on_invalid_endpoint() {
+            test_load_config(
+                FILENAME,
+                r"
+openTelemetry:
+  endpoint: 'invalid value'
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+    }
+
+    mod remote_cache {
+        use super::*;
+
+        #[test]
+        fn loads_defaults() {
+            let config = test_load_config(FILENAME, "remoteCache: {}", |path| {
+                WorkspaceConfig::load_from(path)
+            });
+
+            assert!(!config.remote_cache.is_enabled());
+        }
+
+        #[test]
+        fn can_set_directory() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  directory: /mnt/cache
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            assert!(config.remote_cache.is_enabled());
+            assert_eq!(config.remote_cache.directory, Some("/mnt/cache".into()));
+        }
+
+        #[test]
+        fn can_set_http() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  http:
+    url: https://cache.example.com/moon
+    tokenEnvVar: CACHE_TOKEN
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            let http = config.remote_cache.http.unwrap();
+
+            assert_eq!(http.url, "https://cache.example.com/moon");
+            assert_eq!(http.token_env_var, Some("CACHE_TOKEN".into()));
+        }
+
+        #[test]
+        #[should_panic(expected = "not a valid url")]
+        fn errors_on_invalid_http_url() {
+            test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  http:
+    url: invalid value
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+
+        #[test]
+        fn can_set_reapi() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  reapi:
+    host: grpcs://cache.example.com:443
+    instanceName: main
+    headers:
+      x-team: platform
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            assert!(config.remote_cache.is_enabled());
+
+            let reapi = config.remote_cache.reapi.unwrap();
+
+            assert_eq!(reapi.host, "grpcs://cache.example.com:443");
+            assert_eq!(reapi.instance_name, "main");
+            assert_eq!(reapi.headers.get("x-team").unwrap(), "platform");
+        }
+
+        #[test]
+        fn can_set_s3() {
+            let config = test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  s3:
+    bucket: moon-cache
+    endpoint: http://localhost:9000
+    prefix: artifacts
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+
+            assert!(config.remote_cache.is_enabled());
+
+            let s3 = config.remote_cache.s3.unwrap();
+
+            assert_eq!(s3.bucket, "moon-cache");
+            assert_eq!(s3.endpoint, Some("http://localhost:9000".into()));
+            assert_eq!(s3.prefix, "artifacts");
+            assert_eq!(s3.region, "us-east-1");
+            assert_eq!(s3.access_key_id_env_var, "AWS_ACCESS_KEY_ID");
+            assert_eq!(s3.secret_access_key_env_var, "AWS_SECRET_ACCESS_KEY");
+        }
+
+        #[test]
+        #[should_panic(expected = "a bucket is required")]
+        fn errors_on_missing_s3_bucket() {
+            test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  s3:
+    region: us-west-2
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "must not be empty")]
+        fn errors_on_empty_s3_bucket() {
+            test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  s3:
+    bucket: ''
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "only 1 remote cache backend")]
+        fn errors_on_multiple_backends() {
+            test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  directory: /mnt/cache
+  http:
+    url: https://cache.example.com/moon
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "not a valid url")]
+        fn errors_on_invalid_reapi_host() {
+            test_load_config(
+                FILENAME,
+                r"
+remoteCache:
+  reapi:
+    host: invalid value
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+    }
+
     mod runner {
         use super::*;
         use moon_target::Target;
@@ -469,7 +695,10 @@ notifier:
                 WorkspaceConfig::load_from(path)
             });
 
+            assert!(config.runner.bail);
             assert_eq!(config.runner.cache_lifetime, "7 days");
+            assert_eq!(config.runner.cache_max_size, None);
+            assert_eq!(config.runner.hydration_mode, HydrationMode::Archive);
             assert!(config.runner.inherit_colors_for_piped_tasks);
         }
 
@@ -479,16 +708,35 @@ notifier:
                 FILENAME,
                 r"
 runner:
+  bail: false
   cacheLifetime: 10 hours
+  cacheMaxSize: 5 GB
+  hydrationMode: link
   inheritColorsForPipedTasks: false
 ",
                 |path| WorkspaceConfig::load_from(path),
             );
 
+            assert!(!config.runner.bail);
             assert_eq!(config.runner.cache_lifetime, "10 hours");
+            assert_eq!(config.runner.cache_max_size, Some("5 GB".into()));
+            assert_eq!(config.runner.hydration_mode, HydrationMode::Link);
             assert!(!config.runner.inherit_colors_for_piped_tasks);
         }
 
+        #[test]
+        #[should_panic(expected = "unknown unit \"parsecs\"")]
+        fn errors_on_invalid_cache_max_size() {
+            test_load_config(
+                FILENAME,
+                r"
+runner:
+  cacheMaxSize: 10 parsecs
+",
+                |path| WorkspaceConfig::load_from(path),
+            );
+        }
+
         #[test]
         fn can_use_targets() {
             let config = test_load_config(
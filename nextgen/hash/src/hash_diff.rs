@@ -0,0 +1,164 @@
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub enum ManifestLine<'l> {
+    /// Line only exists in the left manifest.
+    Left(&'l str),
+    /// Line exists in both manifests.
+    Both(&'l str),
+    /// Line only exists in the right manifest.
+    Right(&'l str),
+}
+
+/// Diff 2 pretty printed hash manifests line by line.
+pub fn diff_manifest_lines<'l>(left: &'l str, right: &'l str) -> Vec<ManifestLine<'l>> {
+    diff::lines(left, right)
+        .into_iter()
+        .map(|line| match line {
+            diff::Result::Left(l) => ManifestLine::Left(l),
+            diff::Result::Both(l, _) => ManifestLine::Both(l),
+            diff::Result::Right(r) => ManifestLine::Right(r),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ManifestChangeKind {
+    Dependency,
+    EnvVar,
+    Input,
+    Task,
+    Toolchain,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestChange {
+    pub kind: ManifestChangeKind,
+
+    /// Field that changed, with nested fields separated by a period,
+    /// for example `inputs.src/index.ts` or `envVars.NODE_ENV`.
+    pub key: String,
+
+    pub previous: Option<String>,
+
+    pub current: Option<String>,
+}
+
+impl ManifestChange {
+    /// Return the key without the field it's grouped under.
+    pub fn get_name(&self) -> &str {
+        match self.kind {
+            ManifestChangeKind::Dependency
+            | ManifestChangeKind::EnvVar
+            | ManifestChangeKind::Input => self
+                .key
+                .split_once('.')
+                .map(|(_, name)| name)
+                .unwrap_or(&self.key),
+            _ => &self.key,
+        }
+    }
+}
+
+fn get_change_kind(key: &str) -> ManifestChangeKind {
+    match key.split('.').next().unwrap_or_default() {
+        "deps" | "projectDeps" => ManifestChangeKind::Dependency,
+        "envVars" => ManifestChangeKind::EnvVar,
+        "inputs" => ManifestChangeKind::Input,
+        "args" | "command" | "outputs" | "target" => ManifestChangeKind::Task,
+        _ => ManifestChangeKind::Toolchain,
+    }
+}
+
+fn flatten_value(value: &Value, prefix: String, fields: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map {
+                flatten_value(
+                    item,
+                    if prefix.is_empty() {
+                        key.to_owned()
+                    } else {
+                        format!("{prefix}.{key}")
+                    },
+                    fields,
+                );
+            }
+        }
+        Value::String(string) => {
+            fields.insert(prefix, string.to_owned());
+        }
+        // Lists are compared as a whole, as their indices aren't meaningful
+        _ => {
+            fields.insert(prefix, value.to_string());
+        }
+    };
+}
+
+// Manifests are a list of hashed contents, so merge them into a single
+// map of fields, and only prefix with the index if a field collides.
+fn flatten_manifest(manifest: &str) -> miette::Result<BTreeMap<String, String>> {
+    let mut fields = BTreeMap::new();
+    let value: Value = serde_json::from_str(manifest).into_diagnostic()?;
+
+    let Value::Array(contents) = &value else {
+        flatten_value(&value, String::new(), &mut fields);
+
+        return Ok(fields);
+    };
+
+    for (index, content) in contents.iter().enumerate() {
+        let mut content_fields = BTreeMap::new();
+
+        flatten_value(content, String::new(), &mut content_fields);
+
+        for (key, item) in content_fields {
+            if fields.contains_key(&key) {
+                fields.insert(format!("[{index}].{key}"), item);
+            } else {
+                fields.insert(key, item);
+            }
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Diff 2 hash manifests field by field, and return a list of every
+/// input, env var, dependency, or toolchain field that has changed.
+pub fn diff_manifests(previous: &str, current: &str) -> miette::Result<Vec<ManifestChange>> {
+    let mut previous_fields = flatten_manifest(previous)?;
+    let current_fields = flatten_manifest(current)?;
+    let mut changes = vec![];
+
+    for (key, current_value) in current_fields {
+        let previous_value = previous_fields.remove(&key);
+
+        if previous_value.as_ref() != Some(&current_value) {
+            changes.push(ManifestChange {
+                kind: get_change_kind(&key),
+                key,
+                previous: previous_value,
+                current: Some(current_value),
+            });
+        }
+    }
+
+    // Whatever remains no longer exists
+    for (key, previous_value) in previous_fields {
+        changes.push(ManifestChange {
+            kind: get_change_kind(&key),
+            key,
+            previous: Some(previous_value),
+            current: None,
+        });
+    }
+
+    changes.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(changes)
+}
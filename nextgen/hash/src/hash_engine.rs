@@ -1,25 +1,38 @@
 use crate::hasher::ContentHasher;
 use serde::Serialize;
 use starbase_utils::fs;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tracing::debug;
 
 pub struct HashEngine {
     /// The `.moon/cache/hashes` directory. Stores hash manifests.
     pub hashes_dir: PathBuf,
 
-    /// The `.moon/cache/outputs` directory. Stores task outputs as hashed archives.
+    /// The `.moon/cache/outputs` directory. Stores task outputs as hashed archives,
+    /// and a digest of each archive.
     pub outputs_dir: PathBuf,
+
+    /// The `.moon/cache/store` directory. Stores task outputs unpacked,
+    /// as content-addressed objects, with a manifest per hash.
+    pub store_dir: PathBuf,
+
+    /// Manifests that have been generated, but not written to the cache,
+    /// as dry runs must not modify it. Keyed by hash.
+    unsaved_manifests: Mutex<HashMap<String, String>>,
 }
 
 impl HashEngine {
     pub fn new(cache_dir: &Path) -> miette::Result<HashEngine> {
         let hashes_dir = cache_dir.join("hashes");
         let outputs_dir = cache_dir.join("outputs");
+        let store_dir = cache_dir.join("store");
 
         debug!(
             hashes_dir = ?hashes_dir,
             outputs_dir = ?outputs_dir,
+            store_dir = ?store_dir,
             "Creating hash engine",
         );
 
@@ -29,6 +42,8 @@ impl HashEngine {
         Ok(HashEngine {
             hashes_dir,
             outputs_dir,
+            store_dir,
+            unsaved_manifests: Mutex::new(HashMap::new()),
         })
     }
 
@@ -40,10 +55,52 @@ impl HashEngine {
         self.outputs_dir.join(format!("{hash}.tar.gz"))
     }
 
+    pub fn get_digest_path(&self, hash: &str) -> PathBuf {
+        self.outputs_dir.join(format!("{hash}.digest.json"))
+    }
+
     pub fn get_manifest_path(&self, hash: &str) -> PathBuf {
         self.hashes_dir.join(format!("{hash}.json"))
     }
 
+    pub fn get_store_manifest_path(&self, hash: &str) -> PathBuf {
+        self.store_dir.join(format!("{hash}.json"))
+    }
+
+    pub fn get_store_objects_dir(&self) -> PathBuf {
+        self.store_dir.join("objects")
+    }
+
+    /// Read the manifest for the provided hash, either from memory,
+    /// or from the cache. Return `None` if neither exist.
+    pub fn read_manifest(&self, hash: &str) -> miette::Result<Option<Vec<u8>>> {
+        if let Some(manifest) = self.unsaved_manifests.lock().unwrap().get(hash) {
+            return Ok(Some(manifest.as_bytes().to_vec()));
+        }
+
+        let path = self.get_manifest_path(hash);
+
+        if path.exists() {
+            return Ok(Some(fs::read_file_bytes(path)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Generate a hash, and keep the manifest in memory, instead of writing
+    /// it to the cache. It can still be read with [`HashEngine::read_manifest`].
+    pub fn hold_manifest(&self, mut hasher: ContentHasher) -> miette::Result<String> {
+        let hash = hasher.generate_hash()?;
+        let manifest = hasher.serialize()?.to_owned();
+
+        self.unsaved_manifests
+            .lock()
+            .unwrap()
+            .insert(hash.clone(), manifest);
+
+        Ok(hash)
+    }
+
     pub fn save_manifest(&self, mut hasher: ContentHasher) -> miette::Result<String> {
         let hash = hasher.generate_hash()?;
         let path = self.get_manifest_path(&hash);
@@ -1,8 +1,10 @@
 mod deps_hash;
+mod hash_diff;
 mod hash_engine;
 mod hasher;
 
 pub use deps_hash::*;
+pub use hash_diff::*;
 pub use hash_engine::*;
 pub use hasher::*;
 
@@ -0,0 +1,60 @@
+use moon_hash::*;
+
+const PREVIOUS: &str = r#"[{"command":"build","deps":{"app:lib":"abc"},"envVars":{"NODE_ENV":"development"},"inputs":{"src/a.ts":"111","src/b.ts":"222"}},{"nodeVersion":"18.0.0"}]"#;
+
+#[test]
+fn returns_nothing_when_identical() {
+    assert!(diff_manifests(PREVIOUS, PREVIOUS).unwrap().is_empty());
+}
+
+#[test]
+fn detects_changed_fields() {
+    let current = r#"[{"command":"build","deps":{"app:lib":"def"},"envVars":{"NODE_ENV":"production"},"inputs":{"src/a.ts":"333","src/c.ts":"444"}},{"nodeVersion":"20.0.0"}]"#;
+
+    let changes = diff_manifests(PREVIOUS, current).unwrap();
+
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| (change.kind, change.get_name()))
+            .collect::<Vec<_>>(),
+        vec![
+            (ManifestChangeKind::Dependency, "app:lib"),
+            (ManifestChangeKind::EnvVar, "NODE_ENV"),
+            (ManifestChangeKind::Input, "src/a.ts"),
+            (ManifestChangeKind::Input, "src/b.ts"),
+            (ManifestChangeKind::Input, "src/c.ts"),
+            (ManifestChangeKind::Toolchain, "nodeVersion"),
+        ]
+    );
+
+    let removed = changes.iter().find(|c| c.key == "inputs.src/b.ts").unwrap();
+
+    assert_eq!(removed.previous, Some("222".into()));
+    assert_eq!(removed.current, None);
+
+    let added = changes.iter().find(|c| c.key == "inputs.src/c.ts").unwrap();
+
+    assert_eq!(added.previous, None);
+    assert_eq!(added.current, Some("444".into()));
+}
+
+#[test]
+fn prefixes_colliding_fields_with_index() {
+    let previous = r#"[{"version":"1"},{"version":"1"}]"#;
+    let current = r#"[{"version":"1"},{"version":"2"}]"#;
+
+    let changes = diff_manifests(previous, current).unwrap();
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].key, "[1].version");
+}
+
+#[test]
+fn diffs_lines() {
+    let lines = diff_manifest_lines("a\nb\n", "a\nc\n");
+
+    assert!(matches!(lines[0], ManifestLine::Both("a")));
+    assert!(matches!(lines[1], ManifestLine::Left("b")));
+    assert!(matches!(lines[2], ManifestLine::Right("c")));
+}
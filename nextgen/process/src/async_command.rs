@@ -1,54 +1,67 @@
 use crate::command_inspector::CommandInspector;
-use crate::output_to_error;
+use crate::output::{mask_output_secrets, mask_secrets, output_to_error};
 use crate::process_error::ProcessError;
+use crate::process_group::ProcessGroupGuard;
+use crate::timeout::TimeoutWatchdog;
 use std::process::{Output, Stdio};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::task;
 
 pub struct AsyncCommand<'cmd> {
     pub inner: Command,
     pub inspector: CommandInspector<'cmd>,
+    pub output_listeners: Vec<UnboundedSender<String>>,
+    pub print_output: bool,
+    pub process_group: Option<String>,
+    /// Failure to create the sandbox, which must fail the process,
+    /// instead of running it without one.
+    pub sandbox_error: Option<std::io::Error>,
+    pub secrets: Vec<String>,
+    pub timeout: Option<Duration>,
 }
 
 impl<'cmd> AsyncCommand<'cmd> {
     pub async fn exec_capture_output(&mut self) -> miette::Result<Output> {
         self.inspector.log_command();
+        self.handle_sandbox_error()?;
 
         let command = &mut self.inner;
-        let output: Output;
 
-        if self.inspector.should_pass_stdin() {
-            let mut child = command
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|error| ProcessError::Capture {
-                    bin: self.get_bin_name(),
-                    error,
-                })?;
+        let mut child = command
+            .stdin(if self.inspector.should_pass_stdin() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| ProcessError::Capture {
+                bin: self.get_bin_name(),
+                error,
+            })?;
 
-            self.write_input_to_child(&mut child).await?;
+        let watchdog = self.watch_child(&child);
+        let _group = self.register_group(&child);
 
-            output = child
-                .wait_with_output()
-                .await
-                .map_err(|error| ProcessError::Capture {
-                    bin: self.get_bin_name(),
-                    error,
-                })?;
-        } else {
-            output = command
-                .output()
-                .await
-                .map_err(|error| ProcessError::Capture {
-                    bin: self.get_bin_name(),
-                    error,
-                })?;
+        if self.inspector.should_pass_stdin() {
+            self.write_input_to_child(&mut child).await?;
         }
 
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|error| ProcessError::Capture {
+                bin: self.get_bin_name(),
+                error,
+            })?;
+        let output = mask_output_secrets(output, &self.secrets);
+
+        self.handle_timeout(watchdog)?;
         self.handle_nonzero_status(&output, true)?;
 
         Ok(output)
@@ -56,6 +69,7 @@ impl<'cmd> AsyncCommand<'cmd> {
 
     pub async fn exec_stream_output(&mut self) -> miette::Result<Output> {
         self.inspector.log_command();
+        self.handle_sandbox_error()?;
 
         let command = &mut self.inner;
         let mut child: Child;
@@ -78,11 +92,16 @@ impl<'cmd> AsyncCommand<'cmd> {
             })?;
         };
 
+        let watchdog = self.watch_child(&child);
+        let _group = self.register_group(&child);
+
         let status = child.wait().await.map_err(|error| ProcessError::Stream {
             bin: self.get_bin_name(),
             error,
         })?;
 
+        self.handle_timeout(watchdog)?;
+
         let output = Output {
             status,
             stderr: vec![],
@@ -96,6 +115,7 @@ impl<'cmd> AsyncCommand<'cmd> {
 
     pub async fn exec_stream_and_capture_output(&mut self) -> miette::Result<Output> {
         self.inspector.log_command();
+        self.handle_sandbox_error()?;
 
         let command = &mut self.inner;
 
@@ -113,6 +133,9 @@ impl<'cmd> AsyncCommand<'cmd> {
                 error,
             })?;
 
+        let watchdog = self.watch_child(&child);
+        let _group = self.register_group(&child);
+
         if self.inspector.should_pass_stdin() {
             self.write_input_to_child(&mut child).await?;
         }
@@ -135,16 +158,30 @@ impl<'cmd> AsyncCommand<'cmd> {
         let prefix = Arc::new(self.inspector.get_prefix());
         let stderr_prefix = Arc::clone(&prefix);
         let stdout_prefix = Arc::clone(&prefix);
+        let stderr_listeners = self.output_listeners.clone();
+        let stdout_listeners = self.output_listeners.clone();
+        let print_output = self.print_output;
+        let secrets = Arc::new(self.secrets.clone());
+        let stderr_secrets = Arc::clone(&secrets);
+        let stdout_secrets = Arc::clone(&secrets);
 
         handles.push(task::spawn(async move {
             let mut lines = stderr.lines();
             let mut captured_lines = vec![];
 
             while let Ok(Some(line)) = lines.next_line().await {
-                if stderr_prefix.is_empty() {
-                    eprintln!("{line}");
-                } else {
-                    eprintln!("{stderr_prefix}{line}");
+                let line = mask_secrets(&line, &stderr_secrets);
+
+                if print_output {
+                    if stderr_prefix.is_empty() {
+                        eprintln!("{line}");
+                    } else {
+                        eprintln!("{stderr_prefix}{line}");
+                    }
+                }
+
+                for listener in &stderr_listeners {
+                    let _ = listener.send(line.clone());
                 }
 
                 captured_lines.push(line);
@@ -161,10 +198,18 @@ impl<'cmd> AsyncCommand<'cmd> {
             let mut captured_lines = vec![];
 
             while let Ok(Some(line)) = lines.next_line().await {
-                if stdout_prefix.is_empty() {
-                    println!("{line}");
-                } else {
-                    println!("{stdout_prefix}{line}");
+                let line = mask_secrets(&line, &stdout_secrets);
+
+                if print_output {
+                    if stdout_prefix.is_empty() {
+                        println!("{line}");
+                    } else {
+                        println!("{stdout_prefix}{line}");
+                    }
+                }
+
+                for listener in &stdout_listeners {
+                    let _ = listener.send(line.clone());
                 }
 
                 captured_lines.push(line);
@@ -189,6 +234,8 @@ impl<'cmd> AsyncCommand<'cmd> {
                 error,
             })?;
 
+        self.handle_timeout(watchdog)?;
+
         let output = Output {
             status,
             stdout: captured_stdout.read().unwrap().join("\n").into_bytes(),
@@ -208,6 +255,46 @@ impl<'cmd> AsyncCommand<'cmd> {
             .to_string()
     }
 
+    fn handle_sandbox_error(&mut self) -> miette::Result<()> {
+        if let Some(error) = self.sandbox_error.take() {
+            return Err(ProcessError::Sandbox {
+                bin: self.get_bin_name(),
+                error,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn watch_child(&self, child: &Child) -> Option<TimeoutWatchdog> {
+        match (self.timeout, child.id()) {
+            (Some(timeout), Some(pid)) => Some(TimeoutWatchdog::new(pid, timeout)),
+            _ => None,
+        }
+    }
+
+    fn register_group(&self, child: &Child) -> Option<ProcessGroupGuard> {
+        match (&self.process_group, child.id()) {
+            (Some(key), Some(pid)) => Some(ProcessGroupGuard::new(key, pid)),
+            _ => None,
+        }
+    }
+
+    fn handle_timeout(&self, watchdog: Option<TimeoutWatchdog>) -> miette::Result<()> {
+        if let (Some(watchdog), Some(timeout)) = (watchdog, self.timeout) {
+            if watchdog.stop() {
+                return Err(ProcessError::Timeout {
+                    bin: self.get_bin_name(),
+                    timeout: timeout.as_secs(),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
     fn handle_nonzero_status(&self, output: &Output, with_message: bool) -> miette::Result<()> {
         if self.inspector.should_error_nonzero() && !output.status.success() {
             return Err(output_to_error(self.get_bin_name(), output, with_message).into());
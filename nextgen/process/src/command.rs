@@ -1,11 +1,16 @@
-use crate::{async_command::AsyncCommand, command_inspector::CommandInspector, shell};
+use crate::{
+    async_command::AsyncCommand, command_inspector::CommandInspector, output::mask_secrets,
+    sandbox::Sandbox, shell,
+};
 use moon_common::{color, is_test_env};
 use rustc_hash::FxHashMap;
 use std::{
     ffi::{OsStr, OsString},
     path::{Path, PathBuf},
+    time::Duration,
 };
 use tokio::process::Command as TokioCommand;
+use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Debug)]
 pub struct Command {
@@ -23,14 +28,33 @@ pub struct Command {
     /// Values to pass to stdin
     pub input: Vec<OsString>,
 
+    /// Receive each line of output while streaming and capturing
+    pub output_listeners: Vec<UnboundedSender<String>>,
+
     /// Prefix to prepend to all log lines
     pub prefix: Option<String>,
 
     /// Log the command to the terminal before running
     pub print_command: bool,
 
+    /// Print output to the terminal while streaming and capturing
+    pub print_output: bool,
+
+    /// Spawn in a new process group, registered under this key, so that
+    /// the process and its descendants can be terminated together
+    pub process_group: Option<String>,
+
+    /// Restrict the paths the process can access (Linux only)
+    pub sandbox: Option<Sandbox>,
+
+    /// Values to mask in captured output and logged commands
+    pub secrets: Vec<String>,
+
     /// Shell to wrap executing commands in
     pub shell: Option<shell::Shell>,
+
+    /// Terminate the process and its group after this duration
+    pub timeout: Option<Duration>,
 }
 
 impl Command {
@@ -44,9 +68,15 @@ impl Command {
             env: FxHashMap::default(),
             error_on_nonzero: true,
             input: vec![],
+            output_listeners: vec![],
             prefix: None,
             print_command: false,
+            print_output: true,
+            process_group: None,
+            sandbox: None,
+            secrets: vec![],
             shell: None,
+            timeout: None,
         };
 
         // Referencing a batch script needs to be ran with a shell
@@ -57,6 +87,24 @@ impl Command {
         command
     }
 
+    pub fn add_output_listener(&mut self, listener: UnboundedSender<String>) -> &mut Command {
+        self.output_listeners.push(listener);
+        self
+    }
+
+    pub fn add_secret<V: AsRef<str>>(&mut self, value: V) -> &mut Command {
+        let value = value.as_ref();
+
+        if !value.is_empty() && !self.secrets.iter().any(|s| s == value) {
+            self.secrets.push(value.to_owned());
+
+            // Longest first, so that overlapping secrets are fully masked
+            self.secrets.sort_by(|a, b| b.len().cmp(&a.len()));
+        }
+
+        self
+    }
+
     pub fn arg<A: AsRef<OsStr>>(&mut self, arg: A) -> &mut Command {
         self.args.push(arg.as_ref().to_os_string());
         self
@@ -103,9 +151,49 @@ impl Command {
             command.current_dir(cwd);
         }
 
+        // Spawn in a new process group, so that the entire group
+        // can be terminated when the timeout is reached, or on request
+        #[cfg(unix)]
+        {
+            if self.timeout.is_some() || self.process_group.is_some() {
+                command.process_group(0);
+            }
+        }
+
+        // Build the ruleset before spawning, so that only the syscalls
+        // to enforce it are made between fork and exec. A sandbox was
+        // explicitly requested, so never run without it on failure.
+        #[cfg(target_os = "linux")]
+        let sandbox_error =
+            self.sandbox.as_ref().and_then(
+                |sandbox| match crate::sandbox::landlock::create_ruleset(sandbox) {
+                    Ok(ruleset) => {
+                        unsafe {
+                            command.pre_exec(move || {
+                                use std::os::fd::AsRawFd;
+
+                                crate::sandbox::landlock::restrict_self(ruleset.as_raw_fd())
+                            });
+                        }
+
+                        None
+                    }
+                    Err(error) => Some(error),
+                },
+            );
+
+        #[cfg(not(target_os = "linux"))]
+        let sandbox_error = None;
+
         AsyncCommand {
             inner: command,
             inspector,
+            output_listeners: self.output_listeners.clone(),
+            print_output: self.print_output,
+            process_group: self.process_group.clone(),
+            sandbox_error,
+            secrets: self.secrets.clone(),
+            timeout: self.timeout,
         }
     }
 
@@ -163,11 +251,20 @@ impl Command {
         CommandInspector::new(self)
     }
 
+    pub fn mask_secrets(&self, value: &str) -> String {
+        mask_secrets(value, &self.secrets)
+    }
+
     pub fn set_print_command(&mut self, state: bool) -> &mut Command {
         self.print_command = state;
         self
     }
 
+    pub fn set_print_output(&mut self, state: bool) -> &mut Command {
+        self.print_output = state;
+        self
+    }
+
     pub fn set_error_on_nonzero(&mut self, state: bool) -> &mut Command {
         self.error_on_nonzero = state;
         self
@@ -193,11 +290,26 @@ impl Command {
         self
     }
 
+    pub fn set_process_group<K: AsRef<str>>(&mut self, key: K) -> &mut Command {
+        self.process_group = Some(key.as_ref().to_owned());
+        self
+    }
+
+    pub fn set_sandbox(&mut self, sandbox: Sandbox) -> &mut Command {
+        self.sandbox = Some(sandbox);
+        self
+    }
+
     pub fn set_shell(&mut self, shell: shell::Shell) -> &mut Command {
         self.shell = Some(shell);
         self
     }
 
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Command {
+        self.timeout = Some(timeout);
+        self
+    }
+
     pub fn with_shell(&mut self) -> &mut Command {
         self.set_shell(shell::create_shell());
         self
@@ -189,7 +189,11 @@ impl<'cmd> CommandInspector<'cmd> {
         if self.command.print_command {
             println!(
                 "{}",
-                self.format_command(&command_line.main_command, &workspace_root, None)
+                self.format_command(
+                    &self.command.mask_secrets(&command_line.main_command),
+                    &workspace_root,
+                    None
+                )
             );
         }
 
@@ -212,6 +216,12 @@ impl<'cmd> CommandInspector<'cmd> {
                     key.starts_with("MOON_") || key.starts_with("PROTO_")
                 }
             })
+            .map(|(key, value)| {
+                (
+                    key.to_string_lossy(),
+                    self.command.mask_secrets(&value.to_string_lossy()),
+                )
+            })
             .collect::<FxHashMap<_, _>>();
 
         let working_dir_field = self.command.cwd.as_ref().unwrap_or(&workspace_root);
@@ -220,7 +230,7 @@ impl<'cmd> CommandInspector<'cmd> {
             env_vars = ?env_vars_field,
             working_dir = ?working_dir_field,
             "Running command {}",
-            color::shell(command_line.to_string())
+            color::shell(self.command.mask_secrets(&command_line.to_string()))
         );
     }
 }
@@ -3,9 +3,15 @@ mod command;
 mod command_inspector;
 mod output;
 mod process_error;
+mod process_group;
+mod sandbox;
 pub mod shell;
+mod timeout;
 
 pub use command::*;
 pub use moon_args as args;
 pub use output::*;
 pub use process_error::*;
+pub use process_group::*;
+pub use sandbox::*;
+pub use timeout::*;
@@ -2,6 +2,9 @@ use crate::process_error::ProcessError;
 
 pub use std::process::Output;
 
+/// Replacement for secret values in output and logs.
+pub const SECRET_MASK: &str = "*****";
+
 #[inline]
 pub fn output_to_string(data: &[u8]) -> String {
     String::from_utf8(data.to_vec()).unwrap_or_default()
@@ -36,3 +39,29 @@ pub fn output_to_error(bin: String, output: &Output, with_message: bool) -> Proc
         output: message,
     }
 }
+
+/// Replace all occurrences of the secret values with a mask. Secrets
+/// must be sorted longest first, so that overlapping values are fully masked.
+pub fn mask_secrets(value: &str, secrets: &[String]) -> String {
+    let mut value = value.to_owned();
+
+    for secret in secrets {
+        if value.contains(secret.as_str()) {
+            value = value.replace(secret.as_str(), SECRET_MASK);
+        }
+    }
+
+    value
+}
+
+pub fn mask_output_secrets(output: Output, secrets: &[String]) -> Output {
+    if secrets.is_empty() {
+        return output;
+    }
+
+    Output {
+        status: output.status,
+        stdout: mask_secrets(&String::from_utf8_lossy(&output.stdout), secrets).into_bytes(),
+        stderr: mask_secrets(&String::from_utf8_lossy(&output.stderr), secrets).into_bytes(),
+    }
+}
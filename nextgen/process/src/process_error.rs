@@ -37,6 +37,17 @@ pub enum ProcessError {
         output: String,
     },
 
+    #[diagnostic(code(process::sandbox::failed))]
+    #[error(
+        "Failed to sandbox {}, refusing to run it without one.",
+        .bin.style(Style::Shell),
+    )]
+    Sandbox {
+        bin: String,
+        #[source]
+        error: std::io::Error,
+    },
+
     #[diagnostic(code(process::stream::failed))]
     #[error(
         "Failed to execute {} and stream output.",
@@ -61,6 +72,14 @@ pub enum ProcessError {
         error: std::io::Error,
     },
 
+    #[diagnostic(code(process::timeout))]
+    #[error(
+        "Process {} timed out after {} seconds and was terminated.",
+        .bin.style(Style::Shell),
+        .timeout.style(Style::Symbol),
+    )]
+    Timeout { bin: String, timeout: u64 },
+
     #[diagnostic(code(process::stdin::failed))]
     #[error(
         "Failed to write stdin to {}.",
@@ -0,0 +1,57 @@
+use rustc_hash::FxHashMap;
+use std::sync::{Mutex, OnceLock};
+
+// Process groups that are currently running, keyed by their owner,
+// so that they can be terminated by something other than the owner
+static PROCESS_GROUPS: OnceLock<Mutex<FxHashMap<String, Vec<u32>>>> = OnceLock::new();
+
+fn get_process_groups() -> &'static Mutex<FxHashMap<String, Vec<u32>>> {
+    PROCESS_GROUPS.get_or_init(|| Mutex::new(FxHashMap::default()))
+}
+
+/// Return the process group leaders (by PID) that are currently
+/// running for the provided key.
+pub fn find_process_groups(key: &str) -> Vec<u32> {
+    get_process_groups()
+        .lock()
+        .unwrap()
+        .get(key)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Registers a spawned process group for the key, until dropped.
+pub(crate) struct ProcessGroupGuard {
+    key: String,
+    pid: u32,
+}
+
+impl ProcessGroupGuard {
+    pub fn new(key: &str, pid: u32) -> Self {
+        get_process_groups()
+            .lock()
+            .unwrap()
+            .entry(key.to_owned())
+            .or_default()
+            .push(pid);
+
+        ProcessGroupGuard {
+            key: key.to_owned(),
+            pid,
+        }
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        let mut groups = get_process_groups().lock().unwrap();
+
+        if let Some(pids) = groups.get_mut(&self.key) {
+            pids.retain(|pid| *pid != self.pid);
+
+            if pids.is_empty() {
+                groups.remove(&self.key);
+            }
+        }
+    }
+}
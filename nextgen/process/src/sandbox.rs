@@ -0,0 +1,304 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Paths that a sandboxed process, and all of its children, are allowed to
+/// access. Everything else is denied, so reads of undeclared inputs and
+/// writes to undeclared outputs fail with a permission error.
+///
+/// Sandboxing is only supported on Linux (5.13+) through Landlock, and
+/// is a no-op on other systems.
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    /// Directories (recursively) that files and directories can be created
+    /// in and written to, but not read from, unless also allowed.
+    pub create_dirs: Vec<PathBuf>,
+
+    /// Directories that can be listed, but not read from,
+    /// unless a file or directory within is also allowed.
+    pub list_dirs: Vec<PathBuf>,
+
+    /// Files and directories (recursively) that can be read from and executed.
+    pub read_paths: Vec<PathBuf>,
+
+    /// Directories that must exist before the process is spawned, as rules
+    /// can't be added for missing paths. Created when the sandbox is enforced.
+    pub required_dirs: Vec<PathBuf>,
+
+    /// Files and directories (recursively) that can be read from and written to.
+    pub write_paths: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    pub fn is_supported() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            landlock::get_abi_version() > 0
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
+    pub fn allow_create<P: AsRef<Path>>(&mut self, path: P) -> &mut Sandbox {
+        self.create_dirs.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn allow_list<P: AsRef<Path>>(&mut self, path: P) -> &mut Sandbox {
+        self.list_dirs.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn allow_read<P: AsRef<Path>>(&mut self, path: P) -> &mut Sandbox {
+        self.read_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn require_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Sandbox {
+        self.required_dirs.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn allow_write<P: AsRef<Path>>(&mut self, path: P) -> &mut Sandbox {
+        self.write_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn can_read(&self, path: &Path) -> bool {
+        self.read_paths
+            .iter()
+            .chain(self.write_paths.iter())
+            .any(|p| path.starts_with(p))
+    }
+
+    pub fn can_write(&self, path: &Path) -> bool {
+        self.write_paths
+            .iter()
+            .chain(self.create_dirs.iter())
+            .any(|p| path.starts_with(p))
+    }
+
+    /// Sandbox violations are reported by the process itself as permission
+    /// errors, so extract the paths from those errors in its output, that
+    /// are outside of the allowed paths.
+    pub fn find_violations(&self, output: &str, cwd: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![];
+
+        for line in output.lines() {
+            let lower_line = line.to_lowercase();
+
+            if !lower_line.contains("permission denied") && !line.contains("EACCES") {
+                continue;
+            }
+
+            for token in line.split_whitespace() {
+                let token = token.trim_matches(|c: char| {
+                    matches!(
+                        c,
+                        '\'' | '"' | '`' | ',' | ':' | ';' | '(' | ')' | '[' | ']'
+                    )
+                });
+
+                if !is_path_like(token) {
+                    continue;
+                }
+
+                let path = cwd
+                    .join(token)
+                    .components()
+                    .filter(|c| !matches!(c, Component::CurDir))
+                    .collect::<PathBuf>();
+
+                if !self.can_read(&path) && !self.can_write(&path) && !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths
+    }
+}
+
+fn is_path_like(token: &str) -> bool {
+    (token.contains('/') || token.contains('.'))
+        && token.chars().any(|c| c.is_ascii_alphabetic())
+        && !token.contains("://")
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) mod landlock {
+    use super::Sandbox;
+    use std::ffi::CString;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use tracing::trace;
+
+    // https://docs.kernel.org/userspace-api/landlock.html
+    const CREATE_RULESET_VERSION: u32 = 1 << 0;
+    const RULE_PATH_BENEATH: u32 = 1;
+
+    const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+    const ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+    const ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+    const ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+    const ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+    const ACCESS_FS_MAKE_SYM: u64 = 1 << 12;
+    const ACCESS_FS_REFER: u64 = 1 << 13;
+
+    // Every right of the first ABI, so that all file system access is denied by default
+    const ACCESS_FS_ALL_V1: u64 = (1 << 13) - 1;
+    const ACCESS_FS_FILE: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_WRITE_FILE | ACCESS_FS_READ_FILE;
+    const ACCESS_FS_READ: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+    const ACCESS_FS_CREATE: u64 = ACCESS_FS_WRITE_FILE
+        | ACCESS_FS_REMOVE_DIR
+        | ACCESS_FS_REMOVE_FILE
+        | ACCESS_FS_MAKE_DIR
+        | ACCESS_FS_MAKE_REG
+        | ACCESS_FS_MAKE_SYM;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    pub fn get_abi_version() -> i64 {
+        unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0,
+                CREATE_RULESET_VERSION,
+            )
+        }
+    }
+
+    fn add_rule(ruleset: &OwnedFd, path: &Path, mut access: u64) -> io::Result<()> {
+        let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+            return Ok(());
+        };
+
+        let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+
+        // Paths that don't exist can't be accessed anyways
+        if fd < 0 {
+            trace!(path = ?path, "Skipping sandbox rule for missing path");
+
+            return Ok(());
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        if !path.is_dir() {
+            access &= ACCESS_FS_FILE;
+
+            // Such as listing a file, which is always allowed
+            if access == 0 {
+                return Ok(());
+            }
+        }
+
+        let attr = PathBeneathAttr {
+            allowed_access: access,
+            parent_fd: fd.as_raw_fd(),
+        };
+
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset.as_raw_fd(),
+                RULE_PATH_BENEATH,
+                &attr as *const PathBeneathAttr,
+                0,
+            )
+        };
+
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Create a ruleset for the sandbox, that must be enforced within
+    /// the child process with [`restrict_self`].
+    pub fn create_ruleset(sandbox: &Sandbox) -> io::Result<OwnedFd> {
+        let abi = get_abi_version();
+
+        if abi < 1 {
+            return Err(io::Error::from(io::ErrorKind::Unsupported));
+        }
+
+        // Without handling "refer", files can never be moved between
+        // directories, so allow it within the write paths when possible
+        let refer = if abi >= 2 { ACCESS_FS_REFER } else { 0 };
+
+        let attr = RulesetAttr {
+            handled_access_fs: ACCESS_FS_ALL_V1 | refer,
+        };
+
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                std::mem::size_of::<RulesetAttr>(),
+                0,
+            )
+        };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let ruleset = unsafe { OwnedFd::from_raw_fd(fd as RawFd) };
+
+        for dir in &sandbox.required_dirs {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        for path in &sandbox.create_dirs {
+            add_rule(&ruleset, path, ACCESS_FS_CREATE | refer)?;
+        }
+
+        for path in &sandbox.list_dirs {
+            add_rule(&ruleset, path, ACCESS_FS_READ_DIR)?;
+        }
+
+        for path in &sandbox.read_paths {
+            add_rule(&ruleset, path, ACCESS_FS_READ)?;
+        }
+
+        for path in &sandbox.write_paths {
+            add_rule(&ruleset, path, ACCESS_FS_ALL_V1 | refer)?;
+        }
+
+        Ok(ruleset)
+    }
+
+    /// Enforce the ruleset on the current process. This is called
+    /// between fork and exec, so must only use async-signal-safe calls.
+    pub fn restrict_self(ruleset: RawFd) -> io::Result<()> {
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+}
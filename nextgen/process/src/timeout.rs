@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::{self, JoinHandle};
+use tokio::time::sleep;
+use tracing::debug;
+
+/// How long to wait for the process group to exit after requesting
+/// termination, before forcefully killing it.
+pub const TIMEOUT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+#[cfg(unix)]
+async fn signal_process_group(pid: u32, force: bool) {
+    // A negative PID signals every process in the group
+    unsafe {
+        libc::kill(
+            -(pid as libc::pid_t),
+            if force { libc::SIGKILL } else { libc::SIGTERM },
+        );
+    }
+}
+
+#[cfg(windows)]
+async fn signal_process_group(pid: u32, force: bool) {
+    let mut command = tokio::process::Command::new("taskkill");
+    command.arg("/T").arg("/PID").arg(pid.to_string());
+
+    if force {
+        command.arg("/F");
+    }
+
+    let _ = command.output().await;
+}
+
+#[cfg(unix)]
+async fn is_process_group_running(pid: u32) -> bool {
+    // Signal 0 only checks whether any process in the group still exists
+    unsafe { libc::kill(-(pid as libc::pid_t), 0) == 0 }
+}
+
+// Windows has no process groups, so only check the root of the process tree
+#[cfg(windows)]
+async fn is_process_group_running(pid: u32) -> bool {
+    tokio::process::Command::new("tasklist")
+        .arg("/NH")
+        .arg("/FI")
+        .arg(format!("PID eq {pid}"))
+        .output()
+        .await
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or_default()
+}
+
+/// Terminate the process group led by the process, by sending a SIGTERM,
+/// and then a SIGKILL if it's still running after the grace period.
+/// Resolves once the group has exited, or has been killed.
+pub async fn terminate_process_group(pid: u32, grace_period: Duration) {
+    signal_process_group(pid, false).await;
+
+    let started = Instant::now();
+
+    while started.elapsed() < grace_period {
+        if !is_process_group_running(pid).await {
+            return;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    debug!(
+        pid,
+        "Process group still running after grace period, killing"
+    );
+
+    signal_process_group(pid, true).await;
+}
+
+/// Watches a spawned process, and terminates its process group once the
+/// timeout has been reached. The group is sent a SIGTERM, and then a
+/// SIGKILL if it's still running after the grace period.
+pub struct TimeoutWatchdog {
+    handle: JoinHandle<()>,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl TimeoutWatchdog {
+    pub fn new(pid: u32, timeout: Duration) -> Self {
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let timed_out_clone = Arc::clone(&timed_out);
+
+        let handle = task::spawn(async move {
+            sleep(timeout).await;
+
+            debug!(pid, "Process timed out, terminating its process group");
+
+            timed_out_clone.store(true, Ordering::Release);
+            terminate_process_group(pid, TIMEOUT_GRACE_PERIOD).await;
+        });
+
+        TimeoutWatchdog { handle, timed_out }
+    }
+
+    /// Stop watching the process, and return true if it timed out.
+    pub fn stop(self) -> bool {
+        self.timed_out.load(Ordering::Acquire)
+    }
+}
+
+// Ensure the process isn't terminated after we've stopped waiting on it.
+// The leader exits on SIGTERM, but descendants may not, so once it has
+// timed out, the task is detached to escalate to SIGKILL if necessary.
+impl Drop for TimeoutWatchdog {
+    fn drop(&mut self) {
+        if !self.timed_out.load(Ordering::Acquire) {
+            self.handle.abort();
+        }
+    }
+}
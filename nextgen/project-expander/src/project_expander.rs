@@ -76,6 +76,7 @@ impl<'graph, 'query> ProjectExpander<'graph, 'query> {
             // Resolve in this order!
             expander.expand_env(&mut task)?;
             expander.expand_deps(&mut task)?;
+            expander.expand_finally(&mut task)?;
             expander.expand_inputs(&mut task)?;
             expander.expand_outputs(&mut task, boundaries)?;
             expander.expand_args(&mut task)?;
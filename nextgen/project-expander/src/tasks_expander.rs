@@ -254,6 +254,68 @@ impl<'graph, 'query> TasksExpander<'graph, 'query> {
         Ok(())
     }
 
+    pub fn expand_finally(&mut self, task: &mut Task) -> miette::Result<()> {
+        if task.options.finally.is_empty() {
+            return Ok(());
+        }
+
+        trace!(
+            target = task.target.as_str(),
+            finally = ?task.options.finally.iter().map(|t| t.as_str()).collect::<Vec<_>>(),
+            "Expanding target scopes for cleanup tasks",
+        );
+
+        let project = &self.context.project;
+        let mut targets: Vec<Target> = vec![];
+
+        for finally_target in &task.options.finally {
+            let task_id = &finally_target.task_id;
+
+            let (project_id, exists) = match &finally_target.scope {
+                // ~:task
+                TargetScope::OwnSelf => (&project.id, project.tasks.contains_key(task_id)),
+                // id:task
+                TargetScope::Project(project_id) => (
+                    project_id,
+                    if project_id == &project.id {
+                        project.tasks.contains_key(task_id)
+                    } else {
+                        (self.context.query)(format!("project={id}", id = project_id))?
+                            .iter()
+                            .any(|p| p.tasks.contains_key(task_id))
+                    },
+                ),
+                // :task, ^:task, #tag:task
+                _ => {
+                    return Err(TasksExpanderError::UnsupportedTargetScopeInFinally {
+                        dep: finally_target.to_owned(),
+                        task: task.target.to_owned(),
+                    }
+                    .into());
+                }
+            };
+
+            if !exists {
+                return Err(TasksExpanderError::UnknownTarget {
+                    dep: finally_target.to_owned(),
+                    task: task.target.to_owned(),
+                }
+                .into());
+            }
+
+            let target = Target::new(project_id, task_id)?;
+
+            // Avoid circular references
+            if target != task.target && !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+
+        task.options.finally = targets;
+
+        Ok(())
+    }
+
     pub fn expand_inputs(&mut self, task: &mut Task) -> miette::Result<()> {
         if task.inputs.is_empty() {
             return Ok(());
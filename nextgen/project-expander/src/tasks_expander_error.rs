@@ -60,4 +60,12 @@ pub enum TasksExpanderError {
         .task.id.style(Style::Label),
     )]
     UnsupportedTargetScopeInDeps { dep: Target, task: Target },
+
+    #[diagnostic(code(task_expander::unsupported_finally_target_scope))]
+    #[error(
+        "Invalid cleanup task {} for {}. Only the project (id:) and self (~:) scopes are supported.",
+        .dep.id.style(Style::Label),
+        .task.id.style(Style::Label),
+    )]
+    UnsupportedTargetScopeInFinally { dep: Target, task: Target },
 }
@@ -789,6 +789,95 @@ mod tasks_expander {
         }
     }
 
+    mod expand_finally {
+        use super::*;
+
+        #[test]
+        fn expands_own_self_and_project_scopes() {
+            let sandbox = create_empty_sandbox();
+            let project = create_project_with_tasks(sandbox.path(), "project");
+            let other = create_project_with_tasks(sandbox.path(), "foo");
+
+            let mut task = create_task();
+            task.options.finally.push(Target::parse("~:build").unwrap());
+            task.options.finally.push(Target::parse("lint").unwrap());
+            task.options
+                .finally
+                .push(Target::parse("foo:test").unwrap());
+
+            let context = create_context_with_query(&project, sandbox.path(), |_| Ok(vec![&other]));
+            TasksExpander::new(&context)
+                .expand_finally(&mut task)
+                .unwrap();
+
+            assert_eq!(
+                task.options.finally,
+                vec![
+                    Target::parse("project:build").unwrap(),
+                    Target::parse("project:lint").unwrap(),
+                    Target::parse("foo:test").unwrap(),
+                ]
+            );
+        }
+
+        #[test]
+        fn ignores_self_and_dupes() {
+            let sandbox = create_empty_sandbox();
+            let mut project = create_project_with_tasks(sandbox.path(), "project");
+
+            project.tasks.insert("task".into(), create_task());
+
+            let mut task = create_task();
+            task.options.finally.push(Target::parse("~:task").unwrap());
+            task.options.finally.push(Target::parse("~:build").unwrap());
+            task.options
+                .finally
+                .push(Target::parse("project:build").unwrap());
+
+            let context = create_context(&project, sandbox.path());
+            TasksExpander::new(&context)
+                .expand_finally(&mut task)
+                .unwrap();
+
+            assert_eq!(
+                task.options.finally,
+                vec![Target::parse("project:build").unwrap()]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "Invalid dependency ~:unknown for project:task")]
+        fn errors_for_unknown_task() {
+            let sandbox = create_empty_sandbox();
+            let project = create_project_with_tasks(sandbox.path(), "project");
+
+            let mut task = create_task();
+            task.options
+                .finally
+                .push(Target::parse("~:unknown").unwrap());
+
+            let context = create_context(&project, sandbox.path());
+            TasksExpander::new(&context)
+                .expand_finally(&mut task)
+                .unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "Invalid cleanup task ^:build for project:task")]
+        fn errors_for_deps_scope() {
+            let sandbox = create_empty_sandbox();
+            let project = create_project_with_tasks(sandbox.path(), "project");
+
+            let mut task = create_task();
+            task.options.finally.push(Target::parse("^:build").unwrap());
+
+            let context = create_context(&project, sandbox.path());
+            TasksExpander::new(&context)
+                .expand_finally(&mut task)
+                .unwrap();
+        }
+    }
+
     mod expand_inputs {
         use super::*;
 
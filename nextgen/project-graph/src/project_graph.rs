@@ -190,6 +190,11 @@ impl ProjectGraph {
         self.get(&possible_id)
     }
 
+    /// Return the absolute path to the workspace root.
+    pub fn get_workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
     /// Return a list of IDs for all projects currently within the graph.
     pub fn ids(&self) -> Vec<&Id> {
         self.graph
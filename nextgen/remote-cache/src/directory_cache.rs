@@ -0,0 +1,64 @@
+use crate::remote_cache::RemoteCache;
+use crate::remote_cache_error::RemoteCacheError;
+use async_trait::async_trait;
+use starbase_utils::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use tracing::trace;
+
+/// A remote cache backed by a shared directory, like an NFS mount
+/// or a volume that persists between CI runs.
+#[derive(Debug)]
+pub struct DirectoryRemoteCache {
+    pub root: PathBuf,
+}
+
+impl DirectoryRemoteCache {
+    pub fn new(root: PathBuf) -> miette::Result<DirectoryRemoteCache> {
+        if !root.exists() {
+            return Err(RemoteCacheError::MissingDirectory(root).into());
+        }
+
+        Ok(DirectoryRemoteCache { root })
+    }
+}
+
+#[async_trait]
+impl RemoteCache for DirectoryRemoteCache {
+    fn get_name(&self) -> &str {
+        "directory"
+    }
+
+    async fn exists(&self, key: &str) -> miette::Result<bool> {
+        Ok(self.root.join(key).exists())
+    }
+
+    async fn download(&self, key: &str, dest_path: &Path) -> miette::Result<()> {
+        let src_path = self.root.join(key);
+
+        trace!(src = ?src_path, dest = ?dest_path, "Copying {} from shared directory", key);
+
+        fs::copy_file(src_path, dest_path)?;
+
+        Ok(())
+    }
+
+    async fn upload(&self, key: &str, src_path: &Path) -> miette::Result<()> {
+        let dest_path = self.root.join(key);
+
+        if dest_path.exists() {
+            return Ok(());
+        }
+
+        // Other machines may be reading from this directory at the same time,
+        // so copy to a temporary file first and then atomically move it into place
+        let temp_path = self.root.join(format!("{key}.{}.tmp", process::id()));
+
+        trace!(src = ?src_path, dest = ?dest_path, "Copying {} to shared directory", key);
+
+        fs::copy_file(src_path, &temp_path)?;
+        fs::rename(&temp_path, &dest_path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::remote_cache::{stream_response_to_file, RemoteCache};
+use crate::remote_cache_error::RemoteCacheError;
+use async_trait::async_trait;
+use miette::IntoDiagnostic;
+use moon_config::RemoteCacheHttpConfig;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Client, StatusCode};
+use std::env;
+use std::path::Path;
+use tokio_util::codec::{BytesCodec, FramedRead};
+use tracing::trace;
+
+/// A remote cache backed by a plain HTTP server, where entries are
+/// read with `GET`, written with `PUT`, and checked with `HEAD`
+/// requests against `<url>/<key>`.
+#[derive(Debug)]
+pub struct HttpRemoteCache {
+    client: Client,
+    url: String,
+}
+
+impl HttpRemoteCache {
+    pub fn new(config: &RemoteCacheHttpConfig) -> miette::Result<HttpRemoteCache> {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in &config.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).into_diagnostic()?,
+                HeaderValue::from_str(value).into_diagnostic()?,
+            );
+        }
+
+        if let Some(var_name) = &config.token_env_var {
+            if let Ok(token) = env::var(var_name) {
+                let mut value =
+                    HeaderValue::from_str(&format!("Bearer {token}")).into_diagnostic()?;
+                value.set_sensitive(true);
+
+                headers.insert(AUTHORIZATION, value);
+            }
+        }
+
+        Ok(HttpRemoteCache {
+            client: Client::builder()
+                .default_headers(headers)
+                .build()
+                .into_diagnostic()?,
+            url: config.url.trim_end_matches('/').to_owned(),
+        })
+    }
+
+    fn get_url(&self, key: &str) -> String {
+        format!("{}/{key}", self.url)
+    }
+}
+
+#[async_trait]
+impl RemoteCache for HttpRemoteCache {
+    fn get_name(&self) -> &str {
+        "http"
+    }
+
+    async fn exists(&self, key: &str) -> miette::Result<bool> {
+        let response = self
+            .client
+            .head(self.get_url(key))
+            .send()
+            .await
+            .map_err(|error| RemoteCacheError::CheckFailure {
+                key: key.to_owned(),
+                message: error.to_string(),
+            })?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            status => Err(RemoteCacheError::CheckFailure {
+                key: key.to_owned(),
+                message: status.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    async fn download(&self, key: &str, dest_path: &Path) -> miette::Result<()> {
+        let url = self.get_url(key);
+
+        trace!(url, dest = ?dest_path, "Downloading {} from HTTP server", key);
+
+        let map_error = |message: String| RemoteCacheError::DownloadFailure {
+            key: key.to_owned(),
+            message,
+        };
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|error| map_error(error.to_string()))?;
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(map_error(status.to_string()).into());
+        }
+
+        stream_response_to_file(response, dest_path, map_error).await
+    }
+
+    async fn upload(&self, key: &str, src_path: &Path) -> miette::Result<()> {
+        let url = self.get_url(key);
+
+        trace!(url, src = ?src_path, "Uploading {} to HTTP server", key);
+
+        let map_error = |message: String| RemoteCacheError::UploadFailure {
+            key: key.to_owned(),
+            message,
+        };
+
+        let file = tokio::fs::File::open(src_path).await.into_diagnostic()?;
+        let file_length = file
+            .metadata()
+            .await
+            .map(|meta| meta.len())
+            .unwrap_or_default();
+        let file_stream = FramedRead::new(file, BytesCodec::new());
+
+        let response = self
+            .client
+            .put(url)
+            .header("Content-Length", file_length)
+            .body(reqwest::Body::wrap_stream(file_stream))
+            .send()
+            .await
+            .map_err(|error| map_error(error.to_string()))?;
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(map_error(status.to_string()).into());
+        }
+
+        Ok(())
+    }
+}
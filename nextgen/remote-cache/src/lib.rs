@@ -0,0 +1,43 @@
+mod directory_cache;
+mod http_cache;
+mod reapi_cache;
+mod reapi_client;
+pub mod reapi_proto;
+mod remote_cache;
+mod remote_cache_error;
+mod s3_cache;
+
+pub use directory_cache::*;
+pub use http_cache::*;
+pub use reapi_cache::*;
+pub use reapi_client::*;
+pub use remote_cache::*;
+pub use remote_cache_error::*;
+pub use s3_cache::*;
+
+use moon_config::RemoteCacheConfig;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Create a remote cache backend from the workspace configuration.
+/// Returns `None` if no archive based backend has been configured.
+pub fn create_remote_cache(
+    config: &RemoteCacheConfig,
+    workspace_root: &Path,
+) -> miette::Result<Option<BoxedRemoteCache>> {
+    if let Some(directory) = &config.directory {
+        return Ok(Some(Arc::new(DirectoryRemoteCache::new(
+            workspace_root.join(directory),
+        )?)));
+    }
+
+    if let Some(http) = &config.http {
+        return Ok(Some(Arc::new(HttpRemoteCache::new(http)?)));
+    }
+
+    if let Some(s3) = &config.s3 {
+        return Ok(Some(Arc::new(S3RemoteCache::new(s3)?)));
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,284 @@
+use crate::reapi_client::{create_digest, ReapiClient};
+use crate::reapi_proto::{ActionResult, Digest, OutputFile};
+use crate::remote_cache_error::RemoteCacheError;
+use moon_common::path::{standardize_separators, WorkspaceRelativePathBuf};
+use moon_config::RemoteCacheReapiConfig;
+use rustc_hash::FxHashSet;
+use starbase_utils::glob::GlobSet;
+use starbase_utils::{fs, glob};
+use std::path::{Component, Path};
+use tracing::debug;
+
+/// Create an action digest for the provided target hash. A target hash is the
+/// SHA-256 of its hash manifest, so the digest is created from the manifest's
+/// contents, ensuring that the size is that of the hashed data, as required by
+/// the API. Without a manifest, the digest can't be created.
+pub fn create_action_digest(hash: &str, manifest: Option<&[u8]>) -> miette::Result<Digest> {
+    let Some(manifest) = manifest else {
+        return Err(RemoteCacheError::MissingManifest(hash.to_owned()).into());
+    };
+
+    let digest = create_digest(manifest);
+
+    if digest.hash != hash {
+        return Err(RemoteCacheError::ManifestMismatch(hash.to_owned()).into());
+    }
+
+    Ok(digest)
+}
+
+/// Expand the provided output paths (files, directories, and globs)
+/// into a list of files that exist within the workspace.
+pub fn collect_output_files(
+    workspace_root: &Path,
+    output_paths: &[WorkspaceRelativePathBuf],
+) -> miette::Result<Vec<WorkspaceRelativePathBuf>> {
+    let mut files = FxHashSet::default();
+
+    for output in output_paths {
+        let abs_paths = if glob::is_glob(output) {
+            glob::walk_files(workspace_root, [output.as_str()])?
+        } else {
+            let abs_path = output.to_path(workspace_root);
+
+            if abs_path.is_dir() {
+                fs::read_dir_all(&abs_path)?
+                    .into_iter()
+                    .map(|entry| entry.path())
+                    .collect()
+            } else if abs_path.is_file() {
+                vec![abs_path]
+            } else {
+                vec![]
+            }
+        };
+
+        for abs_path in abs_paths {
+            if let Ok(rel_path) = abs_path.strip_prefix(workspace_root) {
+                files.insert(WorkspaceRelativePathBuf::from(standardize_separators(
+                    rel_path.to_string_lossy(),
+                )));
+            }
+        }
+    }
+
+    let mut files = files.into_iter().collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files)
+}
+
+/// Validate an output path returned by the server, which must be relative,
+/// can't traverse upwards, and must be within one of the task's outputs.
+/// Otherwise a misbehaving server could write files anywhere.
+fn validate_output_path(
+    path: &str,
+    output_paths: &[WorkspaceRelativePathBuf],
+    output_globs: &GlobSet,
+) -> miette::Result<WorkspaceRelativePathBuf> {
+    let is_invalid = path.is_empty()
+        || path.starts_with(['/', '\\'])
+        || path.split(['/', '\\']).any(|part| part == "..")
+        || Path::new(path)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+
+    let rel_path = WorkspaceRelativePathBuf::from(path);
+
+    if is_invalid
+        || !(output_globs.matches(rel_path.as_str())
+            || output_paths.iter().any(|output| {
+                !glob::is_glob(output) && (&rel_path == output || rel_path.starts_with(output))
+            }))
+    {
+        return Err(RemoteCacheError::InvalidOutputPath(path.to_owned()).into());
+    }
+
+    Ok(rel_path)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path)
+        .map(|meta| meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or_default()
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+/// A remote cache backed by a Bazel Remote Execution API compatible server,
+/// like bazel-remote or BuildBuddy. Target hashes map to action results,
+/// and each output file maps to a blob in the content addressable storage.
+#[derive(Clone, Debug)]
+pub struct ReapiRemoteCache {
+    client: ReapiClient,
+}
+
+impl ReapiRemoteCache {
+    pub fn new(config: &RemoteCacheReapiConfig) -> miette::Result<ReapiRemoteCache> {
+        Ok(ReapiRemoteCache {
+            client: ReapiClient::new(config)?,
+        })
+    }
+
+    /// Return the action result for the provided action digest, if it exists.
+    pub async fn read_action_result(
+        &self,
+        action_digest: &Digest,
+    ) -> miette::Result<Option<ActionResult>> {
+        self.client.get_action_result(action_digest).await
+    }
+
+    /// Upload the provided output files and logs as blobs, and then
+    /// record an action result that references them.
+    pub async fn write_action_result(
+        &self,
+        action_digest: &Digest,
+        workspace_root: &Path,
+        output_files: &[WorkspaceRelativePathBuf],
+        log_paths: (&Path, &Path),
+    ) -> miette::Result<()> {
+        let mut blobs = vec![];
+        let mut result = ActionResult::default();
+
+        for file in output_files {
+            let abs_path = file.to_path(workspace_root);
+            let data = fs::read_file_bytes(&abs_path)?;
+            let digest = create_digest(&data);
+
+            result.output_files.push(OutputFile {
+                path: file.to_string(),
+                digest: Some(digest.clone()),
+                is_executable: is_executable(&abs_path),
+                contents: vec![],
+            });
+
+            blobs.push((digest, data));
+        }
+
+        for (index, log_path) in [log_paths.0, log_paths.1].into_iter().enumerate() {
+            if log_path.exists() {
+                let data = fs::read_file_bytes(log_path)?;
+                let digest = create_digest(&data);
+
+                if index == 0 {
+                    result.stdout_digest = Some(digest.clone());
+                } else {
+                    result.stderr_digest = Some(digest.clone());
+                }
+
+                blobs.push((digest, data));
+            }
+        }
+
+        // Only upload blobs that don't already exist in the CAS
+        let missing = self
+            .client
+            .find_missing_blobs(blobs.iter().map(|(digest, _)| digest.clone()).collect())
+            .await?
+            .into_iter()
+            .collect::<FxHashSet<_>>();
+        let mut uploaded = FxHashSet::default();
+
+        blobs.retain(|(digest, _)| missing.contains(digest) && uploaded.insert(digest.clone()));
+
+        debug!(
+            hash = &action_digest.hash,
+            files = output_files.len(),
+            missing = blobs.len(),
+            "Uploading outputs to remote cache"
+        );
+
+        self.client.upload_blobs(blobs).await?;
+        self.client
+            .update_action_result(action_digest, result)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Download all output files and logs referenced by the action result,
+    /// and write them into the workspace, replacing the current outputs.
+    /// Output files must be within the provided output paths.
+    pub async fn hydrate_action_result(
+        &self,
+        action_result: &ActionResult,
+        workspace_root: &Path,
+        output_paths: &[WorkspaceRelativePathBuf],
+        log_paths: (&Path, &Path),
+    ) -> miette::Result<()> {
+        let output_globs = GlobSet::new(
+            &output_paths
+                .iter()
+                .filter(|output| glob::is_glob(output))
+                .cloned()
+                .collect::<Vec<_>>(),
+        )?;
+
+        // Validate every path before touching the workspace
+        let mut files = vec![];
+
+        for file in &action_result.output_files {
+            files.push((
+                validate_output_path(&file.path, output_paths, &output_globs)?
+                    .to_path(workspace_root),
+                file,
+            ));
+        }
+
+        // Remove the current outputs first, so that stale files
+        // that are not part of the result don't survive hydration
+        for output in output_paths {
+            if glob::is_glob(output) {
+                for file in glob::walk_files(workspace_root, [output.as_str()])? {
+                    fs::remove_file(file)?;
+                }
+            } else {
+                fs::remove(output.to_path(workspace_root))?;
+            }
+        }
+
+        let mut blobs = vec![];
+        let mut executables = vec![];
+
+        for (dest_path, file) in files {
+            match &file.digest {
+                Some(digest) => {
+                    blobs.push((digest.clone(), dest_path.clone()));
+                }
+                // Contents may be inlined for small files
+                None => {
+                    fs::write_file(&dest_path, &file.contents)?;
+                }
+            };
+
+            if file.is_executable {
+                executables.push(dest_path);
+            }
+        }
+
+        for (digest, log_path) in [
+            (&action_result.stdout_digest, log_paths.0),
+            (&action_result.stderr_digest, log_paths.1),
+        ] {
+            if let Some(digest) = digest {
+                blobs.push((digest.clone(), log_path.to_path_buf()));
+            }
+        }
+
+        debug!(files = blobs.len(), "Downloading outputs from remote cache");
+
+        self.client.download_blobs(blobs).await?;
+
+        for dest_path in executables {
+            fs::update_perms(dest_path, Some(0o755))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,484 @@
+use crate::reapi_proto::*;
+use crate::remote_cache_error::RemoteCacheError;
+use miette::IntoDiagnostic;
+use moon_config::RemoteCacheReapiConfig;
+use sha2::{Digest as _, Sha256};
+use starbase_utils::fs;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process;
+use tokio::io::AsyncWriteExt;
+use tonic::client::Grpc;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::{Code, Request};
+use tracing::trace;
+
+// Servers typically limit gRPC messages to 4 MiB, so stay well below that
+// when batching blobs, and stream anything larger through ByteStream.
+const MAX_BATCH_SIZE: i64 = 2 * 1024 * 1024;
+const STREAM_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Create a SHA-256 digest for the provided blob.
+pub fn create_digest(data: &[u8]) -> Digest {
+    Digest {
+        hash: format!("{:x}", Sha256::digest(data)),
+        size_bytes: data.len() as i64,
+    }
+}
+
+/// Verify that a downloaded blob matches the digest it was requested with,
+/// as a misbehaving server may return any data.
+fn verify_blob(expected: &Digest, actual: Digest) -> Result<(), RemoteCacheError> {
+    if &actual != expected {
+        return Err(RemoteCacheError::BlobMismatch(expected.hash.clone()));
+    }
+
+    Ok(())
+}
+
+/// Return a temporary path next to the destination, that is unique to this process.
+fn get_temp_path(dest_path: &Path) -> PathBuf {
+    let mut path = dest_path.as_os_str().to_owned();
+    path.push(format!(".{}.tmp", process::id()));
+
+    PathBuf::from(path)
+}
+
+/// A gRPC client for the action cache and content addressable storage
+/// services of the Bazel Remote Execution API.
+#[derive(Clone, Debug)]
+pub struct ReapiClient {
+    channel: Channel,
+    instance_name: String,
+    metadata: MetadataMap,
+}
+
+impl ReapiClient {
+    pub fn new(config: &RemoteCacheReapiConfig) -> miette::Result<ReapiClient> {
+        // Allow the `grpc://` and `grpcs://` schemes used by Bazel
+        let host = if let Some(rest) = config.host.strip_prefix("grpcs://") {
+            format!("https://{rest}")
+        } else if let Some(rest) = config.host.strip_prefix("grpc://") {
+            format!("http://{rest}")
+        } else {
+            config.host.clone()
+        };
+
+        let mut endpoint = Endpoint::from_shared(host.clone()).into_diagnostic()?;
+
+        if host.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new())
+                .into_diagnostic()?;
+        }
+
+        let mut metadata = MetadataMap::new();
+
+        for (name, value) in &config.headers {
+            metadata.insert(
+                MetadataKey::from_bytes(name.to_lowercase().as_bytes()).into_diagnostic()?,
+                MetadataValue::try_from(value).into_diagnostic()?,
+            );
+        }
+
+        if let Some(var_name) = &config.token_env_var {
+            if let Ok(token) = env::var(var_name) {
+                metadata.insert(
+                    "authorization",
+                    MetadataValue::try_from(format!("Bearer {token}")).into_diagnostic()?,
+                );
+            }
+        }
+
+        Ok(ReapiClient {
+            // Connect on first request, so that an unreachable server
+            // does not fail the pipeline during setup
+            channel: endpoint.connect_lazy(),
+            instance_name: config.instance_name.clone(),
+            metadata,
+        })
+    }
+
+    pub async fn get_action_result(
+        &self,
+        action_digest: &Digest,
+    ) -> miette::Result<Option<ActionResult>> {
+        let result = self
+            .unary(
+                GET_ACTION_RESULT_PATH,
+                GetActionResultRequest {
+                    instance_name: self.instance_name.clone(),
+                    action_digest: Some(action_digest.to_owned()),
+                    inline_stdout: false,
+                    inline_stderr: false,
+                },
+            )
+            .await;
+
+        match result {
+            Ok(action_result) => Ok(Some(action_result)),
+            Err(status) if status.code() == Code::NotFound => Ok(None),
+            Err(status) => Err(RemoteCacheError::CheckFailure {
+                key: action_digest.hash.clone(),
+                message: status.message().to_owned(),
+            }
+            .into()),
+        }
+    }
+
+    pub async fn update_action_result(
+        &self,
+        action_digest: &Digest,
+        action_result: ActionResult,
+    ) -> miette::Result<()> {
+        let _: ActionResult = self
+            .unary(
+                UPDATE_ACTION_RESULT_PATH,
+                UpdateActionResultRequest {
+                    instance_name: self.instance_name.clone(),
+                    action_digest: Some(action_digest.to_owned()),
+                    action_result: Some(action_result),
+                },
+            )
+            .await
+            .map_err(|status| RemoteCacheError::UploadFailure {
+                key: action_digest.hash.clone(),
+                message: status.message().to_owned(),
+            })?;
+
+        Ok(())
+    }
+
+    pub async fn find_missing_blobs(&self, digests: Vec<Digest>) -> miette::Result<Vec<Digest>> {
+        let response: FindMissingBlobsResponse = self
+            .unary(
+                FIND_MISSING_BLOBS_PATH,
+                FindMissingBlobsRequest {
+                    instance_name: self.instance_name.clone(),
+                    blob_digests: digests,
+                },
+            )
+            .await
+            .map_err(|status| RemoteCacheError::CheckFailure {
+                key: "blobs".into(),
+                message: status.message().to_owned(),
+            })?;
+
+        Ok(response.missing_blob_digests)
+    }
+
+    /// Upload the provided blobs to the CAS. Small blobs are batched together,
+    /// while large blobs are streamed individually.
+    pub async fn upload_blobs(&self, blobs: Vec<(Digest, Vec<u8>)>) -> miette::Result<()> {
+        let mut batch = vec![];
+        let mut batch_size = 0;
+
+        for (digest, data) in blobs {
+            if digest.size_bytes > MAX_BATCH_SIZE {
+                self.write_blob(&digest, data).await?;
+                continue;
+            }
+
+            if batch_size + digest.size_bytes > MAX_BATCH_SIZE {
+                self.batch_update_blobs(std::mem::take(&mut batch)).await?;
+                batch_size = 0;
+            }
+
+            batch_size += digest.size_bytes;
+            batch.push(BatchUpdateBlobsRequestEntry {
+                digest: Some(digest),
+                data,
+            });
+        }
+
+        if !batch.is_empty() {
+            self.batch_update_blobs(batch).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Download the provided blobs from the CAS, and write each to its destination
+    /// path. Blobs are verified against their digest before being written, and
+    /// a mismatch fails the download.
+    pub async fn download_blobs(&self, blobs: Vec<(Digest, PathBuf)>) -> miette::Result<()> {
+        let mut batch = vec![];
+        let mut batch_size = 0;
+
+        for (digest, dest_path) in blobs {
+            if digest.size_bytes > MAX_BATCH_SIZE {
+                self.read_blob(&digest, &dest_path).await?;
+                continue;
+            }
+
+            if batch_size + digest.size_bytes > MAX_BATCH_SIZE {
+                self.batch_read_blobs(std::mem::take(&mut batch)).await?;
+                batch_size = 0;
+            }
+
+            batch_size += digest.size_bytes;
+            batch.push((digest, dest_path));
+        }
+
+        if !batch.is_empty() {
+            self.batch_read_blobs(batch).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn batch_update_blobs(
+        &self,
+        requests: Vec<BatchUpdateBlobsRequestEntry>,
+    ) -> miette::Result<()> {
+        trace!(count = requests.len(), "Uploading batch of blobs");
+
+        let response: BatchUpdateBlobsResponse = self
+            .unary(
+                BATCH_UPDATE_BLOBS_PATH,
+                BatchUpdateBlobsRequest {
+                    instance_name: self.instance_name.clone(),
+                    requests,
+                },
+            )
+            .await
+            .map_err(|status| RemoteCacheError::UploadFailure {
+                key: "blobs".into(),
+                message: status.message().to_owned(),
+            })?;
+
+        for entry in response.responses {
+            if let Some(status) = entry.status {
+                if status.code != Code::Ok as i32 {
+                    return Err(RemoteCacheError::UploadFailure {
+                        key: entry.digest.map(|d| d.hash).unwrap_or_default(),
+                        message: status.message,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn batch_read_blobs(&self, blobs: Vec<(Digest, PathBuf)>) -> miette::Result<()> {
+        trace!(count = blobs.len(), "Downloading batch of blobs");
+
+        let response: BatchReadBlobsResponse = self
+            .unary(
+                BATCH_READ_BLOBS_PATH,
+                BatchReadBlobsRequest {
+                    instance_name: self.instance_name.clone(),
+                    digests: blobs.iter().map(|(digest, _)| digest.clone()).collect(),
+                },
+            )
+            .await
+            .map_err(|status| RemoteCacheError::DownloadFailure {
+                key: "blobs".into(),
+                message: status.message().to_owned(),
+            })?;
+
+        // Responses are not guaranteed to be in the same order as the requests
+        for (digest, dest_path) in blobs {
+            let entry = response
+                .responses
+                .iter()
+                .find(|entry| entry.digest.as_ref() == Some(&digest));
+
+            match entry {
+                Some(entry)
+                    if entry
+                        .status
+                        .as_ref()
+                        .map_or(true, |status| status.code == Code::Ok as i32) =>
+                {
+                    verify_blob(&digest, create_digest(&entry.data))?;
+
+                    fs::write_file(&dest_path, &entry.data)?;
+                }
+                _ => {
+                    return Err(RemoteCacheError::DownloadFailure {
+                        key: digest.hash,
+                        message: "blob missing from response".into(),
+                    }
+                    .into());
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Stream a large blob to a temporary file, while hashing it, so that it's
+    /// never buffered in memory, and only move it into place once verified.
+    async fn read_blob(&self, digest: &Digest, dest_path: &Path) -> miette::Result<()> {
+        let map_error = |status: tonic::Status| RemoteCacheError::DownloadFailure {
+            key: digest.hash.clone(),
+            message: status.message().to_owned(),
+        };
+
+        trace!(hash = &digest.hash, "Streaming blob from server");
+
+        let mut grpc = self.create_grpc().await.map_err(map_error)?;
+        let mut stream = grpc
+            .server_streaming(
+                self.create_request(ReadRequest {
+                    resource_name: format!(
+                        "{}blobs/{}/{}",
+                        self.get_resource_prefix(),
+                        digest.hash,
+                        digest.size_bytes
+                    ),
+                    read_offset: 0,
+                    read_limit: 0,
+                }),
+                PathAndQuery::from_static(BYTESTREAM_READ_PATH),
+                ProstCodec::<ReadRequest, ReadResponse>::default(),
+            )
+            .await
+            .map_err(map_error)?
+            .into_inner();
+
+        let temp_path = get_temp_path(dest_path);
+        let mut file = tokio::fs::File::from_std(fs::create_file(&temp_path)?);
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+
+        let result: miette::Result<()> = async {
+            while let Some(response) = stream.message().await.map_err(map_error)? {
+                hasher.update(&response.data);
+                size += response.data.len() as i64;
+
+                file.write_all(&response.data).await.into_diagnostic()?;
+            }
+
+            file.flush().await.into_diagnostic()?;
+
+            verify_blob(
+                digest,
+                Digest {
+                    hash: format!("{:x}", hasher.finalize()),
+                    size_bytes: size,
+                },
+            )?;
+
+            Ok(())
+        }
+        .await;
+
+        drop(file);
+
+        if let Err(error) = result {
+            let _ = fs::remove_file(&temp_path);
+
+            return Err(error);
+        }
+
+        fs::rename(&temp_path, dest_path)?;
+
+        Ok(())
+    }
+
+    async fn write_blob(&self, digest: &Digest, data: Vec<u8>) -> miette::Result<()> {
+        let map_error = |status: tonic::Status| RemoteCacheError::UploadFailure {
+            key: digest.hash.clone(),
+            message: status.message().to_owned(),
+        };
+
+        trace!(hash = &digest.hash, "Streaming blob to server");
+
+        let resource_name = format!(
+            "{}uploads/{}/blobs/{}/{}",
+            self.get_resource_prefix(),
+            uuid::Uuid::new_v4(),
+            digest.hash,
+            digest.size_bytes
+        );
+        let chunk_count = data.chunks(STREAM_CHUNK_SIZE).len();
+        let requests = data
+            .chunks(STREAM_CHUNK_SIZE)
+            .enumerate()
+            .map(|(index, chunk)| WriteRequest {
+                // Only the first request requires the resource name
+                resource_name: if index == 0 {
+                    resource_name.clone()
+                } else {
+                    String::new()
+                },
+                write_offset: (index * STREAM_CHUNK_SIZE) as i64,
+                finish_write: index + 1 == chunk_count,
+                data: chunk.to_vec(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut grpc = self.create_grpc().await.map_err(map_error)?;
+        let response = grpc
+            .client_streaming(
+                self.create_request(tokio_stream::iter(requests)),
+                PathAndQuery::from_static(BYTESTREAM_WRITE_PATH),
+                ProstCodec::<WriteRequest, WriteResponse>::default(),
+            )
+            .await
+            .map_err(map_error)?
+            .into_inner();
+
+        if response.committed_size != digest.size_bytes {
+            return Err(RemoteCacheError::UploadFailure {
+                key: digest.hash.clone(),
+                message: format!(
+                    "server committed {} of {} bytes",
+                    response.committed_size, digest.size_bytes
+                ),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    async fn create_grpc(&self) -> Result<Grpc<Channel>, tonic::Status> {
+        let mut grpc = Grpc::new(self.channel.clone());
+
+        grpc.ready()
+            .await
+            .map_err(|error| tonic::Status::unavailable(error.to_string()))?;
+
+        Ok(grpc)
+    }
+
+    fn create_request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        *request.metadata_mut() = self.metadata.clone();
+        request
+    }
+
+    fn get_resource_prefix(&self) -> String {
+        if self.instance_name.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", self.instance_name)
+        }
+    }
+
+    async fn unary<Req, Res>(&self, path: &'static str, message: Req) -> Result<Res, tonic::Status>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Res: prost::Message + Default + Send + Sync + 'static,
+    {
+        let mut grpc = self.create_grpc().await?;
+
+        let response = grpc
+            .unary(
+                self.create_request(message),
+                PathAndQuery::from_static(path),
+                ProstCodec::<Req, Res>::default(),
+            )
+            .await?;
+
+        Ok(response.into_inner())
+    }
+}
@@ -0,0 +1,189 @@
+// A subset of the Bazel Remote Execution API (v2) and ByteStream protobuf
+// messages, as required for remote caching. Field tags must match the
+// upstream definitions exactly:
+// https://github.com/bazelbuild/remote-apis/blob/main/build/bazel/remote/execution/v2/remote_execution.proto
+// https://github.com/googleapis/googleapis/blob/master/google/bytestream/bytestream.proto
+
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+pub const GET_ACTION_RESULT_PATH: &str =
+    "/build.bazel.remote.execution.v2.ActionCache/GetActionResult";
+pub const UPDATE_ACTION_RESULT_PATH: &str =
+    "/build.bazel.remote.execution.v2.ActionCache/UpdateActionResult";
+pub const FIND_MISSING_BLOBS_PATH: &str =
+    "/build.bazel.remote.execution.v2.ContentAddressableStorage/FindMissingBlobs";
+pub const BATCH_UPDATE_BLOBS_PATH: &str =
+    "/build.bazel.remote.execution.v2.ContentAddressableStorage/BatchUpdateBlobs";
+pub const BATCH_READ_BLOBS_PATH: &str =
+    "/build.bazel.remote.execution.v2.ContentAddressableStorage/BatchReadBlobs";
+pub const BYTESTREAM_READ_PATH: &str = "/google.bytestream.ByteStream/Read";
+pub const BYTESTREAM_WRITE_PATH: &str = "/google.bytestream.ByteStream/Write";
+
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Digest {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+    #[prost(int64, tag = "2")]
+    pub size_bytes: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OutputFile {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(message, optional, tag = "2")]
+    pub digest: Option<Digest>,
+    #[prost(bool, tag = "4")]
+    pub is_executable: bool,
+    #[prost(bytes = "vec", tag = "5")]
+    pub contents: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ActionResult {
+    #[prost(message, repeated, tag = "2")]
+    pub output_files: Vec<OutputFile>,
+    #[prost(int32, tag = "4")]
+    pub exit_code: i32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub stdout_raw: Vec<u8>,
+    #[prost(message, optional, tag = "6")]
+    pub stdout_digest: Option<Digest>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub stderr_raw: Vec<u8>,
+    #[prost(message, optional, tag = "8")]
+    pub stderr_digest: Option<Digest>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetActionResultRequest {
+    #[prost(string, tag = "1")]
+    pub instance_name: String,
+    #[prost(message, optional, tag = "2")]
+    pub action_digest: Option<Digest>,
+    #[prost(bool, tag = "3")]
+    pub inline_stdout: bool,
+    #[prost(bool, tag = "4")]
+    pub inline_stderr: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct UpdateActionResultRequest {
+    #[prost(string, tag = "1")]
+    pub instance_name: String,
+    #[prost(message, optional, tag = "2")]
+    pub action_digest: Option<Digest>,
+    #[prost(message, optional, tag = "3")]
+    pub action_result: Option<ActionResult>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FindMissingBlobsRequest {
+    #[prost(string, tag = "1")]
+    pub instance_name: String,
+    #[prost(message, repeated, tag = "2")]
+    pub blob_digests: Vec<Digest>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FindMissingBlobsResponse {
+    #[prost(message, repeated, tag = "2")]
+    pub missing_blob_digests: Vec<Digest>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchUpdateBlobsRequestEntry {
+    #[prost(message, optional, tag = "1")]
+    pub digest: Option<Digest>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchUpdateBlobsRequest {
+    #[prost(string, tag = "1")]
+    pub instance_name: String,
+    #[prost(message, repeated, tag = "2")]
+    pub requests: Vec<BatchUpdateBlobsRequestEntry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchUpdateBlobsResponseEntry {
+    #[prost(message, optional, tag = "1")]
+    pub digest: Option<Digest>,
+    #[prost(message, optional, tag = "2")]
+    pub status: Option<Status>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchUpdateBlobsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub responses: Vec<BatchUpdateBlobsResponseEntry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchReadBlobsRequest {
+    #[prost(string, tag = "1")]
+    pub instance_name: String,
+    #[prost(message, repeated, tag = "2")]
+    pub digests: Vec<Digest>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchReadBlobsResponseEntry {
+    #[prost(message, optional, tag = "1")]
+    pub digest: Option<Digest>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub status: Option<Status>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchReadBlobsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub responses: Vec<BatchReadBlobsResponseEntry>,
+}
+
+/// The `google.rpc.Status` message, without details.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Status {
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ReadRequest {
+    #[prost(string, tag = "1")]
+    pub resource_name: String,
+    #[prost(int64, tag = "2")]
+    pub read_offset: i64,
+    #[prost(int64, tag = "3")]
+    pub read_limit: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ReadResponse {
+    #[prost(bytes = "vec", tag = "10")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WriteRequest {
+    #[prost(string, tag = "1")]
+    pub resource_name: String,
+    #[prost(int64, tag = "2")]
+    pub write_offset: i64,
+    #[prost(bool, tag = "3")]
+    pub finish_write: bool,
+    #[prost(bytes = "vec", tag = "10")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WriteResponse {
+    #[prost(int64, tag = "1")]
+    pub committed_size: i64,
+}
@@ -0,0 +1,81 @@
+use crate::remote_cache_error::RemoteCacheError;
+use async_trait::async_trait;
+use miette::IntoDiagnostic;
+use starbase_utils::fs;
+use std::fmt::Debug;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// A storage backend for sharing task output archives and hash
+/// manifests between machines. Entries are addressed by a key, which
+/// is the file name of the entry within the local cache directory.
+#[async_trait]
+pub trait RemoteCache: Debug + Send + Sync {
+    /// Return a name for the backend, used in logs.
+    fn get_name(&self) -> &str;
+
+    /// Return true if an entry exists for the provided key.
+    async fn exists(&self, key: &str) -> miette::Result<bool>;
+
+    /// Download the entry for the provided key to the destination path.
+    async fn download(&self, key: &str, dest_path: &Path) -> miette::Result<()>;
+
+    /// Upload the file at the source path as the entry for the provided key.
+    async fn upload(&self, key: &str, src_path: &Path) -> miette::Result<()>;
+}
+
+pub type BoxedRemoteCache = Arc<dyn RemoteCache>;
+
+/// Return the key of the outputs archive for the provided hash.
+pub fn get_archive_key(hash: &str) -> String {
+    format!("{hash}.tar.gz")
+}
+
+/// Return the key of the hash manifest for the provided hash.
+pub fn get_manifest_key(hash: &str) -> String {
+    format!("{hash}.json")
+}
+
+/// Return the key of the archive digest for the provided hash.
+pub fn get_digest_key(hash: &str) -> String {
+    format!("{hash}.digest.json")
+}
+
+/// Stream the body of a response to the destination path in chunks, so that
+/// large archives are never buffered in memory. A partially written file is
+/// removed if the response fails mid-stream, or the file can't be written.
+pub(crate) async fn stream_response_to_file<E: Fn(String) -> RemoteCacheError>(
+    response: reqwest::Response,
+    dest_path: &Path,
+    map_error: E,
+) -> miette::Result<()> {
+    let result = write_response_to_file(response, dest_path, map_error).await;
+
+    if result.is_err() {
+        // Don't replace the original error with a failure to clean up
+        let _ = fs::remove_file(dest_path);
+    }
+
+    result
+}
+
+async fn write_response_to_file<E: Fn(String) -> RemoteCacheError>(
+    mut response: reqwest::Response,
+    dest_path: &Path,
+    map_error: E,
+) -> miette::Result<()> {
+    let mut file = tokio::fs::File::from_std(fs::create_file(dest_path)?);
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|error| map_error(error.to_string()))?
+    {
+        file.write_all(&chunk).await.into_diagnostic()?;
+    }
+
+    file.flush().await.into_diagnostic()?;
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use miette::Diagnostic;
+use moon_common::{Style, Stylize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum RemoteCacheError {
+    #[diagnostic(code(remote_cache::missing_directory))]
+    #[error("Remote cache directory {} does not exist.", .0.style(Style::Path))]
+    MissingDirectory(PathBuf),
+
+    #[diagnostic(
+        code(remote_cache::s3::missing_credentials),
+        help = "Set the environment variable, or configure a different variable name."
+    )]
+    #[error("Missing S3 credentials, {} environment variable not set.", .0.style(Style::Symbol))]
+    MissingS3Credentials(String),
+
+    #[diagnostic(code(remote_cache::reapi::missing_manifest))]
+    #[error(
+        "Unable to create an action digest for {}, as its hash manifest does not exist.",
+        .0.style(Style::Hash),
+    )]
+    MissingManifest(String),
+
+    #[diagnostic(code(remote_cache::reapi::manifest_mismatch))]
+    #[error(
+        "Unable to create an action digest for {}, as its hash manifest does not match the hash.",
+        .0.style(Style::Hash),
+    )]
+    ManifestMismatch(String),
+
+    #[diagnostic(code(remote_cache::reapi::blob_mismatch))]
+    #[error(
+        "Blob {} downloaded from remote cache does not match its digest.",
+        .0.style(Style::Hash),
+    )]
+    BlobMismatch(String),
+
+    #[diagnostic(code(remote_cache::reapi::invalid_output_path))]
+    #[error(
+        "Remote cache returned output {}, which is not within the task's outputs, refusing to hydrate it.",
+        .0.style(Style::File),
+    )]
+    InvalidOutputPath(String),
+
+    #[diagnostic(code(remote_cache::check_failed))]
+    #[error("Failed to check for {} in remote cache: {message}", .key.style(Style::File))]
+    CheckFailure { key: String, message: String },
+
+    #[diagnostic(code(remote_cache::download_failed))]
+    #[error("Failed to download {} from remote cache: {message}", .key.style(Style::File))]
+    DownloadFailure { key: String, message: String },
+
+    #[diagnostic(code(remote_cache::upload_failed))]
+    #[error("Failed to upload {} to remote cache: {message}", .key.style(Style::File))]
+    UploadFailure { key: String, message: String },
+}
@@ -0,0 +1,415 @@
+use crate::remote_cache::{stream_response_to_file, RemoteCache};
+use crate::remote_cache_error::RemoteCacheError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use miette::IntoDiagnostic;
+use moon_config::RemoteCacheS3Config;
+use reqwest::{Client, Method, RequestBuilder, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::path::Path;
+use tokio_util::codec::{BytesCodec, FramedRead};
+use tracing::trace;
+
+// Payloads are streamed, so we can't hash them up front
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html
+fn encode_uri_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                encoded.push_str(&format!("%{byte:02X}"));
+            }
+        };
+    }
+
+    encoded
+}
+
+// https://docs.aws.amazon.com/IAM/latest/UserGuide/create-signed-request.html
+// Headers must be lowercased and sorted by name.
+fn create_canonical_request(
+    method: &str,
+    path: &str,
+    headers: &[(&str, String)],
+    payload_hash: &str,
+) -> String {
+    let canonical_headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect::<String>();
+
+    format!(
+        "{method}\n{path}\n\n{canonical_headers}\n{}\n{payload_hash}",
+        get_signed_headers(headers)
+    )
+}
+
+fn create_string_to_sign(timestamp: &str, scope: &str, canonical_request: &str) -> String {
+    format!(
+        "AWS4-HMAC-SHA256\n{timestamp}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    )
+}
+
+fn create_signature(
+    secret_access_key: &str,
+    scope_parts: [&str; 3],
+    string_to_sign: &str,
+) -> String {
+    let mut signing_key = format!("AWS4{secret_access_key}").into_bytes();
+
+    for part in scope_parts.into_iter().chain(["aws4_request"]) {
+        signing_key = hmac_sha256(&signing_key, part);
+    }
+
+    hex(&hmac_sha256(&signing_key, string_to_sign))
+}
+
+fn get_signed_headers(headers: &[(&str, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[derive(Debug)]
+struct S3Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+/// A remote cache backed by an S3 compatible bucket (AWS S3, MinIO, R2, etc),
+/// where entries are stored as objects at `<prefix>/<key>`. Requests are
+/// signed with AWS signature version 4.
+#[derive(Debug)]
+pub struct S3RemoteCache {
+    client: Client,
+    credentials: S3Credentials,
+    prefix: String,
+    region: String,
+    url: Url,
+}
+
+impl S3RemoteCache {
+    pub fn new(config: &RemoteCacheS3Config) -> miette::Result<S3RemoteCache> {
+        let load_var = |name: &str| {
+            env::var(name).map_err(|_| RemoteCacheError::MissingS3Credentials(name.to_owned()))
+        };
+
+        let credentials = S3Credentials {
+            access_key_id: load_var(&config.access_key_id_env_var)?,
+            secret_access_key: load_var(&config.secret_access_key_env_var)?,
+            session_token: env::var(&config.session_token_env_var).ok(),
+        };
+
+        // Custom endpoints (like MinIO) use path-style addressing,
+        // while AWS uses virtual-hosted-style addressing
+        let url = match &config.endpoint {
+            Some(endpoint) => format!("{}/{}/", endpoint.trim_end_matches('/'), config.bucket),
+            None => format!(
+                "https://{}.s3.{}.amazonaws.com/",
+                config.bucket, config.region
+            ),
+        };
+
+        Ok(S3RemoteCache {
+            client: Client::builder().build().into_diagnostic()?,
+            credentials,
+            prefix: config.prefix.trim_matches('/').to_owned(),
+            region: config.region.clone(),
+            url: Url::parse(&url).into_diagnostic()?,
+        })
+    }
+
+    fn get_url(&self, key: &str) -> miette::Result<Url> {
+        let path = if self.prefix.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}/{key}", self.prefix)
+        };
+
+        self.url.join(&encode_uri_path(&path)).into_diagnostic()
+    }
+
+    fn create_request(&self, method: Method, key: &str) -> miette::Result<RequestBuilder> {
+        let url = self.get_url(key)?;
+        let request = self.client.request(method.clone(), url.clone());
+
+        Ok(self.sign_request(request, &method, &url, Utc::now()))
+    }
+
+    fn sign_request(
+        &self,
+        request: RequestBuilder,
+        method: &Method,
+        url: &Url,
+        now: DateTime<Utc>,
+    ) -> RequestBuilder {
+        let date = now.format("%Y%m%d").to_string();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+
+        let mut host = url.host_str().unwrap_or_default().to_owned();
+
+        if let Some(port) = url.port() {
+            host.push_str(&format!(":{port}"));
+        }
+
+        // Headers must be sorted by name
+        let mut headers = vec![
+            ("host", host),
+            ("x-amz-content-sha256", UNSIGNED_PAYLOAD.to_owned()),
+            ("x-amz-date", timestamp.clone()),
+        ];
+
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.to_owned()));
+        }
+
+        let signed_headers = get_signed_headers(&headers);
+        let canonical_request =
+            create_canonical_request(method.as_str(), url.path(), &headers, UNSIGNED_PAYLOAD);
+        let string_to_sign = create_string_to_sign(&timestamp, &scope, &canonical_request);
+        let signature = create_signature(
+            &self.credentials.secret_access_key,
+            [&date, &self.region, "s3"],
+            &string_to_sign,
+        );
+
+        let mut request = request.header(
+            "Authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                self.credentials.access_key_id
+            ),
+        );
+
+        // The host header is set by the client
+        for (name, value) in headers.into_iter().skip(1) {
+            request = request.header(name, value);
+        }
+
+        request
+    }
+}
+
+#[async_trait]
+impl RemoteCache for S3RemoteCache {
+    fn get_name(&self) -> &str {
+        "s3"
+    }
+
+    async fn exists(&self, key: &str) -> miette::Result<bool> {
+        let response = self
+            .create_request(Method::HEAD, key)?
+            .send()
+            .await
+            .map_err(|error| RemoteCacheError::CheckFailure {
+                key: key.to_owned(),
+                message: error.to_string(),
+            })?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            status => Err(RemoteCacheError::CheckFailure {
+                key: key.to_owned(),
+                message: status.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    async fn download(&self, key: &str, dest_path: &Path) -> miette::Result<()> {
+        trace!(dest = ?dest_path, "Downloading {} from S3 bucket", key);
+
+        let map_error = |message: String| RemoteCacheError::DownloadFailure {
+            key: key.to_owned(),
+            message,
+        };
+
+        let response = self
+            .create_request(Method::GET, key)?
+            .send()
+            .await
+            .map_err(|error| map_error(error.to_string()))?;
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(map_error(status.to_string()).into());
+        }
+
+        stream_response_to_file(response, dest_path, map_error).await
+    }
+
+    async fn upload(&self, key: &str, src_path: &Path) -> miette::Result<()> {
+        trace!(src = ?src_path, "Uploading {} to S3 bucket", key);
+
+        let map_error = |message: String| RemoteCacheError::UploadFailure {
+            key: key.to_owned(),
+            message,
+        };
+
+        let file = tokio::fs::File::open(src_path).await.into_diagnostic()?;
+        let file_length = file
+            .metadata()
+            .await
+            .map(|meta| meta.len())
+            .unwrap_or_default();
+        let file_stream = FramedRead::new(file, BytesCodec::new());
+
+        let response = self
+            .create_request(Method::PUT, key)?
+            .header("Content-Length", file_length)
+            .body(reqwest::Body::wrap_stream(file_stream))
+            .send()
+            .await
+            .map_err(|error| map_error(error.to_string()))?;
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(map_error(status.to_string()).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html
+    mod s3_get_object {
+        use super::*;
+
+        const EMPTY_PAYLOAD: &str =
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        fn create_headers() -> Vec<(&'static str, String)> {
+            vec![
+                ("host", "examplebucket.s3.amazonaws.com".into()),
+                ("range", "bytes=0-9".into()),
+                ("x-amz-content-sha256", EMPTY_PAYLOAD.into()),
+                ("x-amz-date", "20130524T000000Z".into()),
+            ]
+        }
+
+        fn create_canonical() -> String {
+            create_canonical_request("GET", "/test.txt", &create_headers(), EMPTY_PAYLOAD)
+        }
+
+        #[test]
+        fn canonical_request() {
+            assert_eq!(
+                create_canonical(),
+                "GET\n/test.txt\n\nhost:examplebucket.s3.amazonaws.com\nrange:bytes=0-9\nx-amz-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\nx-amz-date:20130524T000000Z\n\nhost;range;x-amz-content-sha256;x-amz-date\ne3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            );
+        }
+
+        #[test]
+        fn string_to_sign() {
+            assert_eq!(
+                create_string_to_sign(
+                    "20130524T000000Z",
+                    "20130524/us-east-1/s3/aws4_request",
+                    &create_canonical()
+                ),
+                "AWS4-HMAC-SHA256\n20130524T000000Z\n20130524/us-east-1/s3/aws4_request\n7344ae5b7ee6c3e7e6b0fe0640412a37625d1fbfff95c48bbb2dc43964946972"
+            );
+        }
+
+        #[test]
+        fn signature() {
+            let string_to_sign = create_string_to_sign(
+                "20130524T000000Z",
+                "20130524/us-east-1/s3/aws4_request",
+                &create_canonical(),
+            );
+
+            assert_eq!(
+                create_signature(
+                    "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY",
+                    ["20130524", "us-east-1", "s3"],
+                    &string_to_sign
+                ),
+                "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41"
+            );
+        }
+    }
+
+    // https://github.com/awslabs/aws-c-auth/tree/main/tests/aws-signing-test-suite/v4/get-vanilla
+    mod get_vanilla {
+        use super::*;
+
+        fn create_canonical() -> String {
+            create_canonical_request(
+                "GET",
+                "/",
+                &[
+                    ("host", "example.amazonaws.com".into()),
+                    ("x-amz-date", "20150830T123600Z".into()),
+                ],
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            )
+        }
+
+        #[test]
+        fn canonical_request() {
+            assert_eq!(
+                create_canonical(),
+                "GET\n/\n\nhost:example.amazonaws.com\nx-amz-date:20150830T123600Z\n\nhost;x-amz-date\ne3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            );
+        }
+
+        #[test]
+        fn string_to_sign() {
+            assert_eq!(
+                create_string_to_sign(
+                    "20150830T123600Z",
+                    "20150830/us-east-1/service/aws4_request",
+                    &create_canonical()
+                ),
+                "AWS4-HMAC-SHA256\n20150830T123600Z\n20150830/us-east-1/service/aws4_request\nbb579772317eb040ac9ed261061d46c1f17a8133879d6129b6e1c25292927e63"
+            );
+        }
+
+        #[test]
+        fn signature() {
+            let string_to_sign = create_string_to_sign(
+                "20150830T123600Z",
+                "20150830/us-east-1/service/aws4_request",
+                &create_canonical(),
+            );
+
+            assert_eq!(
+                create_signature(
+                    "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                    ["20150830", "us-east-1", "service"],
+                    &string_to_sign
+                ),
+                "5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+            );
+        }
+    }
+}
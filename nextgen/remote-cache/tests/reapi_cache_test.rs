@@ -0,0 +1,485 @@
+mod utils;
+
+use moon_common::path::WorkspaceRelativePathBuf;
+use moon_config::RemoteCacheReapiConfig;
+use moon_remote_cache::*;
+use starbase_sandbox::create_empty_sandbox;
+use std::fs;
+use std::path::Path;
+use utils::StandInServer;
+
+fn create_cache(host: String) -> ReapiRemoteCache {
+    ReapiRemoteCache::new(&RemoteCacheReapiConfig {
+        host,
+        instance_name: "main".into(),
+        ..RemoteCacheReapiConfig::default()
+    })
+    .unwrap()
+}
+
+fn create_hash_digest() -> reapi_proto::Digest {
+    reapi_proto::Digest {
+        hash: "4bc8e03ac49b42b1b2e0d21ef7e98ac5ab4da06b5fd2fb3fc9ecc7e57a8e5e56".into(),
+        size_bytes: 123,
+    }
+}
+
+fn log_paths(root: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    (root.join("state/stdout.log"), root.join("state/stderr.log"))
+}
+
+mod collect_output_files {
+    use super::*;
+
+    #[test]
+    fn expands_dirs_and_globs() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("app/dist/index.js", "");
+        sandbox.create_file("app/dist/nested/util.js", "");
+        sandbox.create_file("app/build/a.d.ts", "");
+        sandbox.create_file("app/build/b.js", "");
+        sandbox.create_file("app/file.txt", "");
+
+        let files = collect_output_files(
+            sandbox.path(),
+            &[
+                WorkspaceRelativePathBuf::from("app/dist"),
+                WorkspaceRelativePathBuf::from("app/build/*.d.ts"),
+                WorkspaceRelativePathBuf::from("app/file.txt"),
+                WorkspaceRelativePathBuf::from("app/missing.txt"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                WorkspaceRelativePathBuf::from("app/build/a.d.ts"),
+                WorkspaceRelativePathBuf::from("app/dist/index.js"),
+                WorkspaceRelativePathBuf::from("app/dist/nested/util.js"),
+                WorkspaceRelativePathBuf::from("app/file.txt"),
+            ]
+        );
+    }
+}
+
+mod create_action_digest {
+    use super::*;
+
+    #[test]
+    fn uses_the_manifest_size() {
+        let expected = create_digest(b"[\"content\"]");
+        let digest =
+            create_action_digest(&expected.hash, Some(b"[\"content\"]".as_slice())).unwrap();
+
+        assert_eq!(digest, expected);
+        assert_eq!(digest.size_bytes, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "hash manifest does not exist")]
+    fn errors_if_manifest_missing() {
+        create_action_digest("abc", None).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "hash manifest does not match the hash")]
+    fn errors_if_manifest_doesnt_match() {
+        create_action_digest("abc", Some(b"[]".as_slice())).unwrap();
+    }
+}
+
+mod reapi_cache {
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_none_if_missing() {
+        let server = StandInServer::default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+
+        assert!(cache
+            .read_action_result(&create_hash_digest())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Failed to check for")]
+    async fn errors_if_unreachable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        create_cache(format!("http://{addr}"))
+            .read_action_result(&create_hash_digest())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn writes_and_hydrates_outputs() {
+        let server = StandInServer::default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+
+        let source = create_empty_sandbox();
+        source.create_file("app/dist/index.js", "index");
+        source.create_file("app/dist/nested/util.js", "util");
+        source.create_file("state/stdout.log", "out");
+        source.create_file("state/stderr.log", "err");
+
+        let (stdout, stderr) = log_paths(source.path());
+        let files =
+            collect_output_files(source.path(), &[WorkspaceRelativePathBuf::from("app/dist")])
+                .unwrap();
+
+        cache
+            .write_action_result(
+                &create_hash_digest(),
+                source.path(),
+                &files,
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        // 2 outputs + 2 logs
+        assert_eq!(server.blobs.lock().unwrap().len(), 4);
+
+        let result = cache
+            .read_action_result(&create_hash_digest())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.output_files.len(), 2);
+        assert_eq!(result.output_files[0].path, "app/dist/index.js");
+
+        let dest = create_empty_sandbox();
+        let (stdout, stderr) = log_paths(dest.path());
+
+        cache
+            .hydrate_action_result(
+                &result,
+                dest.path(),
+                &[WorkspaceRelativePathBuf::from("app/dist")],
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dest.path().join("app/dist/index.js")).unwrap(),
+            "index"
+        );
+        assert_eq!(
+            fs::read_to_string(dest.path().join("app/dist/nested/util.js")).unwrap(),
+            "util"
+        );
+        assert_eq!(fs::read_to_string(stdout).unwrap(), "out");
+        assert_eq!(fs::read_to_string(stderr).unwrap(), "err");
+    }
+
+    #[tokio::test]
+    async fn removes_stale_outputs_when_hydrating() {
+        let server = StandInServer::default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+
+        let source = create_empty_sandbox();
+        source.create_file("app/dist/index.js", "index");
+
+        let (stdout, stderr) = log_paths(source.path());
+        let outputs = [
+            WorkspaceRelativePathBuf::from("app/dist"),
+            WorkspaceRelativePathBuf::from("app/*.d.ts"),
+        ];
+        let files = collect_output_files(source.path(), &outputs).unwrap();
+
+        cache
+            .write_action_result(
+                &create_hash_digest(),
+                source.path(),
+                &files,
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        let result = cache
+            .read_action_result(&create_hash_digest())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let dest = create_empty_sandbox();
+        dest.create_file("app/dist/stale.js", "stale");
+        dest.create_file("app/stale.d.ts", "stale");
+        dest.create_file("app/source.ts", "source");
+
+        let (stdout, stderr) = log_paths(dest.path());
+
+        cache
+            .hydrate_action_result(&result, dest.path(), &outputs, (&stdout, &stderr))
+            .await
+            .unwrap();
+
+        assert!(dest.path().join("app/dist/index.js").exists());
+        assert!(!dest.path().join("app/dist/stale.js").exists());
+        assert!(!dest.path().join("app/stale.d.ts").exists());
+        assert!(dest.path().join("app/source.ts").exists());
+    }
+
+    #[tokio::test]
+    async fn dedupes_identical_blobs() {
+        let server = StandInServer::default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+
+        let source = create_empty_sandbox();
+        source.create_file("a.txt", "same");
+        source.create_file("b.txt", "same");
+
+        let (stdout, stderr) = log_paths(source.path());
+
+        cache
+            .write_action_result(
+                &create_hash_digest(),
+                source.path(),
+                &[
+                    WorkspaceRelativePathBuf::from("a.txt"),
+                    WorkspaceRelativePathBuf::from("b.txt"),
+                ],
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(server.blobs.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn streams_large_blobs() {
+        let server = StandInServer::default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+
+        let large = "x".repeat(3 * 1024 * 1024);
+        let source = create_empty_sandbox();
+        source.create_file("large.bin", &large);
+        source.create_file("small.bin", "small");
+
+        let (stdout, stderr) = log_paths(source.path());
+
+        cache
+            .write_action_result(
+                &create_hash_digest(),
+                source.path(),
+                &[
+                    WorkspaceRelativePathBuf::from("large.bin"),
+                    WorkspaceRelativePathBuf::from("small.bin"),
+                ],
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(*server.streamed_writes.lock().unwrap(), 1);
+
+        let result = cache
+            .read_action_result(&create_hash_digest())
+            .await
+            .unwrap()
+            .unwrap();
+        let dest = create_empty_sandbox();
+        let (stdout, stderr) = log_paths(dest.path());
+
+        cache
+            .hydrate_action_result(
+                &result,
+                dest.path(),
+                &[WorkspaceRelativePathBuf::from("*.bin")],
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dest.path().join("large.bin")).unwrap(),
+            large
+        );
+        assert_eq!(
+            fs::read_to_string(dest.path().join("small.bin")).unwrap(),
+            "small"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn preserves_executable_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let server = StandInServer::default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+
+        let source = create_empty_sandbox();
+        source.create_file("bin/run", "#!/bin/sh");
+
+        fs::set_permissions(
+            source.path().join("bin/run"),
+            fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+
+        let (stdout, stderr) = log_paths(source.path());
+
+        cache
+            .write_action_result(
+                &create_hash_digest(),
+                source.path(),
+                &[WorkspaceRelativePathBuf::from("bin/run")],
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        let result = cache
+            .read_action_result(&create_hash_digest())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(result.output_files[0].is_executable);
+
+        let dest = create_empty_sandbox();
+        let (stdout, stderr) = log_paths(dest.path());
+
+        cache
+            .hydrate_action_result(
+                &result,
+                dest.path(),
+                &[WorkspaceRelativePathBuf::from("bin")],
+                (&stdout, &stderr),
+            )
+            .await
+            .unwrap();
+
+        let mode = fs::metadata(dest.path().join("bin/run"))
+            .unwrap()
+            .permissions()
+            .mode();
+
+        assert_eq!(mode & 0o111, 0o111);
+    }
+
+    fn create_result(path: &str, digest: Option<reapi_proto::Digest>) -> reapi_proto::ActionResult {
+        reapi_proto::ActionResult {
+            output_files: vec![reapi_proto::OutputFile {
+                path: path.into(),
+                digest,
+                is_executable: false,
+                contents: b"malicious".to_vec(),
+            }],
+            ..reapi_proto::ActionResult::default()
+        }
+    }
+
+    async fn hydrate_result(
+        result: reapi_proto::ActionResult,
+        server: Option<StandInServer>,
+    ) -> miette::Result<()> {
+        let server = server.unwrap_or_default();
+        let addr = server.start().await;
+        let cache = create_cache(format!("grpc://{addr}"));
+        let dest = create_empty_sandbox();
+        let (stdout, stderr) = log_paths(dest.path());
+
+        cache
+            .hydrate_action_result(
+                &result,
+                dest.path(),
+                &[
+                    WorkspaceRelativePathBuf::from("app/dist"),
+                    WorkspaceRelativePathBuf::from("app/*.d.ts"),
+                ],
+                (&stdout, &stderr),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "refusing to hydrate it")]
+    async fn errors_for_traversing_paths() {
+        hydrate_result(create_result("app/dist/../../../escape.txt", None), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "refusing to hydrate it")]
+    async fn errors_for_absolute_paths() {
+        hydrate_result(create_result("/tmp/escape.txt", None), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "refusing to hydrate it")]
+    async fn errors_for_undeclared_outputs() {
+        hydrate_result(create_result("app/source.ts", None), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn allows_declared_outputs() {
+        hydrate_result(create_result("app/dist/nested/index.js", None), None)
+            .await
+            .unwrap();
+        hydrate_result(create_result("app/index.d.ts", None), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "does not match its digest")]
+    async fn errors_if_batched_blob_doesnt_match_digest() {
+        let server = StandInServer::default();
+        let digest = create_digest(b"expected");
+
+        server
+            .blobs
+            .lock()
+            .unwrap()
+            .insert(digest.hash.clone(), b"tampered".to_vec());
+
+        hydrate_result(
+            create_result("app/dist/index.js", Some(digest)),
+            Some(server),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "does not match its digest")]
+    async fn errors_if_streamed_blob_doesnt_match_digest() {
+        let server = StandInServer::default();
+        let digest = create_digest("x".repeat(3 * 1024 * 1024).as_bytes());
+
+        server.blobs.lock().unwrap().insert(
+            digest.hash.clone(),
+            "y".repeat(3 * 1024 * 1024).into_bytes(),
+        );
+
+        hydrate_result(
+            create_result("app/dist/large.bin", Some(digest)),
+            Some(server),
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+use httpmock::prelude::*;
+use httpmock::Method::HEAD;
+use moon_config::{RemoteCacheConfig, RemoteCacheHttpConfig, RemoteCacheS3Config};
+use moon_remote_cache::*;
+use starbase_sandbox::create_empty_sandbox;
+use std::fs;
+
+mod directory {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Remote cache directory")]
+    fn errors_if_dir_missing() {
+        let sandbox = create_empty_sandbox();
+
+        DirectoryRemoteCache::new(sandbox.path().join("shared")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn checks_existence() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("shared/abc.tar.gz", "");
+
+        let cache = DirectoryRemoteCache::new(sandbox.path().join("shared")).unwrap();
+
+        assert!(cache.exists("abc.tar.gz").await.unwrap());
+        assert!(!cache.exists("def.tar.gz").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn uploads_and_downloads() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("shared/.gitkeep", "");
+        sandbox.create_file("local/abc.tar.gz", "archive");
+
+        let cache = DirectoryRemoteCache::new(sandbox.path().join("shared")).unwrap();
+
+        cache
+            .upload("abc.tar.gz", &sandbox.path().join("local/abc.tar.gz"))
+            .await
+            .unwrap();
+
+        assert!(sandbox.path().join("shared/abc.tar.gz").exists());
+
+        cache
+            .download("abc.tar.gz", &sandbox.path().join("other/abc.tar.gz"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("other/abc.tar.gz")).unwrap(),
+            "archive"
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_overwrite_existing_entries() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("shared/abc.tar.gz", "original");
+        sandbox.create_file("local/abc.tar.gz", "changed");
+
+        let cache = DirectoryRemoteCache::new(sandbox.path().join("shared")).unwrap();
+
+        cache
+            .upload("abc.tar.gz", &sandbox.path().join("local/abc.tar.gz"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("shared/abc.tar.gz")).unwrap(),
+            "original"
+        );
+    }
+}
+
+mod http {
+    use super::*;
+
+    fn create_cache(server: &MockServer) -> HttpRemoteCache {
+        HttpRemoteCache::new(&RemoteCacheHttpConfig {
+            headers: Default::default(),
+            token_env_var: None,
+            url: server.url("/cache/"),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn checks_existence() {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(HEAD).path("/cache/abc.tar.gz");
+            then.status(200);
+        });
+
+        server.mock(|when, then| {
+            when.method(HEAD).path("/cache/def.tar.gz");
+            then.status(404);
+        });
+
+        let cache = create_cache(&server);
+
+        assert!(cache.exists("abc.tar.gz").await.unwrap());
+        assert!(!cache.exists("def.tar.gz").await.unwrap());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Failed to check for")]
+    async fn errors_on_server_failure() {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(HEAD).path("/cache/abc.tar.gz");
+            then.status(500);
+        });
+
+        create_cache(&server).exists("abc.tar.gz").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn downloads_entry() {
+        let sandbox = create_empty_sandbox();
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(GET).path("/cache/abc.tar.gz");
+            then.status(200).body("archive");
+        });
+
+        create_cache(&server)
+            .download("abc.tar.gz", &sandbox.path().join("abc.tar.gz"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("abc.tar.gz")).unwrap(),
+            "archive"
+        );
+    }
+
+    #[tokio::test]
+    async fn uploads_entry() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("abc.tar.gz", "archive");
+
+        let server = MockServer::start();
+
+        let mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/cache/abc.tar.gz")
+                .header("Authorization", "Bearer secret")
+                .body("archive");
+            then.status(201);
+        });
+
+        std::env::set_var("MOON_TEST_REMOTE_CACHE_TOKEN", "secret");
+
+        let cache = HttpRemoteCache::new(&RemoteCacheHttpConfig {
+            headers: Default::default(),
+            token_env_var: Some("MOON_TEST_REMOTE_CACHE_TOKEN".into()),
+            url: server.url("/cache"),
+        })
+        .unwrap();
+
+        cache
+            .upload("abc.tar.gz", &sandbox.path().join("abc.tar.gz"))
+            .await
+            .unwrap();
+
+        std::env::remove_var("MOON_TEST_REMOTE_CACHE_TOKEN");
+
+        mock.assert();
+    }
+}
+
+mod s3 {
+    use super::*;
+
+    fn create_config(server: &MockServer) -> RemoteCacheS3Config {
+        std::env::set_var("MOON_TEST_S3_ACCESS_KEY", "access");
+        std::env::set_var("MOON_TEST_S3_SECRET_KEY", "secret");
+
+        RemoteCacheS3Config {
+            access_key_id_env_var: "MOON_TEST_S3_ACCESS_KEY".into(),
+            bucket: "moon".into(),
+            endpoint: Some(server.base_url()),
+            prefix: "/cache/".into(),
+            region: "us-east-1".into(),
+            secret_access_key_env_var: "MOON_TEST_S3_SECRET_KEY".into(),
+            session_token_env_var: "MOON_TEST_S3_SESSION_TOKEN".into(),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing S3 credentials")]
+    fn errors_if_credentials_missing() {
+        let server = MockServer::start();
+
+        S3RemoteCache::new(&RemoteCacheS3Config {
+            access_key_id_env_var: "MOON_TEST_S3_MISSING".into(),
+            ..create_config(&server)
+        })
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn checks_existence() {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(HEAD)
+                .path("/moon/cache/abc.tar.gz")
+                .header_exists("x-amz-date")
+                .header("x-amz-content-sha256", "UNSIGNED-PAYLOAD");
+            then.status(200);
+        });
+
+        server.mock(|when, then| {
+            when.method(HEAD).path("/moon/cache/def.tar.gz");
+            then.status(404);
+        });
+
+        let cache = S3RemoteCache::new(&create_config(&server)).unwrap();
+
+        assert!(cache.exists("abc.tar.gz").await.unwrap());
+        assert!(!cache.exists("def.tar.gz").await.unwrap());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Failed to check for")]
+    async fn errors_on_server_failure() {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method(HEAD).path("/moon/cache/abc.tar.gz");
+            then.status(403);
+        });
+
+        S3RemoteCache::new(&create_config(&server))
+            .unwrap()
+            .exists("abc.tar.gz")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn uploads_and_downloads() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("abc.tar.gz", "archive");
+
+        let server = MockServer::start();
+
+        let upload = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/moon/cache/abc.tar.gz")
+                .header_exists("authorization")
+                .body("archive");
+            then.status(200);
+        });
+
+        server.mock(|when, then| {
+            when.method(GET).path("/moon/cache/abc.tar.gz");
+            then.status(200).body("archive");
+        });
+
+        let cache = S3RemoteCache::new(&create_config(&server)).unwrap();
+
+        cache
+            .upload("abc.tar.gz", &sandbox.path().join("abc.tar.gz"))
+            .await
+            .unwrap();
+
+        upload.assert();
+
+        cache
+            .download("abc.tar.gz", &sandbox.path().join("other/abc.tar.gz"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(sandbox.path().join("other/abc.tar.gz")).unwrap(),
+            "archive"
+        );
+    }
+}
+
+mod create {
+    use super::*;
+
+    #[test]
+    fn returns_none_if_not_configured() {
+        let sandbox = create_empty_sandbox();
+
+        assert!(
+            create_remote_cache(&RemoteCacheConfig::default(), sandbox.path())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn creates_directory_relative_to_workspace() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("shared/.gitkeep", "");
+
+        let cache = create_remote_cache(
+            &RemoteCacheConfig {
+                directory: Some("shared".into()),
+                ..RemoteCacheConfig::default()
+            },
+            sandbox.path(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(cache.get_name(), "directory");
+    }
+}
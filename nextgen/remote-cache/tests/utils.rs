@@ -0,0 +1,324 @@
+#![allow(dead_code)]
+
+use moon_remote_cache::reapi_proto::*;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_stream::StreamExt;
+use tonic::codec::{ProstCodec, Streaming};
+use tonic::codegen::{http, Body, BoxFuture, BoxStream, Context, Poll, Service, StdError};
+use tonic::server::{
+    ClientStreamingService, Grpc, NamedService, ServerStreamingService, UnaryService,
+};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+/// An in-memory stand-in for a REAPI server (like bazel-remote),
+/// that implements the action cache, CAS, and byte stream services.
+#[derive(Clone, Default)]
+pub struct StandInServer {
+    pub action_results: Arc<Mutex<HashMap<String, ActionResult>>>,
+    pub blobs: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    pub streamed_writes: Arc<Mutex<usize>>,
+}
+
+impl StandInServer {
+    pub async fn start(&self) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = self.clone();
+
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(ActionCacheService(server.clone()))
+                .add_service(CasService(server.clone()))
+                .add_service(ByteStreamService(server))
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+                .unwrap();
+        });
+
+        addr
+    }
+
+    fn get_action_result(&self, req: GetActionResultRequest) -> Result<ActionResult, Status> {
+        self.action_results
+            .lock()
+            .unwrap()
+            .get(&req.action_digest.unwrap().hash)
+            .cloned()
+            .ok_or_else(|| Status::not_found("missing action result"))
+    }
+
+    fn update_action_result(&self, req: UpdateActionResultRequest) -> Result<ActionResult, Status> {
+        let result = req.action_result.unwrap();
+
+        self.action_results
+            .lock()
+            .unwrap()
+            .insert(req.action_digest.unwrap().hash, result.clone());
+
+        Ok(result)
+    }
+
+    fn find_missing_blobs(
+        &self,
+        req: FindMissingBlobsRequest,
+    ) -> Result<FindMissingBlobsResponse, Status> {
+        let blobs = self.blobs.lock().unwrap();
+
+        Ok(FindMissingBlobsResponse {
+            missing_blob_digests: req
+                .blob_digests
+                .into_iter()
+                .filter(|digest| !blobs.contains_key(&digest.hash))
+                .collect(),
+        })
+    }
+
+    fn batch_update_blobs(
+        &self,
+        req: BatchUpdateBlobsRequest,
+    ) -> Result<BatchUpdateBlobsResponse, Status> {
+        let mut blobs = self.blobs.lock().unwrap();
+        let mut responses = vec![];
+
+        for entry in req.requests {
+            let digest = entry.digest.unwrap();
+
+            blobs.insert(digest.hash.clone(), entry.data);
+            responses.push(BatchUpdateBlobsResponseEntry {
+                digest: Some(digest),
+                status: Some(Status_::ok()),
+            });
+        }
+
+        Ok(BatchUpdateBlobsResponse { responses })
+    }
+
+    fn batch_read_blobs(
+        &self,
+        req: BatchReadBlobsRequest,
+    ) -> Result<BatchReadBlobsResponse, Status> {
+        let blobs = self.blobs.lock().unwrap();
+
+        Ok(BatchReadBlobsResponse {
+            // Reverse to ensure the client doesn't rely on ordering
+            responses: req
+                .digests
+                .into_iter()
+                .rev()
+                .map(|digest| BatchReadBlobsResponseEntry {
+                    data: blobs.get(&digest.hash).cloned().unwrap_or_default(),
+                    status: Some(if blobs.contains_key(&digest.hash) {
+                        Status_::ok()
+                    } else {
+                        Status_::not_found()
+                    }),
+                    digest: Some(digest),
+                })
+                .collect(),
+        })
+    }
+
+    fn read(&self, req: ReadRequest) -> Result<Vec<ReadResponse>, Status> {
+        // <instance>/blobs/<hash>/<size>
+        let parts = req.resource_name.split('/').collect::<Vec<_>>();
+        let hash = parts[parts.len() - 2];
+        let data = self
+            .blobs
+            .lock()
+            .unwrap()
+            .get(hash)
+            .cloned()
+            .ok_or_else(|| Status::not_found("missing blob"))?;
+
+        Ok(data
+            .chunks(1024 * 512)
+            .map(|chunk| ReadResponse {
+                data: chunk.to_vec(),
+            })
+            .collect())
+    }
+
+    async fn write(&self, mut stream: Streaming<WriteRequest>) -> Result<WriteResponse, Status> {
+        let mut resource_name = String::new();
+        let mut data = vec![];
+
+        while let Some(req) = stream.message().await? {
+            if !req.resource_name.is_empty() {
+                resource_name = req.resource_name;
+            }
+
+            data.extend(req.data);
+        }
+
+        // <instance>/uploads/<uuid>/blobs/<hash>/<size>
+        let parts = resource_name.split('/').collect::<Vec<_>>();
+        let hash = parts[parts.len() - 2].to_owned();
+        let committed_size = data.len() as i64;
+
+        self.blobs.lock().unwrap().insert(hash, data);
+        *self.streamed_writes.lock().unwrap() += 1;
+
+        Ok(WriteResponse { committed_size })
+    }
+}
+
+struct Status_;
+
+impl Status_ {
+    fn ok() -> moon_remote_cache::reapi_proto::Status {
+        moon_remote_cache::reapi_proto::Status {
+            code: 0,
+            message: String::new(),
+        }
+    }
+
+    fn not_found() -> moon_remote_cache::reapi_proto::Status {
+        moon_remote_cache::reapi_proto::Status {
+            code: tonic::Code::NotFound as i32,
+            message: "missing blob".into(),
+        }
+    }
+}
+
+struct Unary<F>(F);
+
+impl<F, Req, Res> UnaryService<Req> for Unary<F>
+where
+    F: Fn(Req) -> Result<Res, Status>,
+    Res: Send + 'static,
+{
+    type Response = Res;
+    type Future = BoxFuture<Response<Res>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        let result = (self.0)(request.into_inner()).map(Response::new);
+
+        Box::pin(async move { result })
+    }
+}
+
+struct ReadStream(StandInServer);
+
+impl ServerStreamingService<ReadRequest> for ReadStream {
+    type Response = ReadResponse;
+    type ResponseStream = BoxStream<ReadResponse>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<ReadRequest>) -> Self::Future {
+        let result = self.0.read(request.into_inner());
+
+        Box::pin(async move {
+            let stream: Self::ResponseStream = Box::pin(tokio_stream::iter(result?).map(Ok));
+
+            Ok(Response::new(stream))
+        })
+    }
+}
+
+struct WriteStream(StandInServer);
+
+impl ClientStreamingService<WriteRequest> for WriteStream {
+    type Response = WriteResponse;
+    type Future = BoxFuture<Response<WriteResponse>, Status>;
+
+    fn call(&mut self, request: Request<Streaming<WriteRequest>>) -> Self::Future {
+        let server = self.0.clone();
+
+        Box::pin(async move { server.write(request.into_inner()).await.map(Response::new) })
+    }
+}
+
+macro_rules! unary {
+    ($server:expr, $req:expr, $method:ident) => {{
+        let server = $server.clone();
+
+        Box::pin(async move {
+            Ok(Grpc::new(ProstCodec::default())
+                .unary(Unary(move |req| server.$method(req)), $req)
+                .await)
+        })
+    }};
+}
+
+// Unknown methods must respond with a gRPC status instead of panicking
+fn unimplemented_path(path: &str) -> BoxFuture<http::Response<tonic::body::BoxBody>, Infallible> {
+    let status = Status::unimplemented(format!("{path} is not implemented"));
+
+    Box::pin(async move { Ok(status.to_http()) })
+}
+
+macro_rules! service {
+    ($name:ident, $service:literal, |$server:ident, $req:ident| $handler:expr) => {
+        #[derive(Clone)]
+        struct $name(StandInServer);
+
+        impl NamedService for $name {
+            const NAME: &'static str = $service;
+        }
+
+        impl<B> Service<http::Request<B>> for $name
+        where
+            B: Body + Send + 'static,
+            B::Error: Into<StdError> + Send + 'static,
+        {
+            type Response = http::Response<tonic::body::BoxBody>;
+            type Error = Infallible;
+            type Future = BoxFuture<Self::Response, Self::Error>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, $req: http::Request<B>) -> Self::Future {
+                let $server = self.0.clone();
+
+                $handler
+            }
+        }
+    };
+}
+
+service!(
+    ActionCacheService,
+    "build.bazel.remote.execution.v2.ActionCache",
+    |server, req| match req.uri().path() {
+        GET_ACTION_RESULT_PATH => unary!(server, req, get_action_result),
+        UPDATE_ACTION_RESULT_PATH => unary!(server, req, update_action_result),
+        path => unimplemented_path(path),
+    }
+);
+
+service!(
+    CasService,
+    "build.bazel.remote.execution.v2.ContentAddressableStorage",
+    |server, req| match req.uri().path() {
+        FIND_MISSING_BLOBS_PATH => unary!(server, req, find_missing_blobs),
+        BATCH_UPDATE_BLOBS_PATH => unary!(server, req, batch_update_blobs),
+        BATCH_READ_BLOBS_PATH => unary!(server, req, batch_read_blobs),
+        path => unimplemented_path(path),
+    }
+);
+
+service!(
+    ByteStreamService,
+    "google.bytestream.ByteStream",
+    |server, req| match req.uri().path() {
+        BYTESTREAM_READ_PATH => Box::pin(async move {
+            Ok(Grpc::new(ProstCodec::default())
+                .server_streaming(ReadStream(server), req)
+                .await)
+        }),
+        BYTESTREAM_WRITE_PATH => Box::pin(async move {
+            Ok(Grpc::new(ProstCodec::default())
+                .client_streaming(WriteStream(server), req)
+                .await)
+        }),
+        path => unimplemented_path(path),
+    }
+);
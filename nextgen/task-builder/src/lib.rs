@@ -1,3 +1,5 @@
 mod tasks_builder;
+mod tasks_builder_error;
 
 pub use tasks_builder::*;
+pub use tasks_builder_error::*;
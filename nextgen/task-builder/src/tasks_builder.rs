@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use crate::tasks_builder_error::TasksBuilderError;
 use moon_args::split_args;
 use moon_common::{color, Id};
 use moon_config::{
@@ -7,6 +8,7 @@ use moon_config::{
     ProjectWorkspaceInheritedTasksConfig, TaskCommandArgs, TaskConfig, TaskMergeStrategy,
     TaskOutputStyle, TaskType, ToolchainConfig,
 };
+use moon_query::build_query;
 use moon_target::Target;
 use moon_task::{Task, TaskOptions};
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -245,6 +247,12 @@ impl<'proj> TasksBuilder<'proj> {
         task.options = self.build_task_options(id, is_local)?;
         task.flags.local = is_local;
 
+        // Ready checks are polled while the process is running, so only
+        // persistent tasks, which never exit, can be waited on
+        if task.options.ready_check.is_some() && !task.options.persistent {
+            return Err(TasksBuilderError::ReadyCheckPersistentRequirement { target }.into());
+        }
+
         // Aggregate all values that are inherited from the global task configs,
         // and should always be included in the task, regardless of merge strategy.
         let global_deps = self.build_global_deps(&target)?;
@@ -267,6 +275,22 @@ impl<'proj> TasksBuilder<'proj> {
         for link in &chain {
             let config = link.config;
 
+            if let Some(condition) = &config.condition {
+                // Queries are otherwise only parsed when the action graph is built
+                if let Some(query) = &condition.query {
+                    if let Err(error) = build_query(query) {
+                        return Err(TasksBuilderError::InvalidConditionQuery {
+                            target,
+                            query: query.to_owned(),
+                            error: error.to_string(),
+                        }
+                        .into());
+                    }
+                }
+
+                task.condition = Some(condition.to_owned());
+            }
+
             if !config.deps.is_empty() {
                 task.deps = self.merge_vec(
                     task.deps,
@@ -421,6 +445,10 @@ impl<'proj> TasksBuilder<'proj> {
                 options.env_file = env_file.to_input_path();
             }
 
+            if let Some(finally) = &config.finally {
+                options.finally = finally.to_owned();
+            }
+
             if let Some(interactive) = &config.interactive {
                 options.interactive = *interactive;
             }
@@ -453,6 +481,14 @@ impl<'proj> TasksBuilder<'proj> {
                 options.persistent = *persistent;
             }
 
+            if let Some(ready_check) = &config.ready_check {
+                options.ready_check = Some(ready_check.to_owned());
+            }
+
+            if let Some(resources) = &config.resources {
+                options.resources = resources.to_owned();
+            }
+
             if let Some(retry_count) = &config.retry_count {
                 options.retry_count = *retry_count;
             }
@@ -469,9 +505,21 @@ impl<'proj> TasksBuilder<'proj> {
                 options.run_from_workspace_root = *run_from_workspace_root;
             }
 
+            if let Some(sandbox) = &config.sandbox {
+                options.sandbox = *sandbox;
+            }
+
+            if let Some(secret_env_vars) = &config.secret_env_vars {
+                options.secret_env_vars = secret_env_vars.to_owned();
+            }
+
             if let Some(shell) = &config.shell {
                 options.shell = *shell;
             }
+
+            if let Some(timeout) = &config.timeout {
+                options.timeout = Some(*timeout);
+            }
         }
 
         if options.interactive {
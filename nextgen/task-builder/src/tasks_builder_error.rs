@@ -0,0 +1,30 @@
+use miette::Diagnostic;
+use moon_common::{Style, Stylize};
+use moon_target::Target;
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum TasksBuilderError {
+    #[diagnostic(code(task_builder::condition::invalid_query))]
+    #[error(
+        "Task {} has configured an invalid {} {}: {error}",
+        .target.id.style(Style::Label),
+        "condition.query".style(Style::Symbol),
+        .query.style(Style::File),
+    )]
+    InvalidConditionQuery {
+        target: Target,
+        query: String,
+        error: String,
+    },
+
+    #[diagnostic(code(task_builder::ready_check::persistent_requirement))]
+    #[error(
+        "Task {} has configured {}, but is not persistent. Ready checks are only supported for long-running processes.\nA task is marked persistent with the {} or {} settings.",
+        .target.id.style(Style::Label),
+        "options.readyCheck".style(Style::Symbol),
+        "local".style(Style::Symbol),
+        "options.persistent".style(Style::Symbol),
+    )]
+    ReadyCheckPersistentRequirement { target: Target },
+}
@@ -2,8 +2,8 @@ use moon_common::Id;
 use moon_config::{
     DenoConfig, InheritedTasksManager, InputPath, NodeConfig, OutputPath, PlatformType,
     ProjectConfig, ProjectWorkspaceConfig, ProjectWorkspaceInheritedTasksConfig, RustConfig,
-    TaskCommandArgs, TaskConfig, TaskOptionAffectedFiles, TaskOutputStyle, TaskType,
-    ToolchainConfig,
+    TaskCommandArgs, TaskConditionConfig, TaskConfig, TaskOptionAffectedFiles, TaskOutputStyle,
+    TaskType, ToolchainConfig,
 };
 use moon_platform_detector::detect_task_platform;
 use moon_target::Target;
@@ -565,6 +565,57 @@ mod tasks_builder {
         }
     }
 
+    mod ready_check {
+        use super::*;
+
+        #[tokio::test]
+        async fn supports_persistent_tasks() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "ready-check/moon.yml").await;
+
+            let task = tasks.get("persistent").unwrap();
+
+            assert_eq!(task.options.ready_check.as_ref().unwrap().port, Some(3000));
+
+            let task = tasks.get("local").unwrap();
+
+            assert_eq!(task.options.ready_check.as_ref().unwrap().port, Some(3000));
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "but is not persistent")]
+        async fn errors_for_non_persistent_tasks() {
+            let sandbox = create_sandbox("builder");
+
+            build_tasks(sandbox.path(), "ready-check-invalid/moon.yml").await;
+        }
+    }
+
+    mod condition {
+        use super::*;
+
+        #[tokio::test]
+        async fn supports_valid_queries() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "condition/moon.yml").await;
+
+            let task = tasks.get("deploy").unwrap();
+
+            assert_eq!(
+                task.condition.as_ref().unwrap().query,
+                Some("projectType=application".into())
+            );
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "has configured an invalid")]
+        async fn errors_for_invalid_queries() {
+            let sandbox = create_sandbox("builder");
+
+            build_tasks(sandbox.path(), "condition-invalid/moon.yml").await;
+        }
+    }
+
     mod local_mode {
         use super::*;
 
@@ -1247,6 +1298,9 @@ mod tasks_builder {
             assert!(task.options.run_in_ci);
             assert!(task.options.persistent);
             assert_eq!(task.options.retry_count, 3);
+            assert!(task.options.sandbox);
+            assert_eq!(task.options.secret_env_vars, vec!["*_TOKEN".to_owned()]);
+            assert_eq!(task.options.timeout, Some(60));
         }
 
         #[tokio::test]
@@ -1260,6 +1314,29 @@ mod tasks_builder {
             assert!(!task.options.persistent);
         }
 
+        #[tokio::test]
+        async fn handles_condition() {
+            let sandbox = create_sandbox("builder");
+            let tasks = build_tasks(sandbox.path(), "extends/moon.yml").await;
+
+            assert_eq!(
+                tasks.get("inherit-condition").unwrap().condition,
+                Some(TaskConditionConfig {
+                    os: vec!["linux".into()],
+                    ..TaskConditionConfig::default()
+                })
+            );
+
+            // Replaced, not merged
+            assert_eq!(
+                tasks.get("extend-condition").unwrap().condition,
+                Some(TaskConditionConfig {
+                    env: vec!["CI".into()],
+                    ..TaskConditionConfig::default()
+                })
+            );
+        }
+
         #[tokio::test]
         async fn inherits_and_merges_globals_extend_chain() {
             let sandbox = create_sandbox("builder");
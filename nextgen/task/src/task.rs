@@ -4,7 +4,7 @@ use moon_common::{
     path::{ProjectRelativePathBuf, WorkspaceRelativePathBuf},
     Id,
 };
-use moon_config::{InputPath, OutputPath, PlatformType, TaskType};
+use moon_config::{InputPath, OutputPath, PlatformType, TaskConditionConfig, TaskType};
 use moon_target::Target;
 use rustc_hash::{FxHashMap, FxHashSet};
 use starbase_utils::glob;
@@ -32,6 +32,8 @@ cacheable!(
 
         pub command: String,
 
+        pub condition: Option<TaskConditionConfig>,
+
         pub deps: Vec<Target>,
 
         pub env: FxHashMap<String, String>,
@@ -102,6 +104,32 @@ impl Task {
         PathBuf::from(self.target.scope_id.as_ref().unwrap().as_str()).join(self.id.as_str())
     }
 
+    /// Return the names and values of environment variables that are marked as
+    /// secret, either by this task or the provided patterns, from the current
+    /// process and this task's `env` (which includes `envFile`).
+    pub fn get_secret_env(&self, patterns: &[String]) -> miette::Result<FxHashMap<String, String>> {
+        let mut secrets = FxHashMap::default();
+
+        if patterns.is_empty() && self.options.secret_env_vars.is_empty() {
+            return Ok(secrets);
+        }
+
+        let patterns = patterns
+            .iter()
+            .chain(self.options.secret_env_vars.iter())
+            .map(|pattern| pattern.as_str())
+            .collect::<Vec<_>>();
+        let globset = glob::GlobSet::new(&patterns)?;
+
+        for (key, value) in env::vars().chain(self.env.clone()) {
+            if !value.is_empty() && globset.matches(&key) {
+                secrets.insert(key, value);
+            }
+        }
+
+        Ok(secrets)
+    }
+
     /// Return true if this task is affected based on touched files.
     /// Will attempt to find any file that matches our list of inputs.
     pub fn is_affected(
@@ -1,5 +1,9 @@
 use moon_common::cacheable;
-use moon_config::{InputPath, TaskMergeStrategy, TaskOptionAffectedFiles, TaskOutputStyle};
+use moon_config::{
+    InputPath, TaskMergeStrategy, TaskOptionAffectedFiles, TaskOptionResource, TaskOutputStyle,
+    TaskReadyCheckConfig,
+};
+use moon_target::Target;
 
 cacheable!(
     #[derive(Clone, Debug, Eq, PartialEq)]
@@ -12,6 +16,8 @@ cacheable!(
 
         pub env_file: Option<InputPath>,
 
+        pub finally: Vec<Target>,
+
         pub interactive: bool,
 
         pub merge_args: TaskMergeStrategy,
@@ -28,6 +34,10 @@ cacheable!(
 
         pub persistent: bool,
 
+        pub ready_check: Option<TaskReadyCheckConfig>,
+
+        pub resources: Vec<TaskOptionResource>,
+
         pub retry_count: u8,
 
         pub run_deps_in_parallel: bool,
@@ -37,7 +47,13 @@ cacheable!(
 
         pub run_from_workspace_root: bool,
 
+        pub sandbox: bool,
+
+        pub secret_env_vars: Vec<String>,
+
         pub shell: bool,
+
+        pub timeout: Option<u64>,
     }
 );
 
@@ -48,6 +64,7 @@ impl Default for TaskOptions {
             allow_failure: false,
             cache: true,
             env_file: None,
+            finally: vec![],
             interactive: false,
             merge_args: TaskMergeStrategy::Append,
             merge_deps: TaskMergeStrategy::Append,
@@ -56,11 +73,16 @@ impl Default for TaskOptions {
             merge_outputs: TaskMergeStrategy::Append,
             output_style: None,
             persistent: false,
+            ready_check: None,
+            resources: vec![],
             retry_count: 0,
             run_deps_in_parallel: true,
             run_in_ci: true,
             run_from_workspace_root: false,
+            sandbox: false,
+            secret_env_vars: vec![],
             shell: true,
+            timeout: None,
         }
     }
 }
@@ -210,6 +210,24 @@ impl Git {
         Ok(None)
     }
 
+    async fn get_file_tree_args<'a>(&self, dir: &'a str) -> miette::Result<Vec<&'a str>> {
+        let mut args = vec![
+            "ls-files",
+            "--full-name",
+            "--cached",
+            "--modified",
+            "--others", // Includes untracked
+            "--exclude-standard",
+            dir,
+        ];
+
+        if self.is_version_supported(">=2.31.0").await? {
+            args.push("--deduplicate");
+        }
+
+        Ok(args)
+    }
+
     #[allow(clippy::needless_lifetimes)]
     pub async fn get_remote_default_branch<'l>(&'l self) -> miette::Result<&'l str> {
         let extract_branch = |result: &'l str| -> Option<&'l str> {
@@ -350,24 +368,25 @@ impl Vcs for Git {
     }
 
     async fn get_file_tree(&self, dir: &str) -> miette::Result<Vec<WorkspaceRelativePathBuf>> {
-        let mut args = vec![
-            "ls-files",
-            "--full-name",
-            "--cached",
-            "--modified",
-            "--others", // Includes untracked
-            "--exclude-standard",
-            dir,
-        ];
+        let args = self.get_file_tree_args(dir).await?;
+        let output = self.process.run(args, true).await?;
 
-        if self.is_version_supported(">=2.31.0").await? {
-            args.push("--deduplicate");
-        }
+        Ok(output
+            .split('\n')
+            .map(WorkspaceRelativePathBuf::from)
+            .collect::<Vec<_>>())
+    }
 
-        let output = self.process.run(args, true).await?;
+    async fn get_file_tree_uncached(
+        &self,
+        dir: &str,
+    ) -> miette::Result<Vec<WorkspaceRelativePathBuf>> {
+        let args = self.get_file_tree_args(dir).await?;
+        let output = self.process.run_uncached(args, true).await?;
 
         Ok(output
             .split('\n')
+            .filter(|file| !file.is_empty())
             .map(WorkspaceRelativePathBuf::from)
             .collect::<Vec<_>>())
     }
@@ -45,6 +45,27 @@ impl ProcessCache {
         self.run_command(self.create_command(args), trim).await
     }
 
+    /// Run the command without reading from or writing to the cache,
+    /// for output that may change during a session.
+    pub async fn run_uncached<I, A>(&self, args: I, trim: bool) -> miette::Result<String>
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<OsStr>,
+    {
+        let output = self
+            .create_command(args)
+            .create_async()
+            .exec_capture_output()
+            .await?;
+        let output = output_to_string(&output.stdout);
+
+        Ok(if trim {
+            output.trim().to_owned()
+        } else {
+            output
+        })
+    }
+
     pub async fn run_with_formatter<I, A>(
         &self,
         args: I,
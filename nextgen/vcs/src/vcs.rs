@@ -33,6 +33,13 @@ pub trait Vcs: Debug {
     /// Directory *must* be relative from the workspace root.
     async fn get_file_tree(&self, dir: &str) -> miette::Result<Vec<WorkspaceRelativePathBuf>>;
 
+    /// Like [`Vcs::get_file_tree`], but always queries the VCS instead of using a cached
+    /// result, for when files may have been created since the tree was last loaded.
+    async fn get_file_tree_uncached(
+        &self,
+        dir: &str,
+    ) -> miette::Result<Vec<WorkspaceRelativePathBuf>>;
+
     /// Return an absolute path to the hooks directory, when applicable.
     async fn get_hooks_dir(&self) -> miette::Result<PathBuf>;
 
@@ -424,6 +424,27 @@ mod file_tree {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn uncached_includes_files_created_after_first_call() {
+        let (sandbox, git) = create_git_sandbox_with_ignored("vcs");
+
+        git.get_file_tree("baz").await.unwrap();
+
+        sandbox.create_file("baz/extra.txt", "");
+
+        assert!(!git
+            .get_file_tree("baz")
+            .await
+            .unwrap()
+            .contains(&WorkspaceRelativePathBuf::from("baz/extra.txt")));
+
+        assert!(git
+            .get_file_tree_uncached("baz")
+            .await
+            .unwrap()
+            .contains(&WorkspaceRelativePathBuf::from("baz/extra.txt")));
+    }
 }
 
 mod touched_files {
@@ -12,6 +12,7 @@ const action: Action = {
 	flaky: false,
 	label: 'RunTarget(app:build)',
 	nodeIndex: 8,
+	skippedReason: null,
 	status: 'passed',
 	finishedAt: '2022-09-12T22:50:12.932311Z',
 	startedAt: '2022-09-12T22:50:12.932311Z',
@@ -15,6 +15,7 @@ function mockReport(): RunReport {
 				flaky: false,
 				label: 'RunTarget(types:build)',
 				nodeIndex: 5,
+				skippedReason: null,
 				status: 'cached',
 				finishedAt: '2022-09-12T22:50:12.932311Z',
 				startedAt: '2022-09-12T22:50:12.932311Z',
@@ -30,6 +31,7 @@ function mockReport(): RunReport {
 				flaky: true,
 				label: 'RunTarget(runtime:typecheck)',
 				nodeIndex: 4,
+				skippedReason: null,
 				status: 'passed',
 				finishedAt: '2022-09-12T22:50:12.932311Z',
 				startedAt: '2022-09-12T22:50:12.932311Z',
@@ -45,6 +47,7 @@ function mockReport(): RunReport {
 				flaky: false,
 				label: 'RunTarget(types:typecheck)',
 				nodeIndex: 6,
+				skippedReason: null,
 				status: 'passed',
 				finishedAt: '2022-09-12T22:50:12.932311Z',
 				startedAt: '2022-09-12T22:50:12.932311Z',
@@ -60,6 +63,7 @@ function mockReport(): RunReport {
 				flaky: false,
 				label: 'RunTarget(website:typecheck)',
 				nodeIndex: 8,
+				skippedReason: null,
 				status: 'passed',
 				finishedAt: '2022-09-12T22:50:12.932311Z',
 				startedAt: '2022-09-12T22:50:12.932311Z',
@@ -67,6 +71,7 @@ function mockReport(): RunReport {
 		],
 		context: {
 			affectedOnly: false,
+			explainMiss: false,
 			initialTargets: [],
 			interactive: false,
 			passthroughArgs: [],
@@ -80,6 +85,8 @@ function mockReport(): RunReport {
 			secs: 0,
 			nanos: 371_006_844,
 		},
+		failed: [],
+		skipped: {},
 		comparisonEstimate: {
 			duration: {
 				secs: 1,
@@ -37,6 +37,7 @@ export type EventType =
 	| 'target-output.hydrated'
 	| 'target-output.hydrating'
 	| 'target.ran'
+	| 'target.ready'
 	| 'target.running'
 	| 'tool.installed'
 	| 'tool.installing'
@@ -132,6 +133,12 @@ export interface EventTargetRan {
 
 export type PayloadTargetRan = WebhookPayload<'target.ran', EventTargetRan>;
 
+export interface EventTargetReady {
+	target: string;
+}
+
+export type PayloadTargetReady = WebhookPayload<'target.ready', EventTargetReady>;
+
 export interface EventTargetOutputArchiving {
 	hash: string;
 	project: Project;
@@ -170,7 +177,7 @@ export type PayloadTargetOutputHydrating = WebhookPayload<
 >;
 
 export interface EventTargetOutputHydrated {
-	archivePath: string;
+	archivePath: string | null;
 	hash: string;
 	project: Project;
 	target: string;
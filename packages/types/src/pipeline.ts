@@ -27,6 +27,7 @@ export interface Action {
 	flaky: boolean;
 	label: string | null;
 	nodeIndex: number;
+	skippedReason: string | null;
 	startedAt: string | null;
 	status: ActionStatus;
 }
@@ -38,6 +39,7 @@ export interface TargetState {
 
 export interface ActionContext {
 	affectedOnly: boolean;
+	explainMiss: boolean;
 	initialTargets: string[];
 	interactive: boolean;
 	passthroughArgs: string[];
@@ -52,6 +54,8 @@ export interface RunReport {
 	actions: Action[];
 	context: ActionContext;
 	duration: Duration;
+	failed: string[];
+	skipped: Record<string, string>;
 	comparisonEstimate: {
 		duration: Duration;
 		gain: Duration | null;
@@ -3,8 +3,10 @@ import type {
 	InheritedTasksConfig,
 	PartialInheritedTasksConfig,
 	PlatformType,
+	TaskConditionConfig,
 	TaskMergeStrategy,
 	TaskOutputStyle,
+	TaskReadyCheckConfig,
 	TaskType,
 } from './tasks-config';
 
@@ -18,6 +20,7 @@ export interface TaskOptions {
 	affectedFiles: boolean | 'args' | 'env';
 	cache: boolean;
 	envFile: string | null;
+	finally: string[];
 	mergeArgs: TaskMergeStrategy;
 	mergeDeps: TaskMergeStrategy;
 	mergeEnv: TaskMergeStrategy;
@@ -25,16 +28,22 @@ export interface TaskOptions {
 	mergeOutputs: TaskMergeStrategy;
 	outputStyle: TaskOutputStyle | null;
 	persistent: boolean;
+	readyCheck: TaskReadyCheckConfig | null;
+	resources: string[];
 	retryCount: number;
 	runDepsInParallel: boolean;
 	runInCI: boolean;
 	runFromWorkspaceRoot: boolean;
+	sandbox: boolean;
+	secretEnvVars: string[];
 	shell: boolean;
+	timeout: number | null;
 }
 
 export interface Task {
 	args: string[];
 	command: string;
+	condition: TaskConditionConfig | null;
 	deps: string[];
 	env: Record<string, string>;
 	id: string;
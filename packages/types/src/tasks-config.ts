@@ -12,11 +12,21 @@ export type TaskMergeStrategy = 'append' | 'prepend' | 'replace';
 
 export type TaskOutputStyle = 'buffer' | 'buffer-only-failure' | 'hash' | 'none' | 'stream';
 
+export interface PartialTaskReadyCheckConfig {
+	file?: string | null;
+	output?: string | null;
+	port?: number | null;
+	/** @default 60 */
+	timeout?: number | null;
+	url?: string | null;
+}
+
 export interface PartialTaskOptionsConfig {
 	affectedFiles?: TaskOptionAffectedFiles | null;
 	allowFailure?: boolean | null;
 	cache?: boolean | null;
 	envFile?: TaskOptionEnvFile | null;
+	finally?: string[] | null;
 	interactive?: boolean | null;
 	mergeArgs?: TaskMergeStrategy | null;
 	mergeDeps?: TaskMergeStrategy | null;
@@ -25,20 +35,34 @@ export interface PartialTaskOptionsConfig {
 	mergeOutputs?: TaskMergeStrategy | null;
 	outputStyle?: TaskOutputStyle | null;
 	persistent?: boolean | null;
+	readyCheck?: PartialTaskReadyCheckConfig | null;
+	resources?: string[] | null;
 	retryCount?: number | null;
 	runDepsInParallel?: boolean | null;
 	runFromWorkspaceRoot?: boolean | null;
 	runInCI?: boolean | null;
+	sandbox?: boolean | null;
+	secretEnvVars?: string[] | null;
 	shell?: boolean | null;
+	timeout?: number | null;
 }
 
 export type PlatformType = 'deno' | 'node' | 'rust' | 'system' | 'unknown';
 
 export type TaskType = 'build' | 'run' | 'test';
 
+export interface PartialTaskConditionConfig {
+	arch?: string[] | null;
+	env?: string[] | null;
+	files?: string[] | null;
+	os?: string[] | null;
+	query?: string | null;
+}
+
 export interface PartialTaskConfig {
 	args?: PartialTaskCommandArgs | null;
 	command?: PartialTaskCommandArgs | null;
+	condition?: PartialTaskConditionConfig | null;
 	deps?: string[] | null;
 	env?: Record<string, string> | null;
 	extends?: string | null;
@@ -62,11 +86,21 @@ export interface PartialInheritedTasksConfig {
 
 export type TaskCommandArgs = null | string | string[];
 
+export interface TaskReadyCheckConfig {
+	file: string | null;
+	output: string | null;
+	port: number | null;
+	/** @default 60 */
+	timeout: number;
+	url: string | null;
+}
+
 export interface TaskOptionsConfig {
 	affectedFiles: TaskOptionAffectedFiles | null;
 	allowFailure: boolean | null;
 	cache: boolean | null;
 	envFile: TaskOptionEnvFile | null;
+	finally: string[] | null;
 	interactive: boolean | null;
 	mergeArgs: TaskMergeStrategy | null;
 	mergeDeps: TaskMergeStrategy | null;
@@ -75,16 +109,30 @@ export interface TaskOptionsConfig {
 	mergeOutputs: TaskMergeStrategy | null;
 	outputStyle: TaskOutputStyle | null;
 	persistent: boolean | null;
+	readyCheck: TaskReadyCheckConfig | null;
+	resources: string[] | null;
 	retryCount: number | null;
 	runDepsInParallel: boolean | null;
 	runFromWorkspaceRoot: boolean | null;
 	runInCI: boolean | null;
+	sandbox: boolean | null;
+	secretEnvVars: string[] | null;
 	shell: boolean | null;
+	timeout: number | null;
+}
+
+export interface TaskConditionConfig {
+	arch: string[];
+	env: string[];
+	files: string[];
+	os: string[];
+	query: string | null;
 }
 
 export interface TaskConfig {
 	args: TaskCommandArgs;
 	command: TaskCommandArgs;
+	condition: TaskConditionConfig | null;
 	deps: string[];
 	env: Record<string, string>;
 	extends: string | null;
@@ -44,6 +44,14 @@ export interface PartialNotifierConfig {
 	webhookUrl?: string | null;
 }
 
+export interface PartialOpenTelemetryConfig {
+	endpoint?: string | null;
+	file?: string | null;
+	headers?: Record<string, string> | null;
+	/** @default 'moon' */
+	serviceName?: string | null;
+}
+
 export interface PartialWorkspaceProjectsConfig {
 	globs?: string[] | null;
 	sources?: Record<string, string> | null;
@@ -54,13 +62,57 @@ export type PartialWorkspaceProjects =
 	| string[]
 	| Record<string, string>;
 
+export interface PartialRemoteCacheHttpConfig {
+	headers?: Record<string, string> | null;
+	tokenEnvVar?: string | null;
+	url?: string | null;
+}
+
+export interface PartialRemoteCacheReapiConfig {
+	headers?: Record<string, string> | null;
+	host?: string | null;
+	instanceName?: string | null;
+	tokenEnvVar?: string | null;
+}
+
+export interface PartialRemoteCacheS3Config {
+	/** @default 'AWS_ACCESS_KEY_ID' */
+	accessKeyIdEnvVar?: string | null;
+	bucket?: string | null;
+	endpoint?: string | null;
+	prefix?: string | null;
+	/** @default 'us-east-1' */
+	region?: string | null;
+	/** @default 'AWS_SECRET_ACCESS_KEY' */
+	secretAccessKeyEnvVar?: string | null;
+	/** @default 'AWS_SESSION_TOKEN' */
+	sessionTokenEnvVar?: string | null;
+}
+
+export interface PartialRemoteCacheConfig {
+	directory?: string | null;
+	http?: PartialRemoteCacheHttpConfig | null;
+	reapi?: PartialRemoteCacheReapiConfig | null;
+	s3?: PartialRemoteCacheS3Config | null;
+}
+
+export type HydrationMode = 'archive' | 'link';
+
+export type UndeclaredOutputsMode = 'error' | 'off' | 'warn';
+
 export interface PartialRunnerConfig {
 	archivableTargets?: string[] | null;
+	/** @default true */
+	bail?: boolean | null;
 	/** @default '7 days' */
 	cacheLifetime?: string | null;
+	cacheMaxSize?: string | null;
+	hydrationMode?: HydrationMode | null;
 	/** @default true */
 	inheritColorsForPipedTasks?: boolean | null;
 	logRunningCommand?: boolean | null;
+	secretEnvVars?: string[] | null;
+	undeclaredOutputs?: UndeclaredOutputsMode | null;
 }
 
 export type VcsManager = 'git';
@@ -87,7 +139,9 @@ export interface PartialWorkspaceConfig {
 	generator?: PartialGeneratorConfig | null;
 	hasher?: PartialHasherConfig | null;
 	notifier?: PartialNotifierConfig | null;
+	openTelemetry?: PartialOpenTelemetryConfig | null;
 	projects?: PartialWorkspaceProjects | null;
+	remoteCache?: PartialRemoteCacheConfig | null;
 	runner?: PartialRunnerConfig | null;
 	/** @default true */
 	telemetry?: boolean | null;
@@ -131,6 +185,14 @@ export interface NotifierConfig {
 	webhookUrl: string | null;
 }
 
+export interface OpenTelemetryConfig {
+	endpoint: string | null;
+	file: string | null;
+	headers: Record<string, string>;
+	/** @default 'moon' */
+	serviceName: string;
+}
+
 export interface WorkspaceProjectsConfig {
 	globs: string[];
 	sources: Record<string, string>;
@@ -138,13 +200,53 @@ export interface WorkspaceProjectsConfig {
 
 export type WorkspaceProjects = WorkspaceProjectsConfig | string[] | Record<string, string>;
 
+export interface RemoteCacheHttpConfig {
+	headers: Record<string, string>;
+	tokenEnvVar: string | null;
+	url: string;
+}
+
+export interface RemoteCacheReapiConfig {
+	headers: Record<string, string>;
+	host: string;
+	instanceName: string;
+	tokenEnvVar: string | null;
+}
+
+export interface RemoteCacheS3Config {
+	/** @default 'AWS_ACCESS_KEY_ID' */
+	accessKeyIdEnvVar: string;
+	bucket: string;
+	endpoint: string | null;
+	prefix: string;
+	/** @default 'us-east-1' */
+	region: string;
+	/** @default 'AWS_SECRET_ACCESS_KEY' */
+	secretAccessKeyEnvVar: string;
+	/** @default 'AWS_SESSION_TOKEN' */
+	sessionTokenEnvVar: string;
+}
+
+export interface RemoteCacheConfig {
+	directory: string | null;
+	http: RemoteCacheHttpConfig | null;
+	reapi: RemoteCacheReapiConfig | null;
+	s3: RemoteCacheS3Config | null;
+}
+
 export interface RunnerConfig {
 	archivableTargets: string[];
+	/** @default true */
+	bail: boolean;
 	/** @default '7 days' */
 	cacheLifetime: string;
+	cacheMaxSize: string | null;
+	hydrationMode: HydrationMode;
 	/** @default true */
 	inheritColorsForPipedTasks: boolean;
 	logRunningCommand: boolean;
+	secretEnvVars: string[];
+	undeclaredOutputs: UndeclaredOutputsMode;
 }
 
 export interface VcsConfig {
@@ -167,7 +269,9 @@ export interface WorkspaceConfig {
 	generator: GeneratorConfig;
 	hasher: HasherConfig;
 	notifier: NotifierConfig;
+	openTelemetry: OpenTelemetryConfig;
 	projects: WorkspaceProjects;
+	remoteCache: RemoteCacheConfig;
 	runner: RunnerConfig;
 	/** @default true */
 	telemetry: boolean;
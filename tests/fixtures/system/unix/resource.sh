@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eo pipefail
+
+# mkdir is atomic, so this fails if another task holds the lock
+if ! mkdir .resource-lock 2>/dev/null; then
+	echo "resource is already held" >&2
+	exit 1
+fi
+
+sleep 1
+rmdir .resource-lock
+
+echo "released resource"
@@ -98,6 +98,24 @@ const sidebars = {
 			items: [
 				'commands/overview',
 				'commands/bin',
+				{
+					type: 'category',
+					label: 'cache',
+					items: [
+						'commands/cache/export',
+						'commands/cache/import',
+						'commands/cache/list',
+						'commands/cache/show',
+						'commands/cache/verify',
+					],
+					link: {
+						type: 'generated-index',
+						title: 'cache',
+						description: 'Operations for inspecting and managing the local cache.',
+						slug: '/commands/cache',
+						keywords: ['cli', 'commands', 'cache'],
+					},
+				},
 				'commands/ci',
 				'commands/check',
 				'commands/clean',